@@ -0,0 +1,230 @@
+mod tests;
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use crate::mythread::myreplay::splitmix64;
+use crate::mythread::mythreadattr::PriorityLevel;
+use crate::safe::{Mutex, Runtime};
+use crate::scheduler::fixed_priority::{rate_monotonic_priorities, PeriodicTask};
+use crate::scheduler::SchedulerType;
+
+// Cargas sintéticas para comparar los schedulers. El tiempo se mide en ticks: cada unidad de
+// trabajo de un hilo es un tick y termina con yield, así todos los schedulers se comparan con la
+// misma granularidad de preempción.
+
+// Vueltas de CPU que cuesta una unidad de trabajo
+const UNIT_SPIN: u32 = 2_000;
+
+pub const SCHEDULERS: [SchedulerType; 4] = [SchedulerType::RoundRobin, SchedulerType::Lottery, SchedulerType::RealTime, SchedulerType::FixedPriority];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Workload {
+    // Todo llega en el tick 0 y solo calcula
+    CpuBound,
+    // Como CpuBound, pero la mitad de los hilos trabaja dentro de un Mutex compartido
+    MixedBlocking,
+    // Llegan en ráfagas separadas por ratos sin trabajo
+    Bursty,
+    // PERIODIC_TASKS, con deadline al final de su periodo (U = 0.7)
+    PeriodicRealTime,
+}
+
+impl Workload {
+    pub const ALL: [Workload; 4] = [Workload::CpuBound, Workload::MixedBlocking, Workload::Bursty, Workload::PeriodicRealTime];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Workload::CpuBound => "cpu-bound",
+            Workload::MixedBlocking => "mixed-blocking",
+            Workload::Bursty => "bursty",
+            Workload::PeriodicRealTime => "periodic-rt",
+        }
+    }
+}
+
+// Tareas de PeriodicRealTime. Con prioridades rate monotonic pasan el RTA, así EDF (RealTime) y
+// FixedPriority se pueden comparar sobre el mismo conjunto.
+pub const PERIODIC_TASKS: [PeriodicTask; 4] = [
+    PeriodicTask { period: 20, wcet: 3, deadline: 20 },
+    PeriodicTask { period: 40, wcet: 8, deadline: 40 },
+    PeriodicTask { period: 50, wcet: 10, deadline: 50 },
+    PeriodicTask { period: 100, wcet: 15, deadline: 100 },
+];
+
+pub struct BenchConfig {
+    pub jobs: usize,
+    pub seed: u64,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self { jobs: 200, seed: 1 }
+    }
+}
+
+// Un trabajo de la carga: cuándo llega, cuántas unidades cuesta y su deadline absoluto (en ticks).
+#[derive(Debug, Clone, Copy)]
+struct Job {
+    arrival: usize,
+    work: usize,
+    priority: PriorityLevel,
+    // Tickets en Lottery; igual a priority salvo en PeriodicRealTime
+    tickets: PriorityLevel,
+    deadline: usize,
+    locked: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchRow {
+    pub workload: Workload,
+    pub scheduler: SchedulerType,
+    pub jobs: usize,
+    pub elapsed: Duration,
+    pub ticks: usize,
+    pub avg_response: f64,
+    pub p99_response: usize,
+    // Índice de Jain sobre trabajo / respuesta de cada hilo (1 = todos avanzan parejo)
+    pub fairness: f64,
+    pub deadline_misses: usize,
+}
+
+impl BenchRow {
+    // Trabajos terminados por segundo de reloj.
+    pub fn throughput(&self) -> f64 {
+        self.jobs as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+fn generate(workload: Workload, config: &BenchConfig) -> Vec<Job> {
+    let mut rng = config.seed;
+    let mut below = |n: u64| splitmix64(&mut rng) % n;
+    let mut jobs = Vec::with_capacity(config.jobs);
+    match workload {
+        Workload::CpuBound | Workload::MixedBlocking => {
+            for i in 0..config.jobs {
+                let work = 5 + below(46) as usize;
+                let locked = workload == Workload::MixedBlocking && i % 2 == 0;
+                // Deadline a mitad de lo que tardaría repartiendo el CPU parejo entre todos
+                let deadline = work * config.jobs / 2;
+                let priority = 1 + below(10) as u8;
+                jobs.push(Job { arrival: 0, work, priority, tickets: priority, deadline, locked });
+            }
+        }
+        Workload::Bursty => {
+            let burst = (config.jobs / 5).max(1);
+            for i in 0..config.jobs {
+                let arrival = (i / burst) * 400 + below(10) as usize;
+                let work = 2 + below(19) as usize;
+                let deadline = arrival + work * burst / 2;
+                let priority = 1 + below(10) as u8;
+                jobs.push(Job { arrival, work, priority, tickets: priority, deadline, locked: false });
+            }
+        }
+        Workload::PeriodicRealTime => {
+            // Prioridades rate monotonic; en Lottery, tickets proporcionales a la tasa (200 / periodo)
+            let priorities = rate_monotonic_priorities(&PERIODIC_TASKS).expect("menos de 256 periodos");
+            let mut release = 0;
+            while jobs.len() < config.jobs {
+                for (task, &priority) in PERIODIC_TASKS.iter().zip(&priorities) {
+                    if release % task.period == 0 && jobs.len() < config.jobs {
+                        let tickets = (200 / task.period).clamp(1, 255) as u8;
+                        jobs.push(Job { arrival: release, work: task.wcet, priority, tickets, deadline: release + task.deadline, locked: false });
+                    }
+                }
+                release += 10;
+            }
+        }
+    }
+    jobs.sort_by_key(|j| j.arrival);
+    jobs
+}
+
+fn burn() {
+    let mut x = 0u32;
+    for i in 0..UNIT_SPIN {
+        x = std::hint::black_box(x.wrapping_mul(31).wrapping_add(i));
+    }
+}
+
+pub fn run(workload: Workload, scheduler: SchedulerType, config: &BenchConfig) -> BenchRow {
+    let jobs = generate(workload, config);
+    let rt = Runtime::with_seed(config.seed);
+    let clock = Rc::new(Cell::new(0usize));
+    let lock = Rc::new(Mutex::new(&rt, ()));
+    let finished: Rc<RefCell<Vec<(usize, usize)>>> = Rc::new(RefCell::new(Vec::new()));
+
+    let start = Instant::now();
+    let mut next = 0;
+    while finished.borrow().len() < jobs.len() {
+        while next < jobs.len() && jobs[next].arrival <= clock.get() {
+            let job = jobs[next];
+            let (r, c, l, f) = (rt.clone(), clock.clone(), lock.clone(), finished.clone());
+            let index = next;
+            rt.builder()
+                .scheduler(scheduler)
+                .priority(if scheduler == SchedulerType::Lottery { job.tickets } else { job.priority })
+                .deadline(job.deadline)
+                .spawn(move || {
+                    for _ in 0..job.work {
+                        let guard = job.locked.then(|| l.lock());
+                        burn();
+                        c.set(c.get() + 1);
+                        r.yield_now();
+                        drop(guard);
+                    }
+                    f.borrow_mut().push((index, c.get()));
+                });
+            next += 1;
+        }
+        if finished.borrow().len() == next {
+            // Nadie listo: el reloj salta a la siguiente llegada
+            match jobs.get(next) {
+                Some(job) => clock.set(clock.get().max(job.arrival)),
+                None => break,
+            }
+            continue;
+        }
+        rt.yield_now();
+    }
+    let elapsed = start.elapsed();
+
+    let finished = finished.take();
+    let mut responses: Vec<usize> = finished.iter().map(|&(i, end)| end - jobs[i].arrival).collect();
+    responses.sort_unstable();
+    let n = responses.len().max(1) as f64;
+    let p99 = responses.get(((responses.len() * 99).div_ceil(100)).saturating_sub(1)).copied().unwrap_or(0);
+    let rates: Vec<f64> = finished.iter().map(|&(i, end)| jobs[i].work as f64 / (end - jobs[i].arrival).max(1) as f64).collect();
+    let (sum, sum_sq) = rates.iter().fold((0.0, 0.0), |(s, q), r| (s + r, q + r * r));
+
+    BenchRow {
+        workload,
+        scheduler,
+        jobs: finished.len(),
+        elapsed,
+        ticks: clock.get(),
+        avg_response: responses.iter().sum::<usize>() as f64 / n,
+        p99_response: p99,
+        fairness: if sum_sq > 0.0 { sum * sum / (n * sum_sq) } else { 1.0 },
+        deadline_misses: finished.iter().filter(|&&(i, end)| end > jobs[i].deadline).count(),
+    }
+}
+
+// Cada carga con cada scheduler.
+pub fn run_all(config: &BenchConfig) -> Vec<BenchRow> {
+    Workload::ALL
+        .iter()
+        .flat_map(|&w| SCHEDULERS.iter().map(move |&s| (w, s)))
+        .map(|(w, s)| run(w, s, config))
+        .collect()
+}
+
+pub fn print_csv(rows: &[BenchRow]) {
+    println!("workload,scheduler,jobs,ticks,throughput_jobs_s,avg_response_ticks,p99_response_ticks,fairness,deadline_misses");
+    for r in rows {
+        println!(
+            "{},{:?},{},{},{:.1},{:.2},{},{:.4},{}",
+            r.workload.name(), r.scheduler, r.jobs, r.ticks, r.throughput(), r.avg_response, r.p99_response, r.fairness, r.deadline_misses
+        );
+    }
+}
@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod tests {
+    use crate::bench::{run, run_all, BenchConfig, Workload, PERIODIC_TASKS, SCHEDULERS};
+    use crate::scheduler::fixed_priority::{rate_monotonic_priorities, response_time_analysis};
+    use crate::scheduler::SchedulerType;
+
+    #[test]
+    fn every_workload_runs_on_every_scheduler() {
+        let config = BenchConfig { jobs: 20, seed: 5 };
+        let rows = run_all(&config);
+        assert_eq!(rows.len(), Workload::ALL.len() * SCHEDULERS.len());
+        for row in &rows {
+            assert_eq!(row.jobs, 20, "{row:?}");
+            assert!(row.fairness > 0.0 && row.fairness <= 1.0 + 1e-9, "{row:?}");
+            assert!(row.avg_response <= row.p99_response as f64 + 1e-9, "{row:?}");
+        }
+    }
+
+    #[test]
+    fn edf_meets_periodic_deadlines_round_robin_does_not() {
+        let config = BenchConfig { jobs: 40, seed: 1 };
+        let edf = run(Workload::PeriodicRealTime, SchedulerType::RealTime, &config);
+        let rr = run(Workload::PeriodicRealTime, SchedulerType::RoundRobin, &config);
+        assert_eq!(edf.deadline_misses, 0, "{edf:?}");
+        assert!(rr.deadline_misses >= edf.deadline_misses, "{rr:?}");
+    }
+
+    #[test]
+    fn rate_monotonic_set_passes_rta_and_meets_deadlines() {
+        let priorities = rate_monotonic_priorities(&PERIODIC_TASKS).unwrap();
+        assert!(response_time_analysis(&PERIODIC_TASKS, &priorities).schedulable);
+
+        let config = BenchConfig { jobs: 40, seed: 1 };
+        let fp = run(Workload::PeriodicRealTime, SchedulerType::FixedPriority, &config);
+        assert_eq!(fp.deadline_misses, 0, "{fp:?}");
+    }
+
+    #[test]
+    fn cpu_bound_total_ticks_do_not_depend_on_scheduler() {
+        let config = BenchConfig { jobs: 30, seed: 9 };
+        let rr = run(Workload::CpuBound, SchedulerType::RoundRobin, &config);
+        let lottery = run(Workload::CpuBound, SchedulerType::Lottery, &config);
+        // Todos llegan juntos: la suma de ticks es la misma, solo cambia el orden
+        assert_eq!(rr.ticks, lottery.ticks);
+    }
+}
@@ -0,0 +1,12 @@
+use mypthreads::bench::{print_csv, run_all, BenchConfig};
+
+// sched_bench [trabajos] [semilla]: corre cada carga sintética con cada scheduler e imprime CSV
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let defaults = BenchConfig::default();
+    let config = BenchConfig {
+        jobs: args.get(1).and_then(|v| v.parse().ok()).unwrap_or(defaults.jobs),
+        seed: args.get(2).and_then(|v| v.parse().ok()).unwrap_or(defaults.seed),
+    };
+    print_csv(&run_all(&config));
+}
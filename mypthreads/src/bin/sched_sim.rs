@@ -0,0 +1,35 @@
+use mypthreads::scheduler::SchedulerType;
+use mypthreads::sim::{builtin_scheduler, load_task_set, print_report, simulate};
+
+// sched_sim <tareas.toml|csv> [scheduler|all] [quantum] [semilla]: simula el conjunto de tareas en
+// tiempo virtual e imprime las métricas por tarea y el diagrama de Gantt
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(path) = args.get(1) else {
+        eprintln!("uso: sched_sim <tareas.toml|csv> [round-robin|lottery|real-time|fixed-priority|all] [quantum] [semilla]");
+        std::process::exit(2);
+    };
+    let tasks = load_task_set(path).unwrap_or_else(|e| {
+        eprintln!("{path}: {e}");
+        std::process::exit(1);
+    });
+    let which = args.get(2).map_or("all", String::as_str);
+    let quantum = args.get(3).and_then(|v| v.parse().ok()).unwrap_or(1);
+    let seed = args.get(4).and_then(|v| v.parse().ok()).unwrap_or(1);
+
+    let names: Vec<&str> = match which {
+        "all" => SchedulerType::BUILTIN.iter().map(|&(name, _)| name).collect(),
+        name => vec![name],
+    };
+    for name in names {
+        let Some((kind, mut scheduler)) = builtin_scheduler(name, seed) else {
+            eprintln!("scheduler desconocido: {name}");
+            std::process::exit(2);
+        };
+        let report = simulate(&tasks, scheduler.as_mut(), kind, quantum);
+        print_report(name, &report);
+        println!();
+        print!("{}", report.gantt(100));
+        println!();
+    }
+}
@@ -1,5 +1,9 @@
 pub mod mythread;
 pub mod scheduler;
+pub mod safe;
+pub mod multicore;
+pub mod bench;
+pub mod sim;
 
 #[cfg(test)]
 
@@ -10,5 +14,9 @@ mod tests;
 pub use scheduler::Scheduler;
 pub use scheduler::scheduler_type::SchedulerType;
 pub use scheduler::round_robin::RRScheduler as RoundRobinScheduler;
-pub use scheduler::lottery::LotteryScheduler;
+pub use scheduler::lottery::{LotteryScheduler, CurrencyId, BASE_CURRENCY};
 pub use scheduler::real_time::RealTimeScheduler;
+pub use scheduler::fixed_priority::FixedPriorityScheduler;
+pub use scheduler::group::{GroupId, GroupScheduler, ROOT_GROUP};
+pub use safe::{Runtime, JoinHandle, Mutex, MutexGuard, MyChannel, MyReceiver, MySender};
+pub use multicore::MultiRuntime;
@@ -0,0 +1,93 @@
+use std::sync::{Arc, Condvar, Mutex};
+use crate::multicore::worker::{block, current_task, current_worker, Shared};
+use crate::mythread::mythread::ThreadId;
+
+struct Slot<T> {
+    // Err lleva el mensaje si el hilo hizo panic
+    value: Option<Result<T, String>>,
+    // Hilos verdes estacionados en el join
+    waiters: Vec<ThreadId>,
+}
+
+// Donde el hilo deja su resultado para el join.
+pub(crate) struct Packet<T> {
+    slot: Mutex<Slot<T>>,
+    done: Condvar,
+}
+
+impl<T> Packet<T> {
+    pub(crate) fn new() -> Arc<Self> {
+        Arc::new(Self { slot: Mutex::new(Slot { value: None, waiters: Vec::new() }), done: Condvar::new() })
+    }
+
+    // Se queda con el primer resultado (el abandono no pisa al de un hilo que terminó) y despierta
+    // a quien espera el join.
+    pub(crate) fn fill(&self, shared: &Shared, value: Result<T, String>) {
+        let waiters = {
+            let mut slot = self.slot.lock().unwrap();
+            slot.value.get_or_insert(value);
+            std::mem::take(&mut slot.waiters)
+        };
+        self.done.notify_all();
+        for tid in waiters {
+            shared.wake(tid);
+        }
+    }
+}
+
+// Handle de un hilo verde del runtime M:N. Se puede mover entre hilos del SO.
+pub struct MultiJoinHandle<T> {
+    tid: ThreadId,
+    packet: Arc<Packet<T>>,
+    shared: Arc<Shared>,
+}
+
+impl<T> MultiJoinHandle<T> {
+    pub(crate) fn new(tid: ThreadId, packet: Arc<Packet<T>>, shared: Arc<Shared>) -> Self {
+        Self { tid, packet, shared }
+    }
+
+    pub fn id(&self) -> ThreadId {
+        self.tid
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.packet.slot.lock().unwrap().value.is_some()
+    }
+
+    // Si el hilo hizo panic, el panic se repite aquí con su mensaje.
+    pub fn join(self) -> T {
+        let tid = self.tid;
+        self.try_join().unwrap_or_else(|msg| panic!("el hilo {tid} falló: {msg}"))
+    }
+
+    // Un hilo verde se estaciona hasta que el hilo termine y su worker sigue con otros; uno sin
+    // pila propia ayuda corriendo otros hilos; desde el driver se duerme.
+    pub fn try_join(self) -> Result<T, String> {
+        if current_worker().is_some() {
+            loop {
+                let mut slot = self.packet.slot.lock().unwrap();
+                if let Some(v) = slot.value.take() {
+                    return v;
+                }
+                let Some(me) = current_task() else {
+                    drop(slot);
+                    self.shared.help_or_yield();
+                    continue;
+                };
+                // Se anota con el slot tomado: el fill de ahí en adelante lo encuentra
+                slot.waiters.push(me);
+                drop(slot);
+                block();
+            }
+        }
+
+        let mut slot = self.packet.slot.lock().unwrap();
+        loop {
+            if let Some(v) = slot.value.take() {
+                return v;
+            }
+            slot = self.packet.done.wait(slot).unwrap();
+        }
+    }
+}
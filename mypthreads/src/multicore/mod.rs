@@ -0,0 +1,13 @@
+mod tests;
+
+pub mod multi_runtime;
+pub mod join_handle;
+pub mod sync;
+pub(crate) mod worker;
+
+pub use multi_runtime::MultiRuntime;
+pub use join_handle::MultiJoinHandle;
+pub use sync::{MultiCondvar, MultiMutex, MultiMutexGuard};
+pub use worker::WorkerStats;
+
+pub(crate) type Body = Box<dyn FnOnce() + Send>;
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle as OsJoinHandle;
+use crate::multicore::worker::{current_worker, worker_loop, Abandon, Parked, Shared, Task, Worker, WorkerStats};
+use crate::multicore::join_handle::Packet;
+use crate::multicore::{Body, MultiCondvar, MultiJoinHandle, MultiMutex};
+use crate::mythread::myerror::{MyError, MyResult};
+use crate::mythread::myfault::panic_message;
+use crate::mythread::mythread::{AnyParam, MyThread, ThreadId};
+use crate::mythread::mythreadattr::MyThreadAttr;
+use crate::scheduler::SchedulerType;
+
+extern "C-unwind" fn trampoline(arg: *mut AnyParam) -> *mut AnyParam {
+    let body = unsafe { Box::from_raw(arg as *mut Body) };
+    body();
+    std::ptr::null_mut()
+}
+
+// Runtime M:N: `workers` hilos del SO, cada uno con sus propias colas por scheduler,
+// que se roban hilos verdes listos entre sí cuando se quedan sin trabajo.
+pub struct MultiRuntime {
+    shared: Arc<Shared>,
+    os_threads: Vec<OsJoinHandle<()>>,
+    next_id: AtomicU64,
+    next_worker: AtomicUsize,
+}
+
+impl MultiRuntime {
+    pub fn new(workers: usize) -> Self {
+        let workers = workers.max(1);
+        let shared = Arc::new(Shared {
+            workers: (0..workers).map(|_| Worker::new()).collect(),
+            live: AtomicUsize::new(0),
+            shutdown: AtomicBool::new(false),
+            idle_lock: Mutex::new(()),
+            idle: Condvar::new(),
+            parked: Mutex::new(HashMap::new()),
+        });
+
+        let os_threads = (0..workers)
+            .map(|i| {
+                let shared = shared.clone();
+                std::thread::Builder::new()
+                    .name(format!("mypthreads-worker-{i}"))
+                    .spawn(move || worker_loop(&shared, i))
+                    .expect("no se pudo crear el worker")
+            })
+            .collect();
+
+        Self {
+            shared,
+            os_threads,
+            next_id: AtomicU64::new(0),
+            next_worker: AtomicUsize::new(0),
+        }
+    }
+
+    // Un worker por CPU disponible.
+    pub fn with_available_parallelism() -> Self {
+        Self::new(std::thread::available_parallelism().map_or(1, |n| n.get()))
+    }
+
+    pub fn workers(&self) -> usize {
+        self.shared.workers.len()
+    }
+
+    pub fn spawn<F, T>(&self, f: F) -> MultiJoinHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        self.spawn_task(MyThreadAttr::default(), SchedulerType::default(), f)
+    }
+
+    // Invalid con SchedulerType::Custom: los schedulers registrados son del runtime de un solo
+    // hilo y los workers no tienen una copia propia de cada uno.
+    pub fn spawn_with<F, T>(&self, attr: MyThreadAttr, scheduler: SchedulerType, f: F) -> MyResult<MultiJoinHandle<T>>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        if let SchedulerType::Custom(_) = scheduler {
+            return Err(MyError::Invalid);
+        }
+        Ok(self.spawn_task(attr, scheduler, f))
+    }
+
+    fn spawn_task<F, T>(&self, attr: MyThreadAttr, scheduler: SchedulerType, f: F) -> MultiJoinHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let packet = Packet::new();
+        let (slot, shared) = (packet.clone(), self.shared.clone());
+        let body: Body = Box::new(move || {
+            // Un panic se guarda como Err para el join; el worker sigue corriendo los demás
+            let value = catch_unwind(AssertUnwindSafe(f)).map_err(|p| panic_message(&*p));
+            slot.fill(&shared, value);
+        });
+        let arg = Box::into_raw(Box::new(body)) as *mut AnyParam;
+        // Si el hilo no llega a terminar (desborde, runtime apagado) el join recibe el motivo
+        let (slot, shared) = (packet.clone(), self.shared.clone());
+        let abandon: Abandon = Box::new(move |reason: &str| slot.fill(&shared, Err(reason.to_string())));
+
+        let tid: ThreadId = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let thread = MyThread::new(tid, attr, trampoline, arg, Some(scheduler));
+
+        // Desde un hilo verde se encola en el worker propio (los demás lo roban); desde fuera, en ronda
+        let target = current_worker().unwrap_or_else(|| {
+            self.next_worker.fetch_add(1, Ordering::Relaxed) % self.shared.workers.len()
+        });
+        self.shared.live.fetch_add(1, Ordering::AcqRel);
+        self.shared.workers[target].ready.lock().unwrap().push(Task::new(thread, abandon));
+        self.shared.notify_work();
+
+        MultiJoinHandle::new(tid, packet, self.shared.clone())
+    }
+
+    // Mutex y condvar que estacionan al hilo verde en vez de bloquear el hilo del SO.
+    pub fn mutex<T>(&self, value: T) -> MultiMutex<T> {
+        MultiMutex::new(self.shared.clone(), value)
+    }
+
+    pub fn condvar(&self) -> MultiCondvar {
+        MultiCondvar::new(self.shared.clone())
+    }
+
+    // Espera desde el driver a que no quede ningún hilo verde vivo.
+    pub fn wait_all(&self) {
+        while self.shared.live.load(Ordering::Acquire) > 0 {
+            self.shared.help_or_yield();
+        }
+    }
+
+    // Hilos listos por worker en este momento.
+    pub fn queue_lengths(&self) -> Vec<usize> {
+        self.shared.workers.iter().map(|w| w.ready.lock().unwrap().len()).collect()
+    }
+
+    pub fn stats(&self) -> Vec<WorkerStats> {
+        self.shared.workers.iter().map(|w| w.stats()).collect()
+    }
+}
+
+impl Drop for MultiRuntime {
+    fn drop(&mut self) {
+        self.shared.shutdown.store(true, Ordering::Release);
+        self.shared.notify_work();
+        for t in self.os_threads.drain(..) {
+            let _ = t.join();
+        }
+
+        // Lo que quedó listo o estacionado ya no va a correr: al soltarlo su join recibe un error
+        let mut leftover: Vec<Task> = self.shared.workers.iter().flat_map(|w| w.drain()).collect();
+        let parked = std::mem::take(&mut *self.shared.parked.lock().unwrap());
+        leftover.extend(parked.into_values().filter_map(|p| match p {
+            Parked::Waiting(_, task) => Some(*task),
+            Parked::Woken => None,
+        }));
+        drop(leftover);
+    }
+}
@@ -0,0 +1,154 @@
+use std::cell::UnsafeCell;
+use std::collections::VecDeque;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use crate::multicore::worker::{block, current_task, Shared};
+use crate::mythread::mythread::ThreadId;
+
+// Mutex para hilos verdes que pueden estar en workers distintos.
+// La adquisición es un compare_exchange; si está tomado el hilo verde se estaciona y su worker
+// sigue con otros hilos hasta que el dueño lo suelte.
+pub struct MultiMutex<T> {
+    shared: Arc<Shared>,
+    locked: AtomicBool,
+    // Hilos estacionados esperando el mutex, en orden de llegada
+    waiters: Mutex<VecDeque<ThreadId>>,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Send for MultiMutex<T> {}
+unsafe impl<T: Send> Sync for MultiMutex<T> {}
+
+pub struct MultiMutexGuard<'a, T> {
+    mutex: &'a MultiMutex<T>,
+}
+
+impl<T> MultiMutex<T> {
+    pub(crate) fn new(shared: Arc<Shared>, value: T) -> Self {
+        Self { shared, locked: AtomicBool::new(false), waiters: Mutex::new(VecDeque::new()), data: UnsafeCell::new(value) }
+    }
+
+    pub fn try_lock(&self) -> Option<MultiMutexGuard<'_, T>> {
+        self.locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| MultiMutexGuard { mutex: self })
+    }
+
+    // Desde el driver (o un hilo sin pila propia, que no se puede estacionar) se ayuda o se cede el CPU.
+    pub fn lock(&self) -> MultiMutexGuard<'_, T> {
+        loop {
+            if let Some(g) = self.try_lock() {
+                return g;
+            }
+            let Some(me) = current_task() else {
+                self.shared.help_or_yield();
+                continue;
+            };
+            {
+                let mut waiters = self.waiters.lock().unwrap();
+                // Con la lista tomada: si lo soltaron antes, el unlock no vio a nadie a quien despertar
+                if let Some(g) = self.try_lock() {
+                    return g;
+                }
+                waiters.push_back(me);
+            }
+            block();
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::Acquire)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T> Deref for MultiMutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<T> DerefMut for MultiMutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<T> Drop for MultiMutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.mutex.locked.store(false, Ordering::Release);
+        // Despierta al primero; si otro lo toma antes, vuelve a anotarse
+        let next = self.mutex.waiters.lock().unwrap().pop_front();
+        if let Some(tid) = next {
+            self.mutex.shared.wake(tid);
+        }
+    }
+}
+
+// Variable de condición para MultiMutex. Los hilos verdes que esperan se estacionan hasta un
+// notify; el driver espera a que avance la secuencia de notifies (puede despertar de más).
+pub struct MultiCondvar {
+    shared: Arc<Shared>,
+    seq: AtomicU64,
+    waiters: Mutex<VecDeque<ThreadId>>,
+}
+
+impl MultiCondvar {
+    pub(crate) fn new(shared: Arc<Shared>) -> Self {
+        Self { shared, seq: AtomicU64::new(0), waiters: Mutex::new(VecDeque::new()) }
+    }
+
+    pub fn wait<'a, T>(&self, guard: MultiMutexGuard<'a, T>) -> MultiMutexGuard<'a, T> {
+        let mutex = guard.mutex;
+        match current_task() {
+            Some(me) => {
+                // Se anota antes de soltar el mutex: un notify de ahí en adelante lo encuentra
+                self.waiters.lock().unwrap().push_back(me);
+                drop(guard);
+                block();
+            }
+            None => {
+                let seen = self.seq.load(Ordering::Acquire);
+                drop(guard);
+                while self.seq.load(Ordering::Acquire) == seen {
+                    self.shared.help_or_yield();
+                }
+            }
+        }
+        mutex.lock()
+    }
+
+    pub fn wait_while<'a, T>(
+        &self,
+        mut guard: MultiMutexGuard<'a, T>,
+        mut condition: impl FnMut(&mut T) -> bool,
+    ) -> MultiMutexGuard<'a, T> {
+        while condition(&mut guard) {
+            guard = self.wait(guard);
+        }
+        guard
+    }
+
+    pub fn notify_one(&self) {
+        self.seq.fetch_add(1, Ordering::AcqRel);
+        let next = self.waiters.lock().unwrap().pop_front();
+        if let Some(tid) = next {
+            self.shared.wake(tid);
+        }
+    }
+
+    pub fn notify_all(&self) {
+        self.seq.fetch_add(1, Ordering::AcqRel);
+        let all: Vec<ThreadId> = self.waiters.lock().unwrap().drain(..).collect();
+        for tid in all {
+            self.shared.wake(tid);
+        }
+    }
+}
@@ -0,0 +1,258 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use crate::multicore::MultiRuntime;
+    use crate::mythread::myerror::MyError;
+    use crate::mythread::myonce::MyOnce;
+    use crate::mythread::myspinlock::MySpinLock;
+    use crate::mythread::mythreadattr::MyThreadAttr;
+    use crate::scheduler::SchedulerType;
+
+    fn fib(n: u64) -> u64 {
+        if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+    }
+
+    #[test]
+    fn spawn_and_join_on_several_workers() {
+        let rt = MultiRuntime::new(4);
+        assert_eq!(rt.workers(), 4);
+
+        let handles: Vec<_> = (0..64u64).map(|i| rt.spawn(move || fib(10) + i)).collect();
+        for (i, h) in handles.into_iter().enumerate() {
+            assert_eq!(h.join(), 55 + i as u64);
+        }
+
+        let executed: usize = rt.stats().iter().map(|s| s.executed).sum();
+        assert_eq!(executed, 64);
+    }
+
+    #[test]
+    fn nested_spawn_and_join_inside_workers() {
+        let rt = Arc::new(MultiRuntime::new(3));
+        let inner = rt.clone();
+
+        let root = rt.spawn(move || {
+            let parts: Vec<_> = (0..8u64).map(|i| inner.spawn(move || fib(12) * i)).collect();
+            parts.into_iter().map(|h| h.join()).sum::<u64>()
+        });
+
+        assert_eq!(root.join(), 144 * 28);
+    }
+
+    #[test]
+    fn idle_workers_steal_from_busy_one() {
+        let rt = Arc::new(MultiRuntime::new(4));
+        let inner = rt.clone();
+
+        // Todos se encolan en el worker del hilo raíz; los demás sólo pueden correrlos robando
+        let root = rt.spawn(move || {
+            let hs: Vec<_> = (0..40)
+                .map(|_| inner.spawn(|| std::thread::sleep(Duration::from_millis(2))))
+                .collect();
+            for h in hs {
+                h.join();
+            }
+        });
+        root.join();
+        rt.wait_all();
+
+        let stolen: usize = rt.stats().iter().map(|s| s.stolen).sum();
+        assert!(stolen > 0, "ningún worker robó trabajo: {:?}", rt.stats());
+    }
+
+    #[test]
+    fn multi_mutex_counts_across_workers() {
+        let rt = MultiRuntime::new(4);
+        let counter = Arc::new(rt.mutex(0usize));
+
+        let hs: Vec<_> = (0..8)
+            .map(|_| {
+                let c = counter.clone();
+                rt.spawn(move || {
+                    for _ in 0..1000 {
+                        *c.lock() += 1;
+                    }
+                })
+            })
+            .collect();
+        for h in hs {
+            h.join();
+        }
+
+        assert_eq!(*counter.lock(), 8000);
+    }
+
+    #[test]
+    fn multi_condvar_producer_consumer() {
+        let rt = MultiRuntime::new(2);
+        let queue = Arc::new(rt.mutex(VecDeque::new()));
+        let ready = Arc::new(rt.condvar());
+
+        let (q, cv) = (queue.clone(), ready.clone());
+        let consumer = rt.spawn(move || {
+            let mut got = Vec::new();
+            while got.len() < 10 {
+                let mut g = cv.wait_while(q.lock(), |items: &mut VecDeque<i32>| items.is_empty());
+                got.extend(g.drain(..));
+            }
+            got
+        });
+
+        let (q, cv) = (queue.clone(), ready.clone());
+        let producer = rt.spawn(move || {
+            for i in 0..10 {
+                q.lock().push_back(i);
+                cv.notify_one();
+            }
+        });
+
+        producer.join();
+        assert_eq!(consumer.join(), (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn lock_waiter_parks_instead_of_spinning_over_the_holder() {
+        let rt = Arc::new(MultiRuntime::new(1));
+        let counter = Arc::new(rt.mutex(0));
+
+        let (inner, c) = (rt.clone(), counter.clone());
+        let holder = rt.spawn(move || {
+            let mut g = c.lock();
+            let c2 = c.clone();
+            let waiter = inner.spawn(move || *c2.lock() += 1);
+            // El join corre encima de este hilo al que espera el mutex: tiene que estacionarse, no girar
+            *g += inner.spawn(|| 7).join();
+            drop(g);
+            waiter
+        });
+
+        holder.join().join();
+        assert_eq!(*counter.lock(), 8);
+    }
+
+    #[test]
+    fn condvar_waiter_parks_instead_of_spinning_over_the_notifier() {
+        let rt = Arc::new(MultiRuntime::new(1));
+        let ready = Arc::new(rt.mutex(false));
+        let cv = Arc::new(rt.condvar());
+
+        let (inner, r, c) = (rt.clone(), ready.clone(), cv.clone());
+        let notifier = rt.spawn(move || {
+            let (r2, c2) = (r.clone(), c.clone());
+            let waiter = inner.spawn(move || {
+                let _g = c2.wait_while(r2.lock(), |ready: &mut bool| !*ready);
+                "despierto"
+            });
+            inner.spawn(|| ()).join();
+            *r.lock() = true;
+            c.notify_one();
+            waiter
+        });
+
+        assert_eq!(notifier.join().join(), "despierto");
+    }
+
+    #[test]
+    fn join_waiter_parks_so_thread_above_can_finish() {
+        let rt = Arc::new(MultiRuntime::new(1));
+        let go = Arc::new(rt.mutex(false));
+        let cv = Arc::new(rt.condvar());
+
+        let (inner, g, c) = (rt.clone(), go.clone(), cv.clone());
+        let first = rt.spawn(move || {
+            let (g2, c2) = (g.clone(), c.clone());
+            let gated = inner.spawn(move || {
+                let _g = c2.wait_while(g2.lock(), |go: &mut bool| !*go);
+            });
+            // Espera a `gated`, que espera a este hilo: si corriera encima de él, nunca volvería aquí
+            let middle = inner.spawn(move || gated.join());
+            inner.spawn(|| ()).join();
+            *g.lock() = true;
+            c.notify_all();
+            middle
+        });
+
+        first.join().join();
+    }
+
+    #[test]
+    fn dropping_runtime_fails_handles_still_waiting() {
+        let rt = MultiRuntime::new(1);
+        let ready = Arc::new(rt.mutex(false));
+        let cv = Arc::new(rt.condvar());
+
+        // Nadie le va a avisar: sigue estacionado cuando se apaga el runtime
+        let (r, c) = (ready.clone(), cv.clone());
+        let stuck = rt.spawn(move || {
+            let _g = c.wait_while(r.lock(), |ready: &mut bool| !*ready);
+        });
+        drop(rt);
+
+        assert_eq!(stuck.try_join().unwrap_err(), "el runtime se apagó antes de que el hilo terminara");
+    }
+
+    #[test]
+    fn spawn_with_keeps_scheduler_and_attr() {
+        let rt = MultiRuntime::new(1);
+        let h = rt.spawn_with(MyThreadAttr::new(5, 3), SchedulerType::RealTime, || "rt").unwrap();
+        assert_eq!(h.join(), "rt");
+        rt.wait_all();
+        assert_eq!(rt.queue_lengths(), vec![0]);
+    }
+
+    #[test]
+    fn spawn_with_custom_scheduler_is_rejected() {
+        let rt = MultiRuntime::new(1);
+        let err = rt.spawn_with(MyThreadAttr::default(), SchedulerType::Custom(0), || ()).err();
+        assert_eq!(err, Some(MyError::Invalid));
+        assert_eq!(rt.queue_lengths(), vec![0]);
+    }
+
+    #[test]
+    fn panic_in_worker_is_reported_by_try_join() {
+        let rt = MultiRuntime::new(2);
+        let bad = rt.spawn(|| -> u64 { panic!("planta en Boom") });
+        let good: Vec<_> = (0..8u64).map(|i| rt.spawn(move || fib(8) + i)).collect();
+
+        assert_eq!(bad.try_join().unwrap_err(), "planta en Boom");
+        for (i, h) in good.into_iter().enumerate() {
+            assert_eq!(h.join(), 21 + i as u64);
+        }
+    }
+
+    #[test]
+    fn spin_lock_and_once_across_workers() {
+        let rt = MultiRuntime::new(4);
+        let lock = Arc::new(MySpinLock::new());
+        let counter = Arc::new(AtomicUsize::new(0));
+        let once = Arc::new(MyOnce::new());
+        let inits = Arc::new(AtomicUsize::new(0));
+
+        let hs: Vec<_> = (0..8)
+            .map(|_| {
+                let (lock, counter, once, inits) = (lock.clone(), counter.clone(), once.clone(), inits.clone());
+                rt.spawn(move || {
+                    once.call_once(|| {
+                        inits.fetch_add(1, Ordering::SeqCst);
+                    });
+                    for _ in 0..1000 {
+                        assert_eq!(lock.lock(), 0);
+                        // Leer y escribir por separado: solo da bien si el lock excluye de verdad
+                        let v = counter.load(Ordering::Relaxed);
+                        counter.store(v + 1, Ordering::Relaxed);
+                        lock.unlock();
+                    }
+                })
+            })
+            .collect();
+        for h in hs {
+            h.join();
+        }
+
+        assert_eq!(counter.load(Ordering::SeqCst), 8000);
+        assert_eq!(inits.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,301 @@
+use std::cell::Cell;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+use crate::mythread::mystack::{park, run_routine, MyStack, RunOutcome};
+use crate::mythread::mythread::{MyThread, ThreadId};
+use crate::mythread::thread_state::ThreadState;
+use crate::scheduler::{lottery::LotteryScheduler, real_time::RealTimeScheduler, round_robin::RRScheduler, FixedPriorityScheduler};
+use crate::scheduler::{Scheduler, SchedulerType};
+
+// Llena el join de un hilo que ya no va a terminar con el motivo como error.
+pub(crate) type Abandon = Box<dyn FnOnce(&str) + Send>;
+
+// Un hilo verde listo para correr en cualquier worker.
+pub(crate) struct Task {
+    pub(crate) thread: MyThread,
+    ran: bool,
+    // None una vez que su rutina retornó
+    abandon: Option<Abandon>,
+}
+
+// Los punteros de MyThread apuntan al closure empaquetado, que se exige Send al crear el hilo.
+unsafe impl Send for Task {}
+
+impl Task {
+    pub(crate) fn new(thread: MyThread, abandon: Abandon) -> Self {
+        Self { thread, ran: false, abandon: Some(abandon) }
+    }
+
+    // La primera vez le reserva su pila propia; sin ella no se puede estacionar y corre sobre la del worker.
+    fn prepare(&mut self) {
+        if !self.ran {
+            self.ran = true;
+            self.thread.stack = MyStack::new(self.thread.attr.stack_size()).ok();
+        }
+        self.thread.state = ThreadState::Running;
+    }
+
+    // Corre la rutina o la retoma donde se estacionó.
+    fn run(&mut self) -> RunOutcome {
+        let region = self.thread.stack.as_ref().map(|s| s.region());
+        run_routine(self.thread.id, region, self.thread.start_routine, self.thread.arg)
+    }
+
+    fn abandon(&mut self, reason: &str) {
+        if let Some(abandon) = self.abandon.take() {
+            abandon(reason);
+        }
+    }
+}
+
+impl Drop for Task {
+    fn drop(&mut self) {
+        // Nunca corrió (runtime apagado): liberamos el closure que lleva de argumento
+        if !self.ran {
+            drop(unsafe { Box::from_raw(self.thread.arg as *mut super::Body) });
+        }
+        // Seguía listo o estacionado al apagarse el runtime: su join recibe el error
+        self.abandon("el runtime se apagó antes de que el hilo terminara");
+    }
+}
+
+// Colas de listos de un worker: un scheduler por tipo, igual que MyTRuntime.
+pub(crate) struct ReadyQueues {
+    schedulers: HashMap<SchedulerType, Box<dyn Scheduler + Send>>,
+    tasks: HashMap<ThreadId, Task>,
+}
+
+impl ReadyQueues {
+    fn new() -> Self {
+        let mut schedulers: HashMap<SchedulerType, Box<dyn Scheduler + Send>> = HashMap::new();
+        schedulers.insert(SchedulerType::RoundRobin, Box::new(RRScheduler::new()));
+        schedulers.insert(SchedulerType::Lottery, Box::new(LotteryScheduler::new()));
+        schedulers.insert(SchedulerType::RealTime, Box::new(RealTimeScheduler::new()));
+        schedulers.insert(SchedulerType::FixedPriority, Box::new(FixedPriorityScheduler::new()));
+        Self { schedulers, tasks: HashMap::new() }
+    }
+
+    pub(crate) fn push(&mut self, mut task: Task) {
+        let tid = task.thread.id();
+        task.thread.state = ThreadState::Ready;
+        // MultiRuntime::spawn_with no deja pasar SchedulerType::Custom
+        if let Some(s) = self.schedulers.get_mut(&task.thread.scheduler) {
+            s.enqueue(tid, &task.thread);
+        }
+        self.tasks.insert(tid, task);
+    }
+
+    // RealTime > FixedPriority > Lottery > RoundRobin, igual que el runtime de un solo hilo
+    fn pop(&mut self) -> Option<Task> {
+        for kind in [SchedulerType::RealTime, SchedulerType::FixedPriority, SchedulerType::Lottery, SchedulerType::RoundRobin] {
+            let Some(s) = self.schedulers.get_mut(&kind) else { continue };
+            while let Some(tid) = s.pick_next() {
+                if let Some(task) = self.tasks.remove(&tid) {
+                    return Some(task);
+                }
+            }
+        }
+        None
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    fn drain(&mut self) -> Vec<Task> {
+        self.tasks.drain().map(|(_, task)| task).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WorkerStats {
+    pub executed: usize,
+    pub stolen: usize,
+}
+
+pub(crate) struct Worker {
+    pub(crate) ready: Mutex<ReadyQueues>,
+    // Hilos que se estacionaron en este worker y ya pueden seguir. Solo este worker los retoma:
+    // su pila quedó a medias en este hilo del SO
+    resumed: Mutex<VecDeque<Task>>,
+    executed: AtomicUsize,
+    stolen: AtomicUsize,
+}
+
+impl Worker {
+    pub(crate) fn new() -> Self {
+        Self {
+            ready: Mutex::new(ReadyQueues::new()),
+            resumed: Mutex::new(VecDeque::new()),
+            executed: AtomicUsize::new(0),
+            stolen: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn stats(&self) -> WorkerStats {
+        WorkerStats {
+            executed: self.executed.load(Ordering::Relaxed),
+            stolen: self.stolen.load(Ordering::Relaxed),
+        }
+    }
+
+    // Saca todo lo que espera correr aquí (al apagar el runtime).
+    pub(crate) fn drain(&self) -> Vec<Task> {
+        let mut tasks = self.ready.lock().unwrap().drain();
+        tasks.extend(self.resumed.lock().unwrap().drain(..));
+        tasks
+    }
+}
+
+// Hilo estacionado esperando un MultiMutex, MultiCondvar o join.
+pub(crate) enum Parked {
+    // En el worker donde se estacionó
+    Waiting(usize, Box<Task>),
+    // El wake llegó antes de que el worker terminara de estacionarlo
+    Woken,
+}
+
+// Estado compartido por todos los workers.
+pub(crate) struct Shared {
+    pub(crate) workers: Vec<Worker>,
+    pub(crate) live: AtomicUsize,
+    pub(crate) shutdown: AtomicBool,
+    pub(crate) idle_lock: Mutex<()>,
+    pub(crate) idle: Condvar,
+    pub(crate) parked: Mutex<HashMap<ThreadId, Parked>>,
+}
+
+thread_local! {
+    // Índice del worker que corre en este hilo del SO (None en el driver)
+    static WORKER_INDEX: Cell<Option<usize>> = const { Cell::new(None) };
+    // Hilo verde que corre ahora en este hilo del SO, si tiene pila propia (solo esos se estacionan)
+    static CURRENT_TASK: Cell<Option<ThreadId>> = const { Cell::new(None) };
+    // Lo pone `block`: ese park espera un wake, no es solo ceder el CPU
+    static BLOCKING: Cell<bool> = const { Cell::new(false) };
+}
+
+pub(crate) fn current_worker() -> Option<usize> {
+    WORKER_INDEX.with(|w| w.get())
+}
+
+pub(crate) fn current_task() -> Option<ThreadId> {
+    CURRENT_TASK.get()
+}
+
+// Estaciona al hilo verde actual hasta que alguien llame a `Shared::wake` con su id. Quien llama
+// ya lo anotó en su lista de espera, así que el wake puede llegar antes de que se estacione.
+pub(crate) fn block() {
+    BLOCKING.set(true);
+    park();
+}
+
+impl Shared {
+    // Busca trabajo: primero en las colas propias, luego robando a los demás en orden.
+    // Los que se estacionaron y ya pueden seguir no se roban.
+    fn find_task(&self, me: usize) -> Option<Task> {
+        if let Some(task) = self.workers[me].ready.lock().unwrap().pop() {
+            return Some(task);
+        }
+        if let Some(task) = self.workers[me].resumed.lock().unwrap().pop_front() {
+            return Some(task);
+        }
+        let n = self.workers.len();
+        for offset in 1..n {
+            let victim = (me + offset) % n;
+            if let Some(task) = self.workers[victim].ready.lock().unwrap().pop() {
+                self.workers[me].stolen.fetch_add(1, Ordering::Relaxed);
+                return Some(task);
+            }
+        }
+        None
+    }
+
+    // Corre un hilo listo en el worker `me`. Lo usan el loop del worker y las esperas (join).
+    pub(crate) fn run_one(&self, me: usize) -> bool {
+        let Some(mut task) = self.find_task(me) else { return false };
+        if !task.ran {
+            // Se cuenta antes de correrla: su join puede volver antes de que `run` retorne aquí
+            self.workers[me].executed.fetch_add(1, Ordering::Relaxed);
+        }
+        task.prepare();
+        let tid = task.thread.id;
+        let outer = CURRENT_TASK.replace(task.thread.stack.is_some().then_some(tid));
+        let outcome = task.run();
+        CURRENT_TASK.set(outer);
+
+        match outcome {
+            RunOutcome::Parked if BLOCKING.replace(false) => self.park_task(me, task),
+            // Solo cedió el CPU (spin lock, once): sigue aquí después de los listos
+            RunOutcome::Parked => self.workers[me].resumed.lock().unwrap().push_back(task),
+            RunOutcome::Returned(_) => self.finish(task, None),
+            RunOutcome::Overflowed => self.finish(task, Some(format!("el hilo {tid} desbordó su pila"))),
+            RunOutcome::Faulted(message) => self.finish(task, Some(message)),
+        }
+        true
+    }
+
+    fn finish(&self, mut task: Task, failure: Option<String>) {
+        match failure {
+            Some(reason) => task.abandon(&reason),
+            None => task.abandon = None,
+        }
+        drop(task);
+        self.live.fetch_sub(1, Ordering::AcqRel);
+    }
+
+    fn park_task(&self, me: usize, task: Task) {
+        let mut parked = self.parked.lock().unwrap();
+        if parked.remove(&task.thread.id).is_some() {
+            drop(parked);
+            self.workers[me].resumed.lock().unwrap().push_back(task);
+        } else {
+            parked.insert(task.thread.id, Parked::Waiting(me, Box::new(task)));
+        }
+    }
+
+    // Devuelve a su worker un hilo estacionado con `block`.
+    pub(crate) fn wake(&self, tid: ThreadId) {
+        let mut parked = self.parked.lock().unwrap();
+        match parked.remove(&tid) {
+            Some(Parked::Waiting(home, task)) => {
+                drop(parked);
+                self.workers[home].resumed.lock().unwrap().push_back(*task);
+                self.notify_work();
+            }
+            _ => {
+                parked.insert(tid, Parked::Woken);
+            }
+        }
+    }
+
+    pub(crate) fn notify_work(&self) {
+        let _guard = self.idle_lock.lock().unwrap();
+        self.idle.notify_all();
+    }
+
+    // Mientras se espera algo desde un hilo verde se ayuda corriendo otros; desde el driver solo se cede.
+    pub(crate) fn help_or_yield(&self) {
+        match current_worker() {
+            Some(me) if self.run_one(me) => {}
+            _ => std::thread::yield_now(),
+        }
+    }
+}
+
+pub(crate) fn worker_loop(shared: &Shared, me: usize) {
+    WORKER_INDEX.with(|w| w.set(Some(me)));
+    loop {
+        if shared.run_one(me) {
+            continue;
+        }
+        if shared.shutdown.load(Ordering::Acquire) {
+            break;
+        }
+        // Nada para correr: dormir un rato o hasta que llegue trabajo
+        let guard = shared.idle_lock.lock().unwrap();
+        let _ = shared.idle.wait_timeout(guard, Duration::from_millis(1)).unwrap();
+    }
+    WORKER_INDEX.with(|w| w.set(None));
+}
@@ -4,7 +4,17 @@ pub mod mypthread;
 pub mod mythread;
 pub mod mymutex;
 pub mod thread_state;
-mod myruntime;
+pub(crate) mod myruntime;
 pub mod mythreadattr;
 pub mod mutexlockkind;
-pub mod mypthreadexits;
+pub mod myerror;
+pub mod myreactor;
+pub mod mysignal;
+pub mod mystack;
+pub mod myfault;
+pub mod myspinlock;
+pub mod myonce;
+pub mod myreplay;
+pub mod myinspect;
+pub mod myaging;
+pub mod myclock;
@@ -0,0 +1,37 @@
+// Envejecimiento contra la inanición. Un hilo RoundRobin detrás de un flujo constante de RealTime, o
+// uno con pocos tickets en Lottery, puede quedarse listo para siempre. El runtime cuenta cuántos
+// despachos lleva cada hilo en su cola de listos; con el aging activo, cada `threshold` despachos de
+// espera el hilo sube un escalón en el orden de clases (RoundRobin, Lottery, registrados, grupos,
+// FixedPriority, RealTime): con el primero pasa delante de los de su clase y con cada uno más queda
+// por encima de la clase siguiente. Nunca pasa por encima de RealTime mientras haya hilos RealTime
+// con el deadline por delante. La espera se mide en despachos y no en ms para que el replay dé lo mismo.
+
+// Escalón de RealTime, la clase más alta
+pub(crate) const MY_REALTIME_RANK: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MyAging {
+    // Despachos que un hilo puede esperar listo antes de promoverlo (al menos 1)
+    pub threshold: usize,
+}
+
+impl MyAging {
+    pub fn new(threshold: usize) -> Self {
+        Self { threshold: threshold.max(1) }
+    }
+}
+
+impl Default for MyAging {
+    fn default() -> Self {
+        Self::new(32)
+    }
+}
+
+// Cifras de toda la corrida; las de cada hilo salen en MyThreadInfo (max_wait, promotions).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MyAgingStats {
+    // Se cuentan aunque el aging esté apagado, así se ve cuánto esperan los hilos sin él
+    pub max_wait: usize,
+    pub promotions: usize,
+    pub dispatches: usize,
+}
@@ -0,0 +1,85 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+// Reloj en ms que comparten el runtime (MyTRuntime::time_ms) y quien lo use encima, p.ej. los
+// semáforos y las plantas de thread-city. Con VirtualClock la simulación corre tan rápido como
+// pueda y el tiempo solo avanza con `advance`; con MonotonicClock (o escalado) sigue al reloj real.
+pub trait Clock: Send + Sync {
+    // ms desde que arrancó el reloj
+    fn now_ms(&self) -> usize;
+
+    // Solo mueve los relojes virtuales; el de pared lo ignora
+    fn advance(&self, _ms: usize) {}
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+// Reloj de pared monotónico, desde que se crea.
+pub struct MonotonicClock {
+    start: Instant,
+}
+
+impl MonotonicClock {
+    pub fn new() -> Self {
+        Self { start: Instant::now() }
+    }
+}
+
+impl Default for MonotonicClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MonotonicClock {
+    fn now_ms(&self) -> usize {
+        self.start.elapsed().as_millis() as usize
+    }
+}
+
+// Reloj que solo avanza a mano; el replay y los tests dan lo mismo sin importar la máquina.
+#[derive(Default)]
+pub struct VirtualClock {
+    now: AtomicUsize,
+}
+
+impl VirtualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now_ms(&self) -> usize {
+        self.now.load(Ordering::Relaxed)
+    }
+
+    fn advance(&self, ms: usize) {
+        self.now.fetch_add(ms, Ordering::Relaxed);
+    }
+}
+
+// Otro reloj visto `factor` veces más rápido (factor 10: 1 ms real son 10 ms del reloj).
+// `advance` mueve el de adentro lo necesario para que este avance `ms`.
+pub struct ScaledClock {
+    inner: SharedClock,
+    factor: f64,
+}
+
+impl ScaledClock {
+    pub fn new(inner: SharedClock, factor: f64) -> Self {
+        assert!(factor > 0.0, "factor de escala inválido: {factor}");
+        Self { inner, factor }
+    }
+}
+
+impl Clock for ScaledClock {
+    fn now_ms(&self) -> usize {
+        (self.inner.now_ms() as f64 * self.factor) as usize
+    }
+
+    fn advance(&self, ms: usize) {
+        self.inner.advance((ms as f64 / self.factor).ceil() as usize);
+    }
+}
@@ -0,0 +1,88 @@
+use std::fmt;
+use std::io;
+use std::os::raw::c_int;
+
+// Errores de toda la API. Cada uno vale lo mismo que su errno de POSIX: las funciones estilo C
+// devuelven 0 o `err as c_int` (como pthread_*), y las de Rust MyResult.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MyError {
+    // Argumento inválido: puntero nulo, mutex sin inicializar, señal o scheduler desconocidos,
+    // join a un hilo detached o que ya espera otro
+    Invalid = libc::EINVAL,
+    // El hilo no existe, ya terminó o ya se limpió
+    NoSuchThread = libc::ESRCH,
+    // La espera nunca terminaría: join a sí mismo, relock del dueño o nadie puede soltar el lock
+    Deadlock = libc::EDEADLK,
+    // Mutex o spinlock tomado (trylock, destroy)
+    Busy = libc::EBUSY,
+    // Soltar un lock que no es suyo o que no estaba tomado; llamar fuera de un hilo verde
+    NotPermitted = libc::EPERM,
+    // El buffer no alcanza (my_thread_getname)
+    OutOfRange = libc::ERANGE,
+    // Códigos de salida de un hilo que el runtime tuvo que matar; los devuelve join.
+    // Tocó la página de guarda de su pila
+    StackOverflow = libc::EFAULT,
+    // Su rutina hizo panic (ver my_thread_panic_message)
+    Faulted = libc::ECANCELED,
+}
+
+pub type MyResult<T> = Result<T, MyError>;
+
+impl MyError {
+    const ALL: [MyError; 8] = [
+        MyError::Invalid,
+        MyError::NoSuchThread,
+        MyError::Deadlock,
+        MyError::Busy,
+        MyError::NotPermitted,
+        MyError::OutOfRange,
+        MyError::StackOverflow,
+        MyError::Faulted,
+    ];
+
+    pub fn errno(self) -> c_int {
+        self as c_int
+    }
+
+    pub fn from_errno(errno: c_int) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.errno() == errno)
+    }
+
+    // Pasa un código de la API de C a MyResult. Un código que no es de la API cuenta como Invalid.
+    pub fn check(rc: c_int) -> MyResult<()> {
+        match rc {
+            0 => Ok(()),
+            rc => Err(Self::from_errno(rc).unwrap_or(MyError::Invalid)),
+        }
+    }
+
+    // Lo contrario de `check`: 0 o el errno.
+    pub fn code(result: MyResult<()>) -> c_int {
+        result.err().map_or(0, MyError::errno)
+    }
+}
+
+impl fmt::Display for MyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            MyError::Invalid => "argumento inválido",
+            MyError::NoSuchThread => "el hilo no existe",
+            MyError::Deadlock => "la espera no terminaría nunca",
+            MyError::Busy => "ocupado",
+            MyError::NotPermitted => "operación no permitida",
+            MyError::OutOfRange => "no cabe en el buffer",
+            MyError::StackOverflow => "el hilo desbordó su pila",
+            MyError::Faulted => "la rutina del hilo hizo panic",
+        };
+        write!(f, "{msg} (errno {})", self.errno())
+    }
+}
+
+impl std::error::Error for MyError {}
+
+impl From<MyError> for io::Error {
+    fn from(e: MyError) -> Self {
+        io::Error::from_raw_os_error(e.errno())
+    }
+}
@@ -0,0 +1,40 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use crate::mythread::mythread::AnyParam;
+
+thread_local! {
+    // Mensaje del último panic atrapado por my_thread_guard, hasta que el runtime lo recoja
+    static FAULT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+// Atrapa el panic de una MyTRoutine: devuelve nulo y el runtime deja al hilo Faulted con el mensaje
+// para quien haga join. El runtime ya envuelve así cada rutina que despacha (las MyTRoutine son
+// `extern "C-unwind"`, el panic llega hasta él); sirve también para atraparlo dentro de la rutina.
+pub fn my_thread_guard<F>(body: F) -> *mut AnyParam
+where
+    F: FnOnce() -> *mut AnyParam,
+{
+    match catch_unwind(AssertUnwindSafe(body)) {
+        Ok(ret) => ret,
+        Err(payload) => {
+            FAULT.with(|f| *f.borrow_mut() = Some(panic_message(&*payload)));
+            std::ptr::null_mut()
+        }
+    }
+}
+
+pub(crate) fn take_fault() -> Option<String> {
+    FAULT.with(|f| f.borrow_mut().take())
+}
+
+// Texto de un panic (los payloads normales son &str o String).
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "panic sin mensaje".to_string()
+    }
+}
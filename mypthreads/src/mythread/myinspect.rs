@@ -0,0 +1,137 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::os::unix::io::RawFd;
+use crate::mythread::mythread::{MyThreadInfo, ThreadId};
+
+// Inspector del runtime: qué está haciendo cada hilo y quién tiene cada mutex. La foto sale con
+// MyTRuntime::dump (o Runtime::dump) y se puede pedir desde fuera con una señal (dump_on_signal).
+
+// Id de un MyMutex inicializado; 0 si nunca se inicializó
+pub type MutexId = usize;
+
+// Lo que espera un hilo Blocked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockedOn {
+    Join(ThreadId),
+    Mutex(MutexId),
+    Io(RawFd),
+    Channel,
+    // Tarea async esperando que alguien llame a su waker
+    Waker,
+    // Runtime::pause: esperando cualquier señal que no tenga enmascarada
+    Signal,
+}
+
+impl fmt::Display for BlockedOn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockedOn::Join(tid) => write!(f, "join {tid}"),
+            BlockedOn::Mutex(id) => write!(f, "mutex {id}"),
+            BlockedOn::Io(fd) => write!(f, "fd {fd}"),
+            BlockedOn::Channel => write!(f, "channel"),
+            BlockedOn::Waker => write!(f, "waker"),
+            BlockedOn::Signal => write!(f, "signal"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MyMutexInfo {
+    pub id: MutexId,
+    pub name: Option<String>,
+    pub owner: Option<ThreadId>,
+    // En el orden en que pidieron el lock
+    pub waiters: Vec<ThreadId>,
+}
+
+// Los mutex se registran al inicializarse y salen al destruirse o soltarse. El registro es del hilo
+// del SO, igual que el runtime; así un MyMutex se puede mover (p.ej. dentro de un BridgeBlock) sin
+// que se pierda.
+thread_local! {
+    static NEXT_MUTEX: Cell<MutexId> = const { Cell::new(1) };
+    static MUTEXES: RefCell<BTreeMap<MutexId, MyMutexInfo>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+pub(crate) fn register_mutex() -> MutexId {
+    let id = NEXT_MUTEX.with(|n| n.replace(n.get() + 1));
+    MUTEXES.with(|m| m.borrow_mut().insert(id, MyMutexInfo { id, name: None, owner: None, waiters: Vec::new() }));
+    id
+}
+
+// Copia el dueño y la cola de espera actuales. No hace nada si el mutex es de otro hilo del SO.
+pub(crate) fn publish_mutex(id: MutexId, owner: Option<ThreadId>, waiters: &VecDeque<ThreadId>) {
+    MUTEXES.with(|m| {
+        if let Some(info) = m.borrow_mut().get_mut(&id) {
+            info.owner = owner;
+            info.waiters = waiters.iter().copied().collect();
+        }
+    });
+}
+
+pub(crate) fn name_mutex(id: MutexId, name: &str) {
+    MUTEXES.with(|m| {
+        if let Some(info) = m.borrow_mut().get_mut(&id) {
+            info.name = Some(name.to_string());
+        }
+    });
+}
+
+pub(crate) fn forget_mutex(id: MutexId) {
+    MUTEXES.with(|m| m.borrow_mut().remove(&id));
+}
+
+// Mutex inicializados en este hilo del SO, por id.
+pub fn my_list_mutexes() -> Vec<MyMutexInfo> {
+    MUTEXES.with(|m| m.borrow().values().cloned().collect())
+}
+
+// Foto del runtime en un momento dado.
+#[derive(Debug, Clone)]
+pub struct MyRuntimeDump {
+    pub threads: Vec<MyThreadInfo>,
+    pub mutexes: Vec<MyMutexInfo>,
+    pub current: Option<ThreadId>,
+}
+
+impl MyRuntimeDump {
+    fn label(&self, tid: ThreadId) -> String {
+        match self.threads.iter().find(|t| t.id == tid).and_then(|t| t.name.as_deref()) {
+            Some(name) => format!("{tid} ({name})"),
+            None => tid.to_string(),
+        }
+    }
+}
+
+// Dos tablas estilo top: hilos y mutex.
+impl fmt::Display for MyRuntimeDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:>6} {:<24} {:<10} {:<16} {:>6} {:>10}  blocked on", "tid", "name", "state", "scheduler", "runs", "cpu")?;
+        for t in &self.threads {
+            let mark = if self.current == Some(t.id) { "*" } else { " " };
+            let blocked = match t.blocked_on {
+                Some(BlockedOn::Join(target)) => format!("join {}", self.label(target)),
+                Some(reason) => reason.to_string(),
+                None => String::new(),
+            };
+            writeln!(
+                f,
+                "{mark}{:>5} {:<24} {:<10} {:<16} {:>6} {:>10}  {blocked}",
+                t.id,
+                t.name.as_deref().unwrap_or("-"),
+                format!("{:?}", t.state),
+                format!("{:?}", t.scheduler),
+                t.runs,
+                format!("{:.3}ms", t.cpu_time.as_secs_f64() * 1000.0),
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{:>6} {:<24} {:<24}  waiters", "mutex", "name", "owner")?;
+        for m in &self.mutexes {
+            let owner = m.owner.map_or("-".to_string(), |o| self.label(o));
+            let waiters: Vec<String> = m.waiters.iter().map(|&w| self.label(w)).collect();
+            writeln!(f, "{:>6} {:<24} {:<24}  {}", m.id, m.name.as_deref().unwrap_or("-"), owner, waiters.join(", "))?;
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
 use std::collections::VecDeque;
 use std::os::raw::c_int;
 use std::sync::atomic::{AtomicBool, Ordering};
-use crate::mythread::mypthreadexits::Exits::{MutexInvalidOwner, MutexInvalidState, MutexLockApproved, MutexLocked, MutexNotInitialized, Ok, UnknownThread};
+use crate::mythread::myerror::MyError;
+use crate::mythread::myinspect::{forget_mutex, name_mutex, publish_mutex, register_mutex, MutexId};
 use crate::mythread::mythread::ThreadId;
 
 pub struct MyMutex {
@@ -9,6 +10,8 @@ pub struct MyMutex {
     pub(crate) owner: Option<ThreadId>,
     pub(crate) locked: AtomicBool,
     pub(crate) wait_queue: VecDeque<ThreadId>,
+    // Registro en el inspector (0 hasta que se inicializa)
+    pub(crate) id: MutexId,
 }
 
 impl MyMutex {
@@ -17,6 +20,20 @@ impl MyMutex {
         self.locked.load(Ordering::Acquire)
     }
 
+    pub fn id(&self) -> MutexId {
+        self.id
+    }
+
+    // Nombre con que aparece en el dump del runtime, p.ej. "bridge-252".
+    pub fn set_name(&self, name: &str) {
+        name_mutex(self.id, name);
+    }
+
+    // Le pasa al inspector el dueño y la cola actuales.
+    pub(crate) fn publish(&self) {
+        publish_mutex(self.id, self.owner, &self.wait_queue);
+    }
+
 }
 
 impl MyMutex {
@@ -26,6 +43,7 @@ impl MyMutex {
             owner: None,
             locked: AtomicBool::new(false),
             wait_queue: VecDeque::new(),
+            id: 0,
         }
     }
     pub unsafe fn init_mut(&mut self) -> c_int {
@@ -33,62 +51,88 @@ impl MyMutex {
         self.owner = None;
         self.wait_queue = VecDeque::new();
         self.initialized = true;
+        forget_mutex(self.id);
+        self.id = register_mutex();
 
-        Ok as c_int
+        0
     }
 
 
+    // EINVAL si no está inicializado, EBUSY si está tomado o alguien lo espera.
     pub unsafe fn destroy(&mut self) -> c_int {
-        if !self.initialized || self.locked.load(Ordering::Acquire) || !self.wait_queue.is_empty() {
-            return MutexInvalidState as c_int;
+        if !self.initialized {
+            return MyError::Invalid as c_int;
+        }
+        if self.locked.load(Ordering::Acquire) || !self.wait_queue.is_empty() {
+            return MyError::Busy as c_int;
         }
         self.initialized = false;
         self.owner = None;
+        forget_mutex(self.id);
+        self.id = 0;
 
-        Ok as c_int
+        0
     }
 
+    // 0 si `tid` lo tomó. Si está ocupado lo deja en la cola de espera y devuelve EBUSY; quien
+    // espera vuelve a llamar cuando se suelte. EDEADLK si `tid` ya es el dueño.
     pub fn lock(&mut self,tid: ThreadId) -> c_int {
         if !self.initialized {
-            return MutexNotInitialized as c_int;
+            return MyError::Invalid as c_int;
+        }
+        if self.owner == Some(tid) {
+            return MyError::Deadlock as c_int;
         }
 
-        if self.locked.load(Ordering::Acquire) {
+        // compare_exchange para que dos workers no puedan tomarlo a la vez
+        if self.locked.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire).is_err() {
             if !self.wait_queue.contains(&tid) {
                 self.wait_queue.push_back(tid);
+                self.publish();
             }
-            return MutexLocked as c_int;
+            return MyError::Busy as c_int;
         }
-        self.locked.swap(true, Ordering::AcqRel);
         self.owner = Some(tid);
+        self.wait_queue.retain(|&w| w != tid);
+        self.publish();
 
-        MutexLockApproved as c_int
+        0
     }
 
+    // EPERM si no estaba tomado o lo tiene otro hilo. Con `tid` None (desde fuera de los hilos, como
+    // hace la ciudad al sacar un vehículo del puente) lo suelta sin importar el dueño.
     pub fn unlock(&mut self, tid: Option<ThreadId>) -> c_int {
-      //  if tid.is_none() {
-       //     return UnknownThread as c_int;
-       // }
-       // if self.owner != tid {
-        //    return MutexInvalidOwner as c_int;
-        //}
+        if !self.initialized {
+            return MyError::Invalid as c_int;
+        }
+        if !self.is_locked() || tid.is_some_and(|t| self.owner != Some(t)) {
+            return MyError::NotPermitted as c_int;
+        }
         self.locked.store(false, Ordering::Release);
         self.owner = None;
+        self.publish();
 
-        Ok as c_int
+        0
     }
 
+    // Como lock pero sin anotarse en la cola: EBUSY si está tomado.
     pub fn try_lock(&mut self,tid: ThreadId) -> c_int {
         if !self.initialized {
-            return MutexNotInitialized as c_int;
+            return MyError::Invalid as c_int;
         }
-        if self.locked.load(Ordering::Acquire) {
-            return MutexLocked as c_int;
+        if self.locked.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire).is_err() {
+            return MyError::Busy as c_int;
         }
-        self.locked.swap(true, Ordering::AcqRel);
         self.owner = Some(tid);
+        self.publish();
+
+        0
+    }
+}
 
-        Ok as c_int
+impl Drop for MyMutex {
+    fn drop(&mut self) {
+        forget_mutex(self.id);
     }
 }
 
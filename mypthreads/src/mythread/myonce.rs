@@ -0,0 +1,88 @@
+use std::os::raw::c_int;
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use crate::mythread::myerror::MyError;
+use crate::mythread::mystack::{green_owner_tag, held_by_green_here, park};
+
+const INCOMPLETE: u8 = 0;
+const RUNNING: u8 = 1;
+const DONE: u8 = 2;
+
+// Control de inicialización única, como pthread_once_t. Se declara como
+// `static ONCE: MyOnce = MyOnce::new();`.
+pub struct MyOnce {
+    state: AtomicU8,
+    // green_owner_tag de quien corre `init`
+    runner: AtomicUsize,
+}
+
+// Si `init` hace panic la inicialización queda sin hacer, como un pthread_once cancelado: el
+// siguiente que llame la vuelve a intentar.
+struct ResetOnUnwind<'a>(&'a MyOnce);
+
+impl Drop for ResetOnUnwind<'_> {
+    fn drop(&mut self) {
+        self.0.runner.store(0, Ordering::Relaxed);
+        self.0.state.store(INCOMPLETE, Ordering::Release);
+    }
+}
+
+impl MyOnce {
+    pub const fn new() -> Self {
+        Self { state: AtomicU8::new(INCOMPLETE), runner: AtomicUsize::new(0) }
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.state.load(Ordering::Acquire) == DONE
+    }
+
+    // Corre `init` una sola vez. Quien llegue mientras otro inicializa espera a que termine,
+    // cediendo igual que MySpinLock. Llamarlo de nuevo desde dentro de `init` se queda esperando.
+    // Hace panic si espera desde el driver a un hilo verde de este hilo del SO (ver `try_call_once`).
+    pub fn call_once(&self, init: impl FnOnce()) {
+        let rc = self.try_call_once(init);
+        assert_eq!(rc, 0, "deadlock: la inicialización la tiene un hilo verde que no va a correr");
+    }
+
+    // Como `call_once`, pero EDEADLK si espera desde el driver mientras inicializa un hilo verde
+    // estacionado en este mismo hilo del SO: nadie lo va a despachar.
+    pub fn try_call_once(&self, init: impl FnOnce()) -> c_int {
+        let mut init = Some(init);
+        loop {
+            match self.state.compare_exchange(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Acquire) {
+                Result::Ok(_) => {
+                    self.runner.store(green_owner_tag(), Ordering::Relaxed);
+                    let reset = ResetOnUnwind(self);
+                    if let Some(init) = init.take() {
+                        init();
+                    }
+                    std::mem::forget(reset);
+                    self.runner.store(0, Ordering::Relaxed);
+                    self.state.store(DONE, Ordering::Release);
+                    return 0;
+                }
+                Err(DONE) => return 0,
+                Err(_) => {
+                    if park() {
+                        continue;
+                    }
+                    if held_by_green_here(self.runner.load(Ordering::Relaxed)) {
+                        return MyError::Deadlock as c_int;
+                    }
+                    std::thread::yield_now();
+                }
+            }
+        }
+    }
+}
+
+impl Default for MyOnce {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// pthread_once: `init_routine` corre una sola vez para `once` aunque lo llamen muchos hilos.
+// EDEADLK como en `MyOnce::try_call_once`.
+pub extern "C" fn my_once(once: &MyOnce, init_routine: extern "C" fn()) -> c_int {
+    once.try_call_once(|| init_routine())
+}
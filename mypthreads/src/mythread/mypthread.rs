@@ -1,11 +1,22 @@
-use std::os::raw::c_int;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_void};
+use std::os::unix::io::RawFd;
 use crate::mythread::mutexlockkind::MyMutexAttr;
 use crate::mythread::mymutex::{MyMutex};
-use crate::mythread::mypthreadexits::Exits::{Ok, MutexNotInitialized, NullMutex, ThreadBlocked, CurrentIsEmpty};
+use crate::mythread::myerror::{MyError, MyResult};
+use crate::mythread::myreplay::MyEventLog;
 use crate::mythread::myruntime::MyTRuntime;
-use crate::mythread::mythread::{AnyParam, MyTRoutine, ThreadId};
+use crate::mythread::mysignal::{MySigHandler, MySigSet};
+use crate::mythread::myspinlock::MySpinLock;
+use crate::mythread::mystack::park;
+use crate::mythread::myaging::{MyAging, MyAgingStats};
+use crate::mythread::myclock::SharedClock;
+use crate::mythread::myinspect::MyRuntimeDump;
+use crate::mythread::mythread::{AnyParam, MyTRoutine, MyThreadInfo, ThreadId, MY_DRIVER_THREAD};
 use crate::mythread::mythreadattr::{MyThreadAttr};
-use crate::scheduler::SchedulerType;
+use crate::scheduler::lottery::LotteryScheduler;
+use crate::scheduler::{GroupId, GroupScheduler, RealTimeScheduler, SchedulerType};
+use crate::Scheduler;
 
 pub struct MyPThread {
     pub(crate) runtime: MyTRuntime,
@@ -19,11 +30,20 @@ impl MyPThread {
         }
     }
 
+    // Con semilla la corrida se graba (ver myreplay) y se puede repetir con `replaying`.
+    pub fn with_seed(seed: u64) -> Self {
+        Self { runtime: MyTRuntime::with_seed(seed) }
+    }
+
+    pub fn replaying(log: MyEventLog) -> Self {
+        Self { runtime: MyTRuntime::replaying(log) }
+    }
+
 
     pub unsafe extern "C" fn my_thread_create(
         &mut self,
         thread: *mut ThreadId,
-        attr: *mut MyThreadAttr,
+        attr: *const MyThreadAttr,
         start_routine: MyTRoutine,
         arg: *mut AnyParam,
         scheduler: Option<SchedulerType>,
@@ -40,10 +60,14 @@ impl MyPThread {
     }
 
     pub unsafe extern "C" fn my_thread_yield(&mut self) -> c_int {
+        // Dentro de un hilo verde se estaciona y vuelve cuando el scheduler lo elija otra vez
+        if park() {
+            return 0;
+        }
         self.runtime.save_context();
         self.runtime.schedule_next();
 
-        Ok as c_int
+        0
     }
 
     pub unsafe extern "C" fn my_thread_end(&mut self, retval: *mut AnyParam) -> c_int {
@@ -51,14 +75,14 @@ impl MyPThread {
     }
 
     pub unsafe extern "C" fn my_thread_detach(&mut self, thread: ThreadId) -> c_int {
-        unsafe {
-            self.runtime.detach(thread)
-        }
+        self.runtime.detach(thread)
     }
 
+    // pthread_mutex_* con errores de un mutex PTHREAD_MUTEX_ERRORCHECK: EINVAL si es nulo o no está
+    // inicializado, EDEADLK si el dueño lo vuelve a pedir, EPERM si lo suelta quien no lo tiene.
     pub unsafe extern "C" fn my_mutex_init(&mut self, mutex: *mut MyMutex, attr: *const MyMutexAttr) -> c_int {
         if mutex.is_null() {
-            return MutexNotInitialized as c_int;
+            return MyError::Invalid as c_int;
         }
         unsafe {
             // Attr  ignorado
@@ -66,38 +90,381 @@ impl MyPThread {
         }
     }
 
+    // EBUSY si está tomado o alguien lo espera.
     pub unsafe extern "C" fn my_mutex_destroy(&mut self, mutex: *mut MyMutex) -> c_int {
-        unsafe {
-            (*mutex).destroy()
-        }
+        let Some(mutex) = (unsafe { mutex.as_mut() }) else { return MyError::Invalid as c_int };
+        unsafe { mutex.destroy() }
     }
 
+    // Si está tomado el hilo actual cede hasta que se suelte; desde el driver corre los hilos listos.
+    // EDEADLK si ya no queda nadie que pueda soltarlo.
     pub unsafe extern "C" fn my_mutex_lock(&mut self, mutex: *mut MyMutex) -> c_int {
-        if mutex.is_null() {
-            return NullMutex as c_int;
-        }
-        unsafe {
-            if let Some(tid) = self.runtime.get_current() {
-                (*mutex).lock(tid);
-            } else {
-                return CurrentIsEmpty as c_int;
+        let Some(mutex) = (unsafe { mutex.as_mut() }) else { return MyError::Invalid as c_int };
+        let me = self.my_thread_self();
+        loop {
+            let rc = mutex.lock(me);
+            if rc != MyError::Busy as c_int {
+                return rc;
+            }
+            if !park() && self.runtime.schedule_next() != 0 {
+                mutex.wait_queue.retain(|&w| w != me);
+                mutex.publish();
+                return MyError::Deadlock as c_int;
             }
         }
-        ThreadBlocked as c_int
     }
 
+    // EBUSY si está tomado.
+    /// # Safety
+    /// `mutex` debe ser nulo o apuntar a un MyMutex válido.
+    pub unsafe extern "C" fn my_mutex_trylock(&mut self, mutex: *mut MyMutex) -> c_int {
+        let Some(mutex) = (unsafe { mutex.as_mut() }) else { return MyError::Invalid as c_int };
+        mutex.try_lock(self.my_thread_self())
+    }
+
+    // Desde el driver suelta el mutex sea quien sea el dueño.
     pub unsafe extern "C" fn my_mutex_unlock(&mut self, mutex: *mut MyMutex) -> c_int {
-        if mutex.is_null() {
-            return NullMutex as c_int;
+        let Some(mutex) = (unsafe { mutex.as_mut() }) else { return MyError::Invalid as c_int };
+        mutex.unlock(self.runtime.get_current())
+    }
+
+
+    // EINVAL si `new_kind` es un SchedulerType::Custom que no se registró.
+    pub unsafe extern "C" fn my_thread_chsched(&mut self, thread: ThreadId, new_kind: SchedulerType) -> c_int {
+        self.runtime.change_scheduler(thread, new_kind)
+    }
+
+
+    // read/write/accept que estacionan al hilo actual en vez de bloquear todo el proceso.
+    // Dejan el fd en modo O_NONBLOCK. Devuelven lo mismo que la llamada de libc (-1 y errno en error).
+    ///
+    /// # Safety
+    /// `buf` debe ser válido para `count` bytes, igual que en `libc::read`/`libc::write`.
+    pub unsafe extern "C" fn my_read(&mut self, fd: RawFd, buf: *mut c_void, count: usize) -> isize {
+        self.runtime.io_read(fd, buf, count)
+    }
+
+    /// # Safety
+    /// Ver `my_read`.
+    pub unsafe extern "C" fn my_write(&mut self, fd: RawFd, buf: *const c_void, count: usize) -> isize {
+        self.runtime.io_write(fd, buf, count)
+    }
+
+    /// # Safety
+    /// `addr` y `len` deben ser nulos o válidos, igual que en `libc::accept`.
+    pub unsafe extern "C" fn my_accept(&mut self, fd: RawFd, addr: *mut libc::sockaddr, len: *mut libc::socklen_t) -> c_int {
+        self.runtime.io_accept(fd, addr, len)
+    }
+
+    // Señales entre hilos verdes. El handler corre dentro del hilo destino la próxima vez que se
+    // despacha; si el destino es el hilo actual corre antes de que my_thread_kill retorne.
+    // Devuelven 0 o un código de errno (EINVAL, ESRCH, EPERM).
+    ///
+    /// # Safety
+    /// No tiene requisitos extra; es `unsafe` como el resto de la API estilo C.
+    pub unsafe extern "C" fn my_thread_kill(&mut self, thread: ThreadId, sig: c_int) -> c_int {
+        let rc = self.runtime.kill(thread, sig);
+        if rc == 0 && self.runtime.get_current() == Some(thread) {
+            self.runtime.deliver_signals(thread);
+        }
+        rc
+    }
+
+    /// # Safety
+    /// `set` y `oldset` deben ser nulos o apuntar a un `MySigSet` válido.
+    pub unsafe extern "C" fn my_thread_sigmask(&mut self, how: c_int, set: *const MySigSet, oldset: *mut MySigSet) -> c_int {
+        let rc = self.runtime.sigmask(how, set, oldset);
+        // Lo que se acaba de desenmascarar se atiende de una vez
+        if let (0, Some(tid)) = (rc, self.runtime.get_current()) {
+            self.runtime.deliver_signals(tid);
+        }
+        rc
+    }
+
+    /// # Safety
+    /// El handler corre dentro del hilo destino; no debe volver a entrar a este MyPThread.
+    pub unsafe extern "C" fn my_signal(&mut self, sig: c_int, handler: MySigHandler) -> c_int {
+        self.runtime.set_signal_handler(sig, std::rc::Rc::new(move |sig, tid| handler(sig, tid)))
+    }
+
+    // Reenvía la señal `sig` del proceso (kill -USR1 <pid>) al hilo `thread`.
+    /// # Safety
+    /// Reemplaza la acción del proceso para `sig` (sigaction).
+    pub unsafe extern "C" fn my_signal_route(&mut self, sig: c_int, thread: ThreadId) -> c_int {
+        self.runtime.route_signal(sig, thread)
+    }
+
+    // Mensaje del panic de un hilo que terminó Faulted (my_thread_join devolvió MyError::Faulted).
+    pub fn my_thread_panic_message(&self, thread: ThreadId) -> Option<String> {
+        self.runtime.panic_message(thread).map(str::to_string)
+    }
+
+    // Nombres e introspección, al estilo de pthread_setname_np/pthread_self/pthread_equal.
+    // A diferencia de pthread el nombre no tiene límite de 16 bytes ("truck-plant(14,19)" no cabría).
+    ///
+    /// # Safety
+    /// `name` debe ser nulo o un string de C terminado en NUL.
+    pub unsafe extern "C" fn my_thread_setname(&mut self, thread: ThreadId, name: *const c_char) -> c_int {
+        if name.is_null() {
+            return MyError::Invalid as c_int;
+        }
+        let name = unsafe { CStr::from_ptr(name) }.to_string_lossy();
+        self.runtime.set_name(thread, &name)
+    }
+
+    /// Copia el nombre (con NUL) en `buf`; ERANGE si no cabe. Un hilo sin nombre da "".
+    ///
+    /// # Safety
+    /// `buf` debe ser válido para `len` bytes.
+    pub unsafe extern "C" fn my_thread_getname(&mut self, thread: ThreadId, buf: *mut c_char, len: usize) -> c_int {
+        if buf.is_null() {
+            return MyError::Invalid as c_int;
+        }
+        if self.runtime.get_state(thread).is_none() {
+            return MyError::NoSuchThread as c_int;
+        }
+        let name = self.runtime.name(thread).unwrap_or("").as_bytes();
+        if name.len() >= len {
+            return MyError::OutOfRange as c_int;
         }
         unsafe {
-            (*mutex).unlock(self.runtime.get_current())
+            std::ptr::copy_nonoverlapping(name.as_ptr(), buf as *mut u8, name.len());
+            *buf.add(name.len()) = 0;
         }
+        0
     }
 
+    // Hilo que está corriendo; MY_DRIVER_THREAD si se llama desde fuera de los hilos verdes.
+    pub extern "C" fn my_thread_self(&self) -> ThreadId {
+        self.runtime.get_current().unwrap_or(MY_DRIVER_THREAD)
+    }
 
-    pub unsafe extern "C" fn my_thread_chsched(&mut self, thread: ThreadId, new_kind: SchedulerType) -> c_int {
-        self.runtime.change_scheduler(thread, new_kind)
+    // Distinto de 0 si ambos ids son el mismo hilo.
+    pub extern "C" fn my_thread_equal(t1: ThreadId, t2: ThreadId) -> c_int {
+        (t1 == t2) as c_int
+    }
+
+    pub fn my_runtime_list_threads(&self) -> Vec<MyThreadInfo> {
+        self.runtime.list_threads()
+    }
+
+    // Hilos con lo que esperan y mutex con dueño y cola; se imprime con {}.
+    pub fn my_runtime_dump(&self) -> MyRuntimeDump {
+        self.runtime.dump()
+    }
+
+    // Con Some, el hilo que espera listo más de `threshold` despachos corre antes que cualquier clase.
+    pub fn my_runtime_setaging(&mut self, aging: Option<MyAging>) {
+        self.runtime.set_aging(aging);
+    }
+
+    pub fn my_runtime_aging_stats(&self) -> MyAgingStats {
+        self.runtime.aging_stats()
+    }
+
+    // Reloj del runtime; el mismo Arc se puede compartir con lo que corre encima.
+    pub fn my_runtime_setclock(&mut self, clock: SharedClock) {
+        self.runtime.set_clock(clock);
+    }
+
+    pub fn my_runtime_clock(&self) -> SharedClock {
+        self.runtime.clock()
+    }
+
+    pub fn my_runtime_time_ms(&self) -> usize {
+        self.runtime.time_ms()
+    }
+
+    // Imprime el dump en stderr cuando llega `sig` al proceso (kill -USR2 <pid>). EINVAL.
+    /// # Safety
+    /// Reemplaza la acción del proceso para `sig` (sigaction).
+    pub unsafe extern "C" fn my_runtime_dump_on(&mut self, sig: c_int) -> c_int {
+        self.runtime.dump_on_signal(sig)
+    }
+
+    // Grupos de scheduling anidados; ver GroupScheduler.
+    pub fn my_groups(&mut self) -> &mut GroupScheduler {
+        &mut self.runtime.groups
+    }
+
+    // Mueve un hilo a otro grupo (None lo devuelve a los schedulers normales). EINVAL o ESRCH.
+    pub fn my_thread_setgroup(&mut self, thread: ThreadId, group: Option<GroupId>) -> c_int {
+        self.runtime.set_group(thread, group)
+    }
+
+    // Registra una implementación propia de Scheduler; sus hilos se crean con el SchedulerType que
+    // devuelve. None si ya hay un scheduler con ese nombre.
+    pub fn my_sched_register(&mut self, name: &str, scheduler: Box<dyn Scheduler>) -> Option<SchedulerType> {
+        self.runtime.register_scheduler(name, scheduler)
+    }
+
+    // Monedas y tickets del scheduler Lottery; ver LotteryScheduler.
+    pub fn my_lottery(&mut self) -> &mut LotteryScheduler {
+        self.runtime.lottery()
+    }
+
+    // Servidores aperiódicos del scheduler RealTime; ver RealTimeScheduler::create_server.
+    pub fn my_real_time(&mut self) -> &mut RealTimeScheduler {
+        self.runtime.real_time()
+    }
+
+    // pthread_spin_*. Devuelven 0 o un código de errno.
+    ///
+    /// # Safety
+    /// `lock` debe ser nulo o apuntar a un MySpinLock válido.
+    pub unsafe extern "C" fn my_spin_init(&mut self, lock: *mut MySpinLock) -> c_int {
+        let Some(lock) = (unsafe { lock.as_mut() }) else { return MyError::Invalid as c_int };
+        *lock = MySpinLock::new();
+        0
+    }
+
+    /// # Safety
+    /// Ver `my_spin_init`.
+    pub unsafe extern "C" fn my_spin_destroy(&mut self, lock: *mut MySpinLock) -> c_int {
+        let Some(lock) = (unsafe { lock.as_ref() }) else { return MyError::Invalid as c_int };
+        if lock.is_locked() { MyError::Busy as c_int } else { 0 }
+    }
+
+    /// Desde el driver, en vez de estacionarse corre hilos listos; si no queda ninguno que
+    /// pueda soltar el lock devuelve EDEADLK.
+    ///
+    /// # Safety
+    /// Ver `my_spin_init`.
+    pub unsafe extern "C" fn my_spin_lock(&mut self, lock: *mut MySpinLock) -> c_int {
+        let Some(lock) = (unsafe { lock.as_ref() }) else { return MyError::Invalid as c_int };
+        lock.lock_with(|| park() || self.runtime.schedule_next() == 0)
+    }
+
+    /// # Safety
+    /// Ver `my_spin_init`.
+    pub unsafe extern "C" fn my_spin_trylock(&mut self, lock: *mut MySpinLock) -> c_int {
+        let Some(lock) = (unsafe { lock.as_ref() }) else { return MyError::Invalid as c_int };
+        lock.try_lock()
+    }
+
+    /// # Safety
+    /// Ver `my_spin_init`.
+    pub unsafe extern "C" fn my_spin_unlock(&mut self, lock: *mut MySpinLock) -> c_int {
+        let Some(lock) = (unsafe { lock.as_ref() }) else { return MyError::Invalid as c_int };
+        lock.unlock()
+    }
+
+}
+
+// La misma API con MyResult en vez de códigos. Casi todas llaman a su my_* y convierten el errno,
+// así las dos no se separan.
+impl MyPThread {
+    /// # Safety
+    /// `arg` llega tal cual a `start_routine`, que debe poder usarlo cuando corra.
+    pub unsafe fn create(&mut self, attr: Option<&MyThreadAttr>, start_routine: MyTRoutine, arg: *mut AnyParam, scheduler: Option<SchedulerType>) -> MyResult<ThreadId> {
+        let mut tid = MY_DRIVER_THREAD;
+        let attr = attr.map_or(std::ptr::null(), |a| a as *const MyThreadAttr);
+        MyError::check(unsafe { self.my_thread_create(&mut tid, attr, start_routine, arg, scheduler) })?;
+        Ok(tid)
+    }
+
+    // Devuelve el ret_val del hilo.
+    pub fn join(&mut self, thread: ThreadId) -> MyResult<*mut AnyParam> {
+        let mut ret: *mut AnyParam = std::ptr::null_mut();
+        MyError::check(unsafe { self.my_thread_join(thread, &mut ret) })?;
+        Ok(ret)
+    }
+
+    pub fn yield_now(&mut self) {
+        unsafe { self.my_thread_yield() };
+    }
+
+    /// # Safety
+    /// `retval` le llega a quien haga join; debe seguir siendo válido hasta entonces.
+    pub unsafe fn end(&mut self, retval: *mut AnyParam) -> MyResult<()> {
+        MyError::check(unsafe { self.my_thread_end(retval) })
+    }
+
+    pub fn detach(&mut self, thread: ThreadId) -> MyResult<()> {
+        MyError::check(unsafe { self.my_thread_detach(thread) })
+    }
+
+    pub fn chsched(&mut self, thread: ThreadId, new_kind: SchedulerType) -> MyResult<()> {
+        MyError::check(unsafe { self.my_thread_chsched(thread, new_kind) })
     }
 
+    pub fn set_group(&mut self, thread: ThreadId, group: Option<GroupId>) -> MyResult<()> {
+        MyError::check(self.my_thread_setgroup(thread, group))
+    }
+
+    pub fn mutex_init(&mut self, mutex: &mut MyMutex) -> MyResult<()> {
+        MyError::check(unsafe { self.my_mutex_init(mutex, std::ptr::null()) })
+    }
+
+    pub fn mutex_destroy(&mut self, mutex: &mut MyMutex) -> MyResult<()> {
+        MyError::check(unsafe { self.my_mutex_destroy(mutex) })
+    }
+
+    pub fn mutex_lock(&mut self, mutex: &mut MyMutex) -> MyResult<()> {
+        MyError::check(unsafe { self.my_mutex_lock(mutex) })
+    }
+
+    pub fn mutex_trylock(&mut self, mutex: &mut MyMutex) -> MyResult<()> {
+        MyError::check(unsafe { self.my_mutex_trylock(mutex) })
+    }
+
+    pub fn mutex_unlock(&mut self, mutex: &mut MyMutex) -> MyResult<()> {
+        MyError::check(unsafe { self.my_mutex_unlock(mutex) })
+    }
+
+    pub fn kill(&mut self, thread: ThreadId, sig: c_int) -> MyResult<()> {
+        MyError::check(unsafe { self.my_thread_kill(thread, sig) })
+    }
+
+    // Devuelve la máscara anterior. Con `set` None solo la consulta.
+    pub fn sigmask(&mut self, how: c_int, set: Option<MySigSet>) -> MyResult<MySigSet> {
+        let mut old: MySigSet = 0;
+        let set = set.as_ref().map_or(std::ptr::null(), |s| s as *const MySigSet);
+        MyError::check(unsafe { self.my_thread_sigmask(how, set, &mut old) })?;
+        Ok(old)
+    }
+
+    /// # Safety
+    /// Ver `my_signal`.
+    pub unsafe fn signal(&mut self, sig: c_int, handler: MySigHandler) -> MyResult<()> {
+        MyError::check(unsafe { self.my_signal(sig, handler) })
+    }
+
+    /// # Safety
+    /// Ver `my_signal_route`.
+    pub unsafe fn signal_route(&mut self, sig: c_int, thread: ThreadId) -> MyResult<()> {
+        MyError::check(unsafe { self.my_signal_route(sig, thread) })
+    }
+
+    // Sin el límite de los strings de C: el nombre puede traer cualquier cosa.
+    pub fn setname(&mut self, thread: ThreadId, name: &str) -> MyResult<()> {
+        MyError::check(self.runtime.set_name(thread, name))
+    }
+
+    pub fn getname(&self, thread: ThreadId) -> MyResult<String> {
+        self.runtime.get_state(thread).ok_or(MyError::NoSuchThread)?;
+        Ok(self.runtime.name(thread).unwrap_or("").to_string())
+    }
+
+    pub fn spin_destroy(&mut self, lock: &MySpinLock) -> MyResult<()> {
+        MyError::check(unsafe { self.my_spin_destroy(lock as *const MySpinLock as *mut MySpinLock) })
+    }
+
+    pub fn spin_lock(&mut self, lock: &MySpinLock) -> MyResult<()> {
+        MyError::check(unsafe { self.my_spin_lock(lock as *const MySpinLock as *mut MySpinLock) })
+    }
+
+    pub fn spin_trylock(&mut self, lock: &MySpinLock) -> MyResult<()> {
+        MyError::check(unsafe { self.my_spin_trylock(lock as *const MySpinLock as *mut MySpinLock) })
+    }
+
+    pub fn spin_unlock(&mut self, lock: &MySpinLock) -> MyResult<()> {
+        MyError::check(unsafe { self.my_spin_unlock(lock as *const MySpinLock as *mut MySpinLock) })
+    }
+
+    /// # Safety
+    /// Ver `my_runtime_dump_on`.
+    pub unsafe fn dump_on(&mut self, sig: c_int) -> MyResult<()> {
+        MyError::check(unsafe { self.my_runtime_dump_on(sig) })
+    }
 }
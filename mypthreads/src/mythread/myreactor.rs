@@ -0,0 +1,131 @@
+use std::collections::{HashMap, HashSet};
+use std::os::raw::c_int;
+use std::os::unix::io::RawFd;
+use libc::{epoll_event, EPOLLERR, EPOLLHUP, EPOLLIN, EPOLLONESHOT, EPOLLOUT, EPOLL_CTL_ADD, EPOLL_CTL_MOD};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Interest {
+    Read,
+    Write,
+}
+
+impl Interest {
+    fn events(self) -> u32 {
+        match self {
+            Interest::Read => EPOLLIN,
+            Interest::Write => EPOLLOUT,
+        }
+    }
+}
+
+// Reactor de epoll del runtime: los hilos que harían un read/write bloqueante registran
+// el fd aquí y quedan Blocked; cuando no hay nada más que correr el runtime duerme en epoll_wait.
+pub struct MyReactor {
+    epfd: RawFd,
+    // Direcciones armadas por fd (EPOLLIN/EPOLLOUT). Se registran ONESHOT, así que al dispararse
+    // una se desarma todo el fd y `poll` vuelve a armar la que sigue pendiente
+    interests: HashMap<RawFd, u32>,
+    ready: HashSet<(RawFd, Interest)>,
+}
+
+impl MyReactor {
+    pub fn new() -> Self {
+        let epfd = unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) };
+        Self { epfd, interests: HashMap::new(), ready: HashSet::new() }
+    }
+
+    // Pide que se avise cuando `fd` esté listo para `interest`. Devuelve 0 o -1 (errno queda puesto).
+    pub fn register(&mut self, fd: RawFd, interest: Interest) -> c_int {
+        let known = self.interests.contains_key(&fd);
+        let events = self.interests.get(&fd).copied().unwrap_or(0) | interest.events();
+        let mut ev = epoll_event { events: events | EPOLLONESHOT, u64: fd as u64 };
+
+        let mut rc = unsafe { libc::epoll_ctl(self.epfd, if known { EPOLL_CTL_MOD } else { EPOLL_CTL_ADD }, fd, &mut ev) };
+        if rc != 0 {
+            // El fd pudo haberse cerrado y reabierto (ENOENT) o seguir en epoll tras dispararse (EEXIST)
+            let op = if known { EPOLL_CTL_ADD } else { EPOLL_CTL_MOD };
+            rc = unsafe { libc::epoll_ctl(self.epfd, op, fd, &mut ev) };
+        }
+        if rc == 0 {
+            self.interests.insert(fd, events);
+        }
+        rc
+    }
+
+    // Consume el aviso de que `fd` está listo (si llegó).
+    pub fn take_ready(&mut self, fd: RawFd, interest: Interest) -> bool {
+        self.ready.remove(&(fd, interest))
+    }
+
+    pub fn has_interests(&self) -> bool {
+        !self.interests.is_empty()
+    }
+
+    // epoll_wait con `timeout_ms` (-1 = sin límite). Devuelve cuántos fds quedaron listos.
+    pub fn poll(&mut self, timeout_ms: c_int) -> usize {
+        let mut events = [epoll_event { events: 0, u64: 0 }; 32];
+        let n = unsafe { libc::epoll_wait(self.epfd, events.as_mut_ptr(), events.len() as c_int, timeout_ms) };
+        if n <= 0 {
+            return 0;
+        }
+
+        for ev in &events[..n as usize] {
+            let fd = ev.u64 as RawFd;
+            let fired = ev.events;
+            // Un error o cierre despierta a todos los que esperaban el fd para que vean el resultado
+            let done = if fired & (EPOLLERR | EPOLLHUP) != 0 { EPOLLIN | EPOLLOUT } else { fired & (EPOLLIN | EPOLLOUT) };
+            self.mark_ready(fd, done);
+
+            let pending = self.interests.remove(&fd).unwrap_or(0) & !done;
+            if pending != 0 {
+                // Quien espera la otra dirección sigue esperando: se vuelve a armar solo esa
+                let mut ev = epoll_event { events: pending | EPOLLONESHOT, u64: fd as u64 };
+                if unsafe { libc::epoll_ctl(self.epfd, EPOLL_CTL_MOD, fd, &mut ev) } == 0 {
+                    self.interests.insert(fd, pending);
+                } else {
+                    // Sin poder armarlo (el fd se cerró) se le avisa igual: al reintentar verá el error
+                    self.mark_ready(fd, pending);
+                }
+            }
+        }
+        n as usize
+    }
+
+    fn mark_ready(&mut self, fd: RawFd, events: u32) {
+        for interest in [Interest::Read, Interest::Write] {
+            if events & interest.events() != 0 {
+                self.ready.insert((fd, interest));
+            }
+        }
+    }
+}
+
+impl Default for MyReactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for MyReactor {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.epfd);
+        }
+    }
+}
+
+pub(crate) fn set_nonblocking(fd: RawFd) {
+    unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+        if flags >= 0 && flags & libc::O_NONBLOCK == 0 {
+            libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK);
+        }
+    }
+}
+
+pub(crate) fn would_block() -> bool {
+    matches!(
+        std::io::Error::last_os_error().raw_os_error(),
+        Some(libc::EAGAIN) | Some(libc::EINTR)
+    )
+}
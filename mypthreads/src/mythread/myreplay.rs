@@ -0,0 +1,230 @@
+use std::cell::RefCell;
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+use crate::mythread::mythread::ThreadId;
+
+// Semilla por defecto: la misma que usaba el LotteryScheduler antes de tener semilla global.
+pub const MY_DEFAULT_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+// Un evento de la bitácora: qué hilo eligió el runtime o qué número aleatorio se sacó.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MyEvent {
+    Pick(ThreadId),
+    Draw(u64),
+}
+
+// Corrida grabada: la semilla con que empezó y todas sus decisiones, en orden.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MyEventLog {
+    pub seed: u64,
+    pub events: Vec<MyEvent>,
+}
+
+impl MyEventLog {
+    // Formato de texto, una línea por evento: "seed 42", "pick 3", "draw 123".
+    pub fn to_text(&self) -> String {
+        let mut out = format!("seed {}\n", self.seed);
+        for event in &self.events {
+            let _ = match event {
+                MyEvent::Pick(tid) => writeln!(out, "pick {tid}"),
+                MyEvent::Draw(value) => writeln!(out, "draw {value}"),
+            };
+        }
+        out
+    }
+
+    pub fn from_text(text: &str) -> io::Result<Self> {
+        let bad = |n: usize, line: &str| io::Error::new(io::ErrorKind::InvalidData, format!("línea {}: {line:?}", n + 1));
+        let mut log = MyEventLog::default();
+        let mut seen_seed = false;
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (kind, value) = line.split_once(' ').ok_or_else(|| bad(n, line))?;
+            let value: u64 = value.trim().parse().map_err(|_| bad(n, line))?;
+            match kind {
+                "seed" if !seen_seed => {
+                    log.seed = value;
+                    seen_seed = true;
+                }
+                "pick" => log.events.push(MyEvent::Pick(value as ThreadId)),
+                "draw" => log.events.push(MyEvent::Draw(value)),
+                _ => return Err(bad(n, line)),
+            }
+        }
+        if !seen_seed {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "falta la línea seed"));
+        }
+        Ok(log)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_text())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_text(&std::fs::read_to_string(path)?)
+    }
+}
+
+enum Mode {
+    // Solo genera números con la semilla, sin guardar nada
+    Free,
+    Record,
+    // Vuelve a entregar los eventos de `log`; `pos` es el siguiente a consumir
+    Replay { pos: usize },
+}
+
+// Sesión de reproducción del hilo del SO. El runtime es de un solo hilo del SO, así que cada
+// runtime (y lo que corre sobre él) comparte la de su hilo; crear uno con semilla la reinicia.
+struct Session {
+    mode: Mode,
+    rng_state: u64,
+    log: MyEventLog,
+    diverged: Option<usize>,
+}
+
+impl Session {
+    fn new(seed: u64, mode: Mode, log: MyEventLog) -> Self {
+        Self { mode, rng_state: seed, log, diverged: None }
+    }
+
+    fn replay_pos(&self) -> Option<usize> {
+        match self.mode {
+            Mode::Replay { pos } => Some(pos),
+            _ => None,
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        splitmix64(&mut self.rng_state)
+    }
+
+    // Anota `event` o, en replay, lo compara con lo grabado. Devuelve lo que estaba grabado.
+    fn note(&mut self, event: MyEvent) -> Option<MyEvent> {
+        match self.mode {
+            Mode::Free => None,
+            Mode::Record => {
+                self.log.events.push(event);
+                None
+            }
+            Mode::Replay { ref mut pos } => {
+                let recorded = self.log.events.get(*pos).copied();
+                if recorded != Some(event) && self.diverged.is_none() {
+                    self.diverged = Some(*pos);
+                }
+                *pos += 1;
+                recorded
+            }
+        }
+    }
+}
+
+// splitmix64, igual que el LotteryScheduler: https://prng.di.unimi.it/splitmix64.c
+pub(crate) fn splitmix64(state: &mut u64) -> u64 {
+    let mut z = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    *state = z;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+thread_local! {
+    static SESSION: RefCell<Session> = RefCell::new(Session::new(MY_DEFAULT_SEED, Mode::Free, MyEventLog { seed: MY_DEFAULT_SEED, events: Vec::new() }));
+}
+
+// Empieza a grabar desde cero con `seed`.
+pub fn my_replay_record(seed: u64) {
+    let log = MyEventLog { seed, events: Vec::new() };
+    SESSION.with(|s| *s.borrow_mut() = Session::new(seed, Mode::Record, log));
+}
+
+// Vuelve a correr una grabación: misma semilla, cada sorteo devuelve el valor grabado y el runtime
+// despacha el hilo grabado mientras esté listo (si no, elige su scheduler y queda la divergencia).
+pub fn my_replay_start(log: MyEventLog) {
+    SESSION.with(|s| *s.borrow_mut() = Session::new(log.seed, Mode::Replay { pos: 0 }, log));
+}
+
+// Termina la grabación o el replay y devuelve la bitácora. Se sigue generando con la misma secuencia.
+pub fn my_replay_stop() -> MyEventLog {
+    SESSION.with(|s| {
+        let mut s = s.borrow_mut();
+        s.mode = Mode::Free;
+        let seed = s.log.seed;
+        std::mem::replace(&mut s.log, MyEventLog { seed, events: Vec::new() })
+    })
+}
+
+// Copia de lo grabado hasta ahora (o de la grabación que se está reproduciendo).
+pub fn my_replay_log() -> MyEventLog {
+    SESSION.with(|s| s.borrow().log.clone())
+}
+
+pub fn my_replay_seed() -> u64 {
+    SESSION.with(|s| s.borrow().log.seed)
+}
+
+// Índice del primer evento en que el replay no coincidió con lo grabado (None si va igual).
+pub fn my_replay_diverged() -> Option<usize> {
+    SESSION.with(|s| s.borrow().diverged)
+}
+
+// Eventos de la grabación que el replay todavía no consumió.
+pub fn my_replay_remaining() -> usize {
+    SESSION.with(|s| {
+        let s = s.borrow();
+        match s.mode {
+            Mode::Replay { pos } => s.log.events.len().saturating_sub(pos),
+            _ => 0,
+        }
+    })
+}
+
+// Número aleatorio de la sesión. En replay devuelve el valor grabado para que la corrida
+// sea idéntica aunque alguien más haya sacado números en otro orden.
+pub fn my_random_u64() -> u64 {
+    SESSION.with(|s| {
+        let mut s = s.borrow_mut();
+        let value = s.next_u64();
+        match s.note(MyEvent::Draw(value)) {
+            Some(MyEvent::Draw(recorded)) => recorded,
+            _ => value,
+        }
+    })
+}
+
+// Uniforme en 0..n (0 si n es 0).
+pub fn my_random_below(n: u64) -> u64 {
+    // toma la parte alta de (rand * n) para evitar sesgo
+    (((my_random_u64() as u128) * (n as u128)) >> 64) as u64
+}
+
+// Uniforme en [0, 1).
+pub fn my_random_f64() -> f64 {
+    (my_random_u64() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+pub fn my_random_bool(p: f64) -> bool {
+    my_random_f64() < p
+}
+
+// En replay, el hilo que la grabación despachó en este punto (None si lo que sigue no es un pick).
+pub(crate) fn recorded_pick() -> Option<ThreadId> {
+    SESSION.with(|s| {
+        let s = s.borrow();
+        match s.log.events.get(s.replay_pos()?) {
+            Some(MyEvent::Pick(tid)) => Some(*tid),
+            _ => None,
+        }
+    })
+}
+
+// El runtime anota cada hilo que despacha.
+pub(crate) fn note_pick(tid: ThreadId) {
+    SESSION.with(|s| {
+        s.borrow_mut().note(MyEvent::Pick(tid));
+    });
+}
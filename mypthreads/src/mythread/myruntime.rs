@@ -1,24 +1,63 @@
-use std::collections::{HashMap, VecDeque};
-use std::os::raw::c_int;
-use crate::mythread::mypthreadexits::Exits::{Ok, ThreadIsTerminated, UnknownThread};
-use crate::mythread::mythread::{AnyParam, MyTRoutine, MyThread, ThreadId};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::os::raw::{c_int, c_void};
+use std::os::unix::io::RawFd;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Instant;
+use crate::mythread::myerror::MyError;
+use crate::mythread::mythread::{AnyParam, MyTRoutine, MyThread, MyThreadInfo, ThreadId};
 use crate::mythread::mythreadattr::MyThreadAttr;
+use crate::mythread::myclock::{SharedClock, VirtualClock};
+use crate::mythread::myaging::{MyAging, MyAgingStats, MY_REALTIME_RANK};
+use crate::mythread::myinspect::{my_list_mutexes, BlockedOn, MyRuntimeDump};
+use crate::mythread::myreplay::{my_replay_record, my_replay_start, note_pick, recorded_pick, MyEventLog};
+use crate::mythread::myreactor::{set_nonblocking, would_block, Interest, MyReactor};
+use crate::mythread::mystack::{park, run_routine, MyStack, RunOutcome, StackRegion};
+use crate::mythread::mysignal::{install_process_handler, is_valid_signal, my_sigbit, signals_in, take_process_pending, MySigSet, MY_SIG_BLOCK, MY_SIG_SETMASK, MY_SIG_UNBLOCK};
 use crate::mythread::thread_state::ThreadState;
 use crate::Scheduler;
-use crate::scheduler::SchedulerType;
+use crate::scheduler::{GroupId, GroupScheduler, SchedulerId, SchedulerType};
 
-use crate::scheduler::{round_robin::RRScheduler, lottery::LotteryScheduler, real_time::RealTimeScheduler};
+use crate::scheduler::{round_robin::RRScheduler, lottery::LotteryScheduler, real_time::RealTimeScheduler, FixedPriorityScheduler};
 pub struct MyTRuntime {
-    pub(crate) time_ms: usize,
+    // Reloj de la corrida (VirtualClock por defecto); ver myclock
+    pub(crate) clock: SharedClock,
     pub(crate) run_queue: VecDeque<ThreadId>,
     pub(crate) threads: HashMap<ThreadId, MyThread>,
     pub(crate) next_id: ThreadId,
     pub(crate) current: Option<ThreadId>,
     pub(crate) wait_on: HashMap<ThreadId, Vec<ThreadId>>, // target -> waiters
-    schedulers: HashMap<SchedulerType, Box<dyn Scheduler>>
-
+    schedulers: HashMap<SchedulerType, Box<dyn Scheduler>>,
+    // Nombre de cada scheduler, en orden de registro; los propios se escogen en este orden
+    scheduler_names: Vec<(String, SchedulerType)>,
+    // Hilos con grupo (attr.group); van después de RealTime y FixedPriority y antes de Lottery y RoundRobin
+    pub(crate) groups: GroupScheduler,
+    pub(crate) reactor: MyReactor,
+    // Hilos estacionados hasta que el reactor vea su fd listo en esa dirección; los despierta poll_io
+    io_waiters: HashMap<(RawFd, Interest), Vec<ThreadId>>,
+    sig_handlers: HashMap<c_int, SignalHandler>,
+    sig_routes: HashMap<c_int, ThreadId>, // señal del proceso -> hilo que la atiende
+    pub(crate) chooser: Option<Chooser>,
+    // Hilos corriendo uno encima del otro (join o lock que despacha a otros) y desde cuándo se
+    // les está contando el CPU; solo corre el reloj del de arriba
+    run_clock: Vec<(ThreadId, Instant)>,
+    // Señal del proceso que imprime el dump en stderr
+    dump_signal: Option<c_int>,
+    // None: cada scheduler decide solo, aunque algún hilo espere para siempre
+    aging: Option<MyAging>,
+    aging_stats: MyAgingStats,
+    // (ready_since, tid) de los hilos listos, el que más espera primero; el aging solo revisa el
+    // principio. Puede tener entradas viejas (se limpian al encontrarlas)
+    ready_order: BTreeSet<(usize, ThreadId)>,
 }
 
+// Reemplaza a los schedulers al escoger el siguiente hilo: recibe los listos (en el orden en que
+// los habrían dado los schedulers) y devuelve el índice del que corre, o None para no correr más.
+pub(crate) type Chooser = Box<dyn FnMut(&[ThreadId]) -> Option<usize>>;
+
+// Handler guardado por el runtime; la API de C envuelve su `extern "C" fn` en uno de estos.
+pub(crate) type SignalHandler = Rc<dyn Fn(c_int, ThreadId)>;
+
 impl MyTRuntime {
     pub fn new() -> Self {
 
@@ -26,29 +65,84 @@ impl MyTRuntime {
         schedulers.insert(SchedulerType::RoundRobin, Box::new(RRScheduler::new()));
         schedulers.insert(SchedulerType::Lottery,   Box::new(LotteryScheduler::new()));
         schedulers.insert(SchedulerType::RealTime,   Box::new(RealTimeScheduler::new()));
+        schedulers.insert(SchedulerType::FixedPriority, Box::new(FixedPriorityScheduler::new()));
 
         Self {
-            time_ms: 0,
+            clock: Arc::new(VirtualClock::new()),
             threads: HashMap::new(),
             run_queue: VecDeque::new(),
             next_id: 0,
             current: None,
             wait_on: HashMap::new(),
             schedulers,
+            scheduler_names: SchedulerType::BUILTIN.iter().map(|&(name, kind)| (name.to_string(), kind)).collect(),
+            groups: GroupScheduler::new(),
+            reactor: MyReactor::new(),
+            io_waiters: HashMap::new(),
+            sig_handlers: HashMap::new(),
+            sig_routes: HashMap::new(),
+            chooser: None,
+            run_clock: Vec::new(),
+            dump_signal: None,
+            aging: None,
+            aging_stats: MyAgingStats::default(),
+            ready_order: BTreeSet::new(),
+        }
+    }
+
+    // Runtime que graba cada decisión y sorteo de la corrida a partir de `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        my_replay_record(seed);
+        Self::new()
+    }
+
+    // Runtime que vuelve a correr `log` tal cual se grabó.
+    pub fn replaying(log: MyEventLog) -> Self {
+        my_replay_start(log);
+        Self::new()
+    }
+
+
+
+    // Agrega un scheduler propio bajo `name`; los hilos lo usan con el SchedulerType que devuelve.
+    // None si el nombre ya existe o no quedan ids.
+    pub fn register_scheduler(&mut self, name: &str, scheduler: Box<dyn Scheduler>) -> Option<SchedulerType> {
+        if self.scheduler_by_name(name).is_some() {
+            return None;
         }
+        let used = self.scheduler_names.iter().filter(|(_, k)| matches!(k, SchedulerType::Custom(_))).count();
+        let kind = SchedulerType::Custom(SchedulerId::try_from(used).ok()?);
+        self.schedulers.insert(kind, scheduler);
+        self.scheduler_names.push((name.to_string(), kind));
+        Some(kind)
     }
 
+    pub fn scheduler_by_name(&self, name: &str) -> Option<SchedulerType> {
+        self.scheduler_names.iter().find(|(n, _)| n == name).map(|&(_, kind)| kind)
+    }
 
+    pub fn scheduler_name(&self, kind: SchedulerType) -> Option<&str> {
+        self.scheduler_names.iter().find(|&&(_, k)| k == kind).map(|(n, _)| n.as_str())
+    }
 
+    // Para llegar a la API propia de un scheduler (con as_any).
+    pub fn scheduler_mut(&mut self, kind: SchedulerType) -> Option<&mut dyn Scheduler> {
+        Some(self.schedulers.get_mut(&kind)?.as_mut())
+    }
+
+    // EINVAL si `new_kind` no está registrado, ESRCH si el hilo no existe o ya terminó.
     pub fn change_scheduler(&mut self, tid: ThreadId, new_kind: SchedulerType) -> c_int {
+        if !self.schedulers.contains_key(&new_kind) {
+            return MyError::Invalid as c_int;
+        }
         // Validaciones básicas
         let t = match self.threads.get_mut(&tid) {
             Some(t) => t,
-            None => return -1,
+            None => return MyError::NoSuchThread as c_int,
         };
 
-        if t.state == ThreadState::Terminated {
-            return -1;
+        if t.state.is_finished() {
+            return MyError::NoSuchThread as c_int;
         }
 
         // Para ver si es el mismo
@@ -57,55 +151,135 @@ impl MyTRuntime {
             return 0;
         }
 
-        // Cambiar el tipo de scheduler del hilo
-        t.scheduler = new_kind;
-
-        // Reconstruir colas de los que están en ready de los schedulers para asegurar consistencia
-        self.rebuild_ready_queues();
+        // Si estaba en una cola pasa a la del nuevo scheduler; los demás hilos no se mueven
+        let queued = self.dequeue_ready(tid);
+        if let Some(t) = self.threads.get_mut(&tid) {
+            t.scheduler = new_kind;
+        }
+        if queued {
+            self.enqueue_ready(tid);
+        }
 
         0
     }
 
+    // Scheduler que atiende a `tid`: el de su grupo si tiene uno, si no el de su tipo.
+    fn scheduler_of(&mut self, tid: ThreadId) -> Option<&mut dyn Scheduler> {
+        let t = self.threads.get(&tid)?;
+        if t.attr.group.is_some() {
+            return Some(&mut self.groups);
+        }
+        Some(self.schedulers.get_mut(&t.scheduler)?.as_mut())
+    }
 
-    fn rebuild_ready_queues(&mut self) {
-        // Reinicializarlas
-        self.schedulers.insert(SchedulerType::RoundRobin, Box::new(RRScheduler::new()));
-        self.schedulers.insert(SchedulerType::Lottery,    Box::new(LotteryScheduler::new()));
-        self.schedulers.insert(SchedulerType::RealTime,   Box::new(RealTimeScheduler::new()));
+    // Saca a `tid` de la cola de listos en que esté. False si no estaba encolado.
+    fn dequeue_ready(&mut self, tid: ThreadId) -> bool {
+        self.get_state(tid) == Some(ThreadState::Ready) && self.scheduler_of(tid).is_some_and(|s| s.remove(tid))
+    }
 
-        // Reencolar hilos en estado Ready en su scheduler actual
-        for (&tid, t) in self.threads.iter() {
-            if t.state == ThreadState::Ready {
-                if let Some(s) = self.schedulers.get_mut(&t.scheduler) {
-                    s.enqueue(tid, t);
-                }
+    // Avisos on_block/on_wake al scheduler del hilo cuando se bloquea esperando algo y cuando vuelve.
+    pub(crate) fn block(&mut self, tid: ThreadId, reason: BlockedOn) {
+        self.set_state(tid, ThreadState::Blocked);
+        if let Some(t) = self.threads.get_mut(&tid) {
+            t.blocked_on = Some(reason);
+        }
+        if let Some(s) = self.scheduler_of(tid) {
+            s.on_block(tid);
+        }
+    }
+
+    pub(crate) fn note_wake(&mut self, tid: ThreadId) {
+        if let Some(s) = self.scheduler_of(tid) {
+            s.on_wake(tid);
+        }
+    }
+
+    // Encola a `tid` en su grupo si tiene uno, si no en el scheduler de su tipo.
+    fn enqueue_ready(&mut self, tid: ThreadId) -> bool {
+        let now = self.aging_stats.dispatches;
+        let Some(t) = self.threads.get_mut(&tid) else { return false };
+        // Si solo cambia de cola (de scheduler o de grupo) conserva lo que ya esperó
+        if t.ready_since.is_none() {
+            t.ready_since = Some(now);
+            self.ready_order.insert((now, tid));
+        }
+        let t = &*t;
+        if t.attr.group.is_some() {
+            self.groups.enqueue(tid, t);
+            return true;
+        }
+        match self.schedulers.get_mut(&t.scheduler) {
+            Some(s) => {
+                s.enqueue(tid, t);
+                true
             }
+            None => false,
+        }
+    }
+
+    // Mueve el hilo a `group` (None lo saca de los grupos). EINVAL si el grupo no existe.
+    pub fn set_group(&mut self, tid: ThreadId, group: Option<GroupId>) -> c_int {
+        if group.is_some_and(|g| !self.groups.contains(g)) {
+            return MyError::Invalid as c_int;
+        }
+        let Some(t) = self.threads.get_mut(&tid) else { return MyError::NoSuchThread as c_int };
+        if t.attr.group == group {
+            return 0;
+        }
+        let grouped = t.attr.group.is_some();
+        let queued = self.dequeue_ready(tid);
+        if grouped {
+            self.groups.on_exit(tid);
+        }
+        if let Some(t) = self.threads.get_mut(&tid) {
+            t.attr.group = group;
         }
+        if queued {
+            self.enqueue_ready(tid);
+        }
+        0
+    }
+
+    // Avanza el reloj si es virtual; con uno de pared no hace nada.
+    pub fn advance_steps(&mut self, passed: usize) {
+        self.clock.advance(passed);
+    }
+
+    pub fn time_ms(&self) -> usize {
+        self.clock.now_ms()
+    }
+
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        self.clock = clock;
     }
 
-        pub fn advance_steps(&mut self, passed: usize) {
-        self.time_ms = self.time_ms.saturating_add(passed);
+    pub fn clock(&self) -> SharedClock {
+        self.clock.clone()
     }
 
     // Crea un hilo en estado Ready y lo encola.
-    pub fn create(&mut self,thread_out: *mut ThreadId,attr: *mut MyThreadAttr,start_routine: MyTRoutine,args: *mut AnyParam,scheduler: Option<SchedulerType>,
+    pub fn create(&mut self,thread_out: *mut ThreadId,attr: *const MyThreadAttr,start_routine: MyTRoutine,args: *mut AnyParam,scheduler: Option<SchedulerType>,
     ) -> c_int {
         let id = self.next_id;
         self.next_id += 1;
 
         let sched = scheduler.unwrap_or_default();
-        let mut new_thread = MyThread::new(id, attr, start_routine, args, Default::default());
+        if !self.schedulers.contains_key(&sched) {
+            return MyError::Invalid as c_int;
+        }
+        // El hilo se queda con su propia copia, el attr del llamador puede morir después de esto
+        let attr = unsafe { attr.as_ref() }.cloned().unwrap_or_default();
+        let mut new_thread = MyThread::new(id, attr, start_routine, args, Some(sched));
         new_thread.state = ThreadState::Ready;
+        // Igual que pthread_create, la máscara de señales se hereda del creador
+        new_thread.sig_mask = self.current.and_then(|c| self.threads.get(&c)).map_or(0, |t| t.sig_mask);
 
 
         self.threads.insert(id, new_thread);
 
 
-        if let Some(s) = self.schedulers.get_mut(&sched) {
-            let t = self.threads.get(&id).unwrap();
-            s.enqueue(id, t);
-        } else {
-            return -1; // no debería llegar aquí nunca
+        if !self.enqueue_ready(id) {
+            return MyError::Invalid as c_int; // no debería llegar aquí nunca
         }
 
 
@@ -119,69 +293,379 @@ impl MyTRuntime {
 
 
 
-    // RealTime > Lottery > RoundRobin en orden
+    // RealTime > FixedPriority > grupos > registrados (en orden de registro) > Lottery > RoundRobin
     fn pick_any_next(&mut self) -> Option<ThreadId> {
-        for kind in [SchedulerType::RealTime, SchedulerType::Lottery, SchedulerType::RoundRobin] {
-            if let Some(s) = self.schedulers.get_mut(&kind) {
-                if !s.is_empty() {
-                    if let Some(tid) = s.pick_next() {
-                        return Some(tid);
-                    }
-                }
+        for kind in [SchedulerType::RealTime, SchedulerType::FixedPriority] {
+            if let Some(tid) = self.schedulers.get_mut(&kind).and_then(|s| s.pick_next()) {
+                return Some(tid);
+            }
+        }
+        if let Some(tid) = self.groups.pick_next() {
+            return Some(tid);
+        }
+        for &(_, kind) in &self.scheduler_names {
+            if let SchedulerType::Custom(_) = kind
+                && let Some(tid) = self.schedulers.get_mut(&kind).and_then(|s| s.pick_next())
+            {
+                return Some(tid);
+            }
+        }
+        for kind in [SchedulerType::Lottery, SchedulerType::RoundRobin] {
+            if let Some(s) = self.schedulers.get_mut(&kind)
+                && !s.is_empty()
+                && let Some(tid) = s.pick_next()
+            {
+                return Some(tid);
             }
         }
         None
     }
 
+    // Con chooser: saca todos los listos, deja que él escoja y devuelve los demás a sus colas.
+    fn choose_next(&mut self) -> Option<ThreadId> {
+        let mut ready = Vec::new();
+        while let Some(tid) = self.pick_any_next() {
+            match self.threads.get(&tid) {
+                Some(t) if !t.state.is_finished() => ready.push(tid),
+                _ => self.wake_joiners(&tid),
+            }
+        }
+        if ready.is_empty() {
+            return None;
+        }
 
-    fn run_thread(&mut self, tid: ThreadId) {
-        // Aquí se haría el “contexto” si nos da tiempo de hacerlo.
-        let (routine, arg, scheduler_kind, detached);
-        {
-            let t = self.threads.get_mut(&tid).unwrap();
+        let mut chooser = self.chooser.take()?;
+        let chosen = chooser(&ready).filter(|&i| i < ready.len());
+        self.chooser = Some(chooser);
+
+        for (i, &tid) in ready.iter().enumerate() {
+            if Some(i) != chosen {
+                self.requeue(tid);
+            }
+        }
+        chosen.map(|i| ready[i])
+    }
+
+
+    // Toma el siguiente hilo del scheduler, lo marca Running y devuelve lo necesario para ejecutarlo.
+    // Separado de `finish_thread` para poder correr la rutina sin tener prestado el runtime.
+    pub(crate) fn begin_next(&mut self) -> Option<(ThreadId, MyTRoutine, *mut AnyParam)> {
+        for s in self.schedulers.values_mut() {
+            s.on_tick();
+        }
+        self.groups.on_tick();
+        self.aging_stats.dispatches += 1;
+        loop {
+            let next = match self.forced_pick().or_else(|| self.promote_starving()) {
+                Some(tid) => tid,
+                None if self.chooser.is_some() => self.choose_next()?,
+                None => self.pick_any_next()?,
+            };
+            note_pick(next);
+            self.current = Some(next);
+
+            let now = self.aging_stats.dispatches;
+            let Some(t) = self.threads.get_mut(&next) else { continue };
+            if let Some(since) = t.ready_since.take() {
+                self.ready_order.remove(&(since, next));
+                t.max_wait = t.max_wait.max(now - since);
+                self.aging_stats.max_wait = self.aging_stats.max_wait.max(t.max_wait);
+            }
+            if t.state.is_finished() {
+                // Nada que hacer, despierta joiners y sigue
+                self.wake_joiners(&next);
+                continue;
+            }
+            if t.state == ThreadState::Blocked {
+                // Quedó en una cola de antes de bloquearse; vuelve a ella cuando lo despierten
+                continue;
+            }
             t.state = ThreadState::Running;
-            routine = t.start_routine;
-            arg = t.arg;
-            scheduler_kind = t.scheduler;
-            detached = unsafe { (*t.attr).detached };
+            t.runs += 1;
+            if t.stack.is_none() {
+                // Sin memoria para la pila el hilo corre sobre la de quien lo despacha
+                t.stack = MyStack::new(t.attr.stack_size()).ok();
+            }
+            let job = (next, t.start_routine, t.arg);
+            self.start_clock(next);
+            return Some(job);
         }
+    }
+
+    // En replay, el hilo que despachó la grabación si sigue listo. Como el promovido por aging, sale de
+    // su cola sin pasar por pick_next; si no está listo decide el scheduler y el replay queda divergido.
+    fn forced_pick(&mut self) -> Option<ThreadId> {
+        let tid = recorded_pick()?;
+        self.dequeue_ready(tid).then_some(tid)
+    }
 
-        let ret = (routine)(arg);
+    // Con aging, cada `threshold` despachos que un hilo lleva listo sube un escalón sobre su clase
+    // (ver myaging): con uno pasa delante de los de su clase, con más por encima de las de arriba.
+    // Corre el más viejo de los que ya alcanzan a la clase que despacharía ahora. Nadie pasa por encima
+    // de RealTime mientras algún hilo RealTime tenga su deadline por delante. Sale de la cola de su
+    // scheduler sin pasar por pick_next, así que ese despacho no se le cobra (tickets, budget, stride).
+    // Con chooser (exploración) no se promueve a nadie: el orden lo decide él.
+    fn promote_starving(&mut self) -> Option<ThreadId> {
+        let aging = self.aging.filter(|_| self.chooser.is_none())?;
+        let now = self.aging_stats.dispatches;
+        let starving = |since: usize| (now - since) / aging.threshold;
+        // Lo normal es que nadie pase del umbral: solo se mira el primero
+        if starving(self.ready_order.first()?.0) == 0 {
+            return None;
+        }
+        let top = self.top_ready_rank()?;
+        let ceiling = if self.real_time().deadlines_in_flight() { MY_REALTIME_RANK - 1 } else { MY_REALTIME_RANK };
+
+        let mut stale = Vec::new();
+        let mut chosen = None;
+        for &(since, tid) in &self.ready_order {
+            let steps = starving(since);
+            if steps == 0 {
+                break;
+            }
+            match self.threads.get(&tid) {
+                Some(t) if t.ready_since == Some(since) && t.state == ThreadState::Ready => {
+                    if (Self::class_rank(t) + steps - 1).min(ceiling) >= top {
+                        chosen = Some((since, tid));
+                        break;
+                    }
+                }
+                _ => stale.push((since, tid)),
+            }
+        }
+        for entry in stale {
+            self.ready_order.remove(&entry);
+        }
+
+        let (since, tid) = chosen?;
+        if !self.dequeue_ready(tid) {
+            // Listo pero en ninguna cola: no está esperando a ningún scheduler
+            self.ready_order.remove(&(since, tid));
+            if let Some(t) = self.threads.get_mut(&tid) {
+                t.ready_since = None;
+            }
+            return None;
+        }
+        self.aging_stats.promotions += 1;
+        if let Some(t) = self.threads.get_mut(&tid) {
+            t.promotions += 1;
+        }
+        Some(tid)
+    }
+
+    // Escalón de la clase del hilo en el orden de pick_any_next (RoundRobin 0 .. RealTime 5).
+    fn class_rank(t: &MyThread) -> usize {
+        if t.attr.group.is_some() {
+            return 3;
+        }
+        match t.scheduler {
+            SchedulerType::RoundRobin => 0,
+            SchedulerType::Lottery => 1,
+            SchedulerType::Custom(_) => 2,
+            SchedulerType::FixedPriority => 4,
+            SchedulerType::RealTime => MY_REALTIME_RANK,
+        }
+    }
+
+    // Escalón de la clase de la que saldría el siguiente hilo sin aging.
+    fn top_ready_rank(&self) -> Option<usize> {
+        let queued = |kind| self.schedulers.get(&kind).is_some_and(|s| !s.is_empty());
+        if queued(SchedulerType::RealTime) {
+            Some(MY_REALTIME_RANK)
+        } else if queued(SchedulerType::FixedPriority) {
+            Some(4)
+        } else if !self.groups.is_empty() {
+            Some(3)
+        } else if self.scheduler_names.iter().any(|&(_, kind)| matches!(kind, SchedulerType::Custom(_)) && queued(kind)) {
+            Some(2)
+        } else if queued(SchedulerType::Lottery) {
+            Some(1)
+        } else if queued(SchedulerType::RoundRobin) {
+            Some(0)
+        } else {
+            None
+        }
+    }
 
+    pub fn set_aging(&mut self, aging: Option<MyAging>) {
+        self.aging = aging;
+    }
+
+    pub fn aging_stats(&self) -> MyAgingStats {
+        self.aging_stats
+    }
+
+    // El que estaba corriendo deja de contar mientras corre `tid` encima.
+    fn start_clock(&mut self, tid: ThreadId) {
+        let now = Instant::now();
+        if let Some(&(below, since)) = self.run_clock.last() {
+            self.charge(below, now - since);
+        }
+        self.run_clock.push((tid, now));
+    }
+
+    fn stop_clock(&mut self, tid: ThreadId) {
+        let now = Instant::now();
+        if let Some(pos) = self.run_clock.iter().rposition(|&(t, _)| t == tid) {
+            let (_, since) = self.run_clock.remove(pos);
+            self.charge(tid, now - since);
+        }
+        if let Some(last) = self.run_clock.last_mut() {
+            last.1 = now;
+        }
+    }
+
+    fn charge(&mut self, tid: ThreadId, time: std::time::Duration) {
+        if let Some(t) = self.threads.get_mut(&tid) {
+            t.cpu_time += time;
+        }
+    }
+
+    // Registra el retorno de la rutina y libera lo que el hilo tenía en el runtime.
+    pub(crate) fn finish_thread(&mut self, tid: ThreadId, ret: *mut AnyParam) {
+        let (scheduler_kind, detached, grouped);
         {
-            let t = self.threads.get_mut(&tid).unwrap();
+            let Some(t) = self.threads.get_mut(&tid) else { return };
             t.ret_val = ret;
             t.state = ThreadState::Terminated;
+            if let Some(since) = t.ready_since.take() {
+                self.ready_order.remove(&(since, tid));
+            }
+            t.stack = None;
+            scheduler_kind = t.scheduler;
+            detached = t.attr.detached;
+            grouped = t.attr.group.is_some();
         }
 
-        if let Some(s) = self.schedulers.get_mut(&scheduler_kind) {
+        if grouped {
+            self.groups.on_exit(tid);
+        } else if let Some(s) = self.schedulers.get_mut(&scheduler_kind) {
             s.on_exit(tid);
         }
 
         self.wake_joiners(&tid);
+        // Si murió estacionado esperando E/S ya nadie lo va a despertar
+        self.io_waiters.retain(|_, waiters| {
+            waiters.retain(|&w| w != tid);
+            !waiters.is_empty()
+        });
         if detached {
-
+            // Nadie va a hacer join, el registro ya no sirve
+            self.threads.remove(&tid);
         }
     }
 
-    // Ejecuta un próximo hilo si existe (scheduler decide).
-    pub fn schedule_next(&mut self) -> c_int {
-        if let Some(next) = self.pick_any_next() {
-            self.current = Some(next);
-
-            if let Some(th) = self.threads.get(&next) {
-                if th.state == ThreadState::Terminated {
-                    // Nada que hacer, despierta joiners y sigue
-                    self.wake_joiners(&next);
-                    return 0;
+    // Cierra un hilo según cómo terminó su rutina. Si se desbordó solo muere ese hilo.
+    pub(crate) fn finish_run(&mut self, tid: ThreadId, outcome: RunOutcome) {
+        self.stop_clock(tid);
+        match outcome {
+            RunOutcome::Returned(ret) => self.finish_thread(tid, ret),
+            RunOutcome::Parked => {
+                // Si se estacionó Blocked (esperando un canal) queda fuera de las colas hasta `wake_parked`.
+                // Si ya lo despertaron (un wake de otro hilo del SO entre el park y aquí) ya está encolado
+                if self.get_state(tid) == Some(ThreadState::Running) {
+                    self.requeue(tid);
                 }
             }
+            RunOutcome::Overflowed => {
+                if let Some(t) = self.threads.get_mut(&tid) {
+                    t.exit_code = MyError::StackOverflow as c_int;
+                }
+                self.finish_thread(tid, std::ptr::null_mut());
+            }
+            RunOutcome::Faulted(message) => {
+                if let Some(t) = self.threads.get_mut(&tid) {
+                    t.exit_code = MyError::Faulted as c_int;
+                    t.panic_message = Some(message);
+                }
+                self.finish_thread(tid, std::ptr::null_mut());
+                self.set_state(tid, ThreadState::Faulted);
+            }
+        }
+    }
 
-            self.run_thread(next);
-            0
-        } else {
-            self.current = None;
-            1 // nada para correr
+    pub fn set_name(&mut self, tid: ThreadId, name: &str) -> c_int {
+        let Some(t) = self.threads.get_mut(&tid) else { return MyError::NoSuchThread as c_int };
+        t.name = Some(name.to_string());
+        0
+    }
+
+    pub fn name(&self, tid: ThreadId) -> Option<&str> {
+        self.threads.get(&tid)?.name()
+    }
+
+    // Foto de los hilos que todavía no terminan, ordenada por id.
+    pub fn list_threads(&self) -> Vec<MyThreadInfo> {
+        let mut infos: Vec<MyThreadInfo> = self
+            .threads
+            .values()
+            .filter(|t| !t.state.is_finished())
+            .map(MyThread::info)
+            .collect();
+        infos.sort_by_key(|i| i.id);
+        infos
+    }
+
+    // Hilos vivos y mutex de este hilo del SO, para el inspector.
+    pub fn dump(&self) -> MyRuntimeDump {
+        MyRuntimeDump { threads: self.list_threads(), mutexes: my_list_mutexes(), current: self.current }
+    }
+
+    // Con `sig` del proceso se imprime el dump en stderr en el siguiente despacho (p.ej. kill -USR2).
+    pub fn dump_on_signal(&mut self, sig: c_int) -> c_int {
+        if !is_valid_signal(sig) || sig == libc::SIGKILL || sig == libc::SIGSTOP {
+            return MyError::Invalid as c_int;
+        }
+        if install_process_handler(sig) != 0 {
+            return MyError::Invalid as c_int;
+        }
+        self.dump_signal = Some(sig);
+        0
+    }
+
+    // Mensaje del panic de un hilo Faulted que sigue en la tabla.
+    pub fn panic_message(&self, tid: ThreadId) -> Option<&str> {
+        self.threads.get(&tid)?.panic_message.as_deref()
+    }
+
+    // Vuelve a dejar listo en su scheduler a un hilo que cedió el CPU sin terminar.
+    fn requeue(&mut self, tid: ThreadId) {
+        let Some(t) = self.threads.get_mut(&tid) else { return };
+        t.state = ThreadState::Ready;
+        self.enqueue_ready(tid);
+    }
+
+    // Despierta a un hilo que se estacionó Blocked. No hace nada si ya no está bloqueado.
+    pub(crate) fn wake_parked(&mut self, tid: ThreadId) {
+        if self.get_state(tid) == Some(ThreadState::Blocked) {
+            self.note_wake(tid);
+            self.requeue(tid);
+        }
+    }
+
+    pub(crate) fn stack_of(&self, tid: ThreadId) -> Option<StackRegion> {
+        self.threads.get(&tid)?.stack.as_ref().map(|s| s.region())
+    }
+
+    fn run_thread(&mut self, tid: ThreadId, routine: MyTRoutine, arg: *mut AnyParam) {
+        // La rutina corre sobre la pila propia del hilo y vuelve aquí al terminar
+        self.deliver_signals(tid);
+        let outcome = run_routine(tid, self.stack_of(tid), routine, arg);
+        self.finish_run(tid, outcome);
+    }
+
+    // Ejecuta un próximo hilo si existe (scheduler decide). Si no hay nadie listo pero hay hilos
+    // estacionados esperando E/S, duerme en epoll hasta que alguno despierte.
+    pub fn schedule_next(&mut self) -> c_int {
+        loop {
+            if let Some((next, routine, arg)) = self.begin_next() {
+                self.run_thread(next, routine, arg);
+                return 0;
+            }
+            if self.io_waiters.is_empty() || !self.poll_io(-1) {
+                self.current = None;
+                return 1; // nada para correr
+            }
         }
     }
 
@@ -193,10 +677,10 @@ impl MyTRuntime {
     }
 
     pub fn save_context(&mut self) {
-        if let Some(tid) = self.current {
-            if let Some(th) = self.threads.get_mut(&tid) {
-                th.state = ThreadState::Ready;
-            }
+        if let Some(tid) = self.current
+            && let Some(th) = self.threads.get_mut(&tid)
+        {
+            th.state = ThreadState::Ready;
         }
     }
 
@@ -216,22 +700,23 @@ impl MyTRuntime {
         self.current = None;
     }
 
-    pub unsafe fn detach(&mut self, tid: ThreadId) -> c_int {
+    pub fn detach(&mut self, tid: ThreadId) -> c_int {
         if let Some(th) = self.threads.get_mut(&tid) {
-            unsafe { th.attr.as_mut().unwrap().detach(); }
-            if th.state == ThreadState::Terminated {
+            th.attr.detach();
+            if th.state.is_finished() {
                 self.threads.remove(&tid);
             }
             0
         } else {
-            libc::ESRCH
+            MyError::NoSuchThread as c_int
         }
     }
 
+    // EPERM fuera de un hilo verde.
     pub fn end_current(&mut self, retval: *mut AnyParam, ) -> c_int {
         let Some(cur) = self.current else {
             // No hay hilo en ejecución devolvemos error
-            return -1;
+            return MyError::NotPermitted as c_int;
         };
 
         // Marca terminado y guarda el retorno (que no tenemos aun)
@@ -239,7 +724,7 @@ impl MyTRuntime {
             th.ret_val = retval;
             th.state = ThreadState::Terminated;
         } else {
-            return -1; // el TID actual no está en el mapa
+            return MyError::NoSuchThread as c_int; // el TID actual no está en el mapa
         }
 
         // Despierta a los joiners
@@ -276,113 +761,375 @@ impl MyTRuntime {
 
 
 
+    // API propia del LotteryScheduler (monedas, transferencias, compensación).
+    pub fn lottery(&mut self) -> &mut LotteryScheduler {
+        self.scheduler_mut(SchedulerType::Lottery)
+            .and_then(|s| s.as_any().downcast_mut::<LotteryScheduler>())
+            .expect("el runtime siempre tiene un LotteryScheduler")
+    }
+
+    pub fn real_time(&mut self) -> &mut RealTimeScheduler {
+        self.scheduler_mut(SchedulerType::RealTime)
+            .and_then(|s| s.as_any().downcast_mut::<RealTimeScheduler>())
+            .expect("el runtime siempre tiene un RealTimeScheduler")
+    }
+
+    // Avisa a los schedulers que `waiter` se bloquea esperando a `target` (transferencia de tickets).
+    pub(crate) fn lend_tickets(&mut self, waiter: ThreadId, target: ThreadId) {
+        for sched in self.schedulers.values_mut() {
+            sched.on_wait(waiter, target);
+        }
+        self.groups.on_wait(waiter, target);
+    }
+
+    pub(crate) fn return_tickets(&mut self, waiter: ThreadId) {
+        for sched in self.schedulers.values_mut() {
+            sched.on_wait_done(waiter);
+        }
+        self.groups.on_wait_done(waiter);
+    }
+
+    // Como pthread_join: ESRCH si el hilo no existe, EINVAL si es detached o ya lo espera otro,
+    // EDEADLK si es el mismo hilo o ya no hay nada que corra para que termine. Si el runtime lo
+    // mató devuelve su código de salida (MyError::StackOverflow o MyError::Faulted).
     pub fn join(&mut self, target: ThreadId, ret_val_out: *mut *mut AnyParam) -> c_int {
         //  Validaciones básicas
 
         // Asegurar que el target exista
         let target_exists = match self.threads.get(&target) {
-            Some(t) => t, None => return -1, // Hilo objetivo no existe
+            Some(t) => t, None => return MyError::NoSuchThread as c_int, // Hilo objetivo no existe
         };
 
         // No join sobre detached
-        let is_detached = unsafe { (*target_exists.attr).detached };
-        if is_detached {return -1;}
+        let is_detached = target_exists.attr.detached;
+        if is_detached {return MyError::Invalid as c_int;}
 
         // Si ya terminó, retorna su valor
-        if target_exists.state == ThreadState::Terminated {
-            if !ret_val_out.is_null() {unsafe { *ret_val_out = target_exists.ret_val as *mut AnyParam; }}
-            return 0;
+        if target_exists.state.is_finished() {
+            return self.join_result(target, ret_val_out);
         }
 
         // MODO DRIVER, esto es solo para las pruebas, no hay hilo actual (join desde el hilo de prueba / fuera del runtime)
         if self.current.is_none() {
             loop {
                 // terminó el target?
-                let done = match self.threads.get(&target) { Some(t) => t.state == ThreadState::Terminated, None => true };
+                let done = match self.threads.get(&target) { Some(t) => t.state.is_finished(), None => true };
                 if done {break;}
                 // Avanza el scheduler, si no hay nada para correr y no terminó
-                if self.schedule_next() != 0 {return -1;}
+                if self.schedule_next() != 0 {return MyError::Deadlock as c_int;}
             }
-            if let Some(t) = self.threads.get(&target) {
-                if !ret_val_out.is_null() {unsafe { *ret_val_out = t.ret_val as *mut AnyParam; }}
-            } else {
-                if !ret_val_out.is_null() {unsafe { *ret_val_out = std::ptr::null_mut(); }}
-            }
-            return 0;
+            return self.join_result(target, ret_val_out);
         }
 
         // MODO RUNTIME (el para no test) hay hilo actual, aplicar bloqueo y espera
         let current_tid = match self.current {Some(id) => id, None => unreachable!()};
-        if current_tid == target {return -1;}
+        if current_tid == target {return MyError::Deadlock as c_int;}
 
         // Registrar que el hilo "current" espera a "target"
         {
             let waiters = self.wait_on.entry(target).or_default();
-            if waiters.iter().any(|&w| w == current_tid) {return -1;}
-            if !waiters.is_empty() {return -1;} waiters.push(current_tid);
+            if !waiters.is_empty() {return MyError::Invalid as c_int;} waiters.push(current_tid);
         }
 
         // Bloquea al hilo actual y ceder el CPU
         {
             let cur = self.threads.get_mut(&current_tid).unwrap();
             cur.state = ThreadState::Blocked;
+            cur.blocked_on = Some(BlockedOn::Join(target));
         }
+        // Mientras espera, sus tickets ayudan al objetivo a terminar
+        self.lend_tickets(current_tid, target);
 
         // dejamos que el scheduler corra otros hilos hasta que el objetivo termine (wake_joiners lo reactivará) y
         // se vuelve a meter
         loop {
             // Si el objetivo ya terminó, salimos del loop para devolver ret_val
-            if let Some(t) = self.threads.get(&target) {if t.state == ThreadState::Terminated {break;}}
+            if let Some(t) = self.threads.get(&target) {if t.state.is_finished() {break;}}
             else {break;}
 
             // Que el runtime ejecute el siguiente hilo disponible schedule_next() hará run-to-completion del elegido,
             // y al terminar "target", llamará a wake_joiners() que nos re-encola.
-            if self.schedule_next() != 0 {return -1;}
+            if self.schedule_next() != 0 {
+                self.return_tickets(current_tid);
+                self.wait_on.remove(&target);
+                self.current = Some(current_tid);
+                self.set_state(current_tid, ThreadState::Running);
+                return MyError::Deadlock as c_int;
+            }
 
             // Si ya volvimos a ser el hilo actual, revisa nuevamente el estado del target.
             if self.current == Some(current_tid) {
-                if let Some(t) = self.threads.get(&target) { if t.state == ThreadState::Terminated {break;} }
+                if let Some(t) = self.threads.get(&target) { if t.state.is_finished() {break;} }
                 else {break;}
             }
         }
 
+        self.return_tickets(current_tid);
+
         // El target está Terminated Recupera el ret_val y limpia la espera.
-        if let Some(t) = self.threads.get(&target) {
-            if !ret_val_out.is_null() {unsafe { *ret_val_out = t.ret_val as *mut AnyParam; }}
-        } else {
-            if !ret_val_out.is_null() {unsafe { *ret_val_out = std::ptr::null_mut(); }}
-        }
+        let rc = self.join_result(target, ret_val_out);
 
         // Limpiar la lista de waiters para este target
         self.wait_on.remove(&target);
 
-        0
+        // Volvemos a correr: atender lo que nos llegó mientras esperábamos
+        self.current = Some(current_tid);
+        self.deliver_signals(current_tid);
+        rc
+    }
+
+    // Escribe el ret_val de un hilo terminado y devuelve su código de salida (0 si retornó normal).
+    fn join_result(&self, target: ThreadId, ret_val_out: *mut *mut AnyParam) -> c_int {
+        let t = self.threads.get(&target);
+        if !ret_val_out.is_null() {
+            unsafe { *ret_val_out = t.map_or(std::ptr::null_mut(), |t| t.ret_val); }
+        }
+        t.map_or(0, |t| t.exit_code)
+    }
+
+    // Código de salida de un hilo que todavía está en la tabla.
+    pub fn exit_code(&self, tid: ThreadId) -> Option<c_int> {
+        self.threads.get(&tid).map(|t| t.exit_code)
     }
 
 
 
 
 pub fn wake_thread(&mut self, target: ThreadId) -> c_int {
-        if let Some(th) = self.threads.get_mut(&target) {
-            if th.state != ThreadState::Terminated {
-                th.state = ThreadState::Ready;
-                return Ok as c_int;
-            }
-            return ThreadIsTerminated as c_int;
+        if let Some(th) = self.threads.get_mut(&target)
+            && !th.state.is_finished()
+        {
+            th.state = ThreadState::Ready;
+            return 0;
         }
-        UnknownThread as c_int
+        MyError::NoSuchThread as c_int
     }
     
     fn wake_joiners(&mut self, objective: &ThreadId) {
         if let Some(waiters) = self.wait_on.remove(objective) {
             for w in waiters {
-                if let Some(tw) = self.threads.get_mut(&w) {
-                    if tw.state == ThreadState::Blocked {
-                        tw.state = ThreadState::Ready;
-                        self.run_queue.push_back(w);
-                    }
+                if let Some(tw) = self.threads.get_mut(&w)
+                    && tw.state == ThreadState::Blocked
+                {
+                    tw.state = ThreadState::Ready;
+                    self.run_queue.push_back(w);
                 }
             }
         }
     }
+
+    // E/S que no bloquea el proceso: si el fd no está listo el hilo actual queda Blocked,
+    // corren los demás y, cuando ya no hay nadie listo, el runtime duerme en epoll.
+    pub fn io_read(&mut self, fd: RawFd, buf: *mut c_void, count: usize) -> isize {
+        set_nonblocking(fd);
+        loop {
+            let n = unsafe { libc::read(fd, buf, count) };
+            if n >= 0 || !would_block() {
+                return n;
+            }
+            if self.wait_io(fd, Interest::Read) != 0 {
+                return -1;
+            }
+        }
+    }
+
+    pub fn io_write(&mut self, fd: RawFd, buf: *const c_void, count: usize) -> isize {
+        set_nonblocking(fd);
+        loop {
+            let n = unsafe { libc::write(fd, buf, count) };
+            if n >= 0 || !would_block() {
+                return n;
+            }
+            if self.wait_io(fd, Interest::Write) != 0 {
+                return -1;
+            }
+        }
+    }
+
+    pub fn io_accept(&mut self, fd: RawFd, addr: *mut libc::sockaddr, len: *mut libc::socklen_t) -> c_int {
+        set_nonblocking(fd);
+        loop {
+            let conn = unsafe { libc::accept4(fd, addr, len, libc::SOCK_CLOEXEC) };
+            if conn >= 0 || !would_block() {
+                return conn;
+            }
+            if self.wait_io(fd, Interest::Read) != 0 {
+                return -1;
+            }
+        }
+    }
+
+    fn wait_io(&mut self, fd: RawFd, interest: Interest) -> c_int {
+        if self.reactor.register(fd, interest) != 0 {
+            return -1;
+        }
+        let me = self.current;
+        if let Some(tid) = me {
+            // Con pila propia se estaciona: quien lo despachó sigue con los demás y poll_io lo despierta
+            self.block_on_io(tid, fd, interest);
+            if park() {
+                return 0;
+            }
+            self.unblock_io(tid, fd, interest);
+        }
+
+        // Sin pila propia (o desde el driver) espera aquí, corriendo a los demás encima suyo. Se vuelve
+        // a armar el fd en cada vuelta: el aviso pudo llevárselo otro hilo que esperaba lo mismo
+        while !self.reactor.take_ready(fd, interest) {
+            if self.schedule_next() != 0 {
+                self.poll_io(-1);
+            }
+            if self.reactor.register(fd, interest) != 0 {
+                break;
+            }
+        }
+
+        self.current = me;
+        if let Some(tid) = me {
+            self.note_wake(tid);
+            self.set_state(tid, ThreadState::Running);
+            self.deliver_signals(tid);
+        }
+        0
+    }
+
+    // Deja a `tid` Blocked esperando `fd` hasta que poll_io lo despierte; falta estacionarlo.
+    pub(crate) fn block_on_io(&mut self, tid: ThreadId, fd: RawFd, interest: Interest) {
+        self.block(tid, BlockedOn::Io(fd));
+        self.io_waiters.entry((fd, interest)).or_default().push(tid);
+    }
+
+    // Deshace block_on_io cuando el hilo no pudo estacionarse; sigue Blocked.
+    pub(crate) fn unblock_io(&mut self, tid: ThreadId, fd: RawFd, interest: Interest) {
+        if let Some(waiters) = self.io_waiters.get_mut(&(fd, interest)) {
+            waiters.retain(|&w| w != tid);
+            if waiters.is_empty() {
+                self.io_waiters.remove(&(fd, interest));
+            }
+        }
+    }
+
+    pub(crate) fn has_io_waiters(&self) -> bool {
+        !self.io_waiters.is_empty()
+    }
+
+    // Duerme en epoll (-1 = sin límite) y despierta a los hilos estacionados cuyo fd quedó listo;
+    // ellos reintentan la operación. Devuelve false si nadie tiene un fd armado.
+    pub(crate) fn poll_io(&mut self, timeout_ms: c_int) -> bool {
+        if !self.reactor.has_interests() {
+            return false;
+        }
+        self.reactor.poll(timeout_ms);
+        let keys: Vec<(RawFd, Interest)> = self.io_waiters.keys().copied().collect();
+        for (fd, interest) in keys {
+            if self.reactor.take_ready(fd, interest)
+                && let Some(waiters) = self.io_waiters.remove(&(fd, interest))
+            {
+                for tid in waiters {
+                    self.wake_parked(tid);
+                }
+            }
+        }
+        true
+    }
+
+    // Instala el handler que corre cuando un hilo atiende `sig`. Sin handler la señal se descarta.
+    pub fn set_signal_handler(&mut self, sig: c_int, handler: SignalHandler) -> c_int {
+        if !is_valid_signal(sig) {
+            return MyError::Invalid as c_int;
+        }
+        self.sig_handlers.insert(sig, handler);
+        0
+    }
+
+    // Hace que la señal `sig` del proceso le llegue al hilo `tid` la próxima vez que se despache.
+    pub fn route_signal(&mut self, sig: c_int, tid: ThreadId) -> c_int {
+        if !is_valid_signal(sig) || sig == libc::SIGKILL || sig == libc::SIGSTOP {
+            return MyError::Invalid as c_int;
+        }
+        if !self.is_alive(tid) {
+            return MyError::NoSuchThread as c_int;
+        }
+        if install_process_handler(sig) != 0 {
+            return MyError::Invalid as c_int;
+        }
+        self.sig_routes.insert(sig, tid);
+        0
+    }
+
+    // Deja `sig` pendiente en `tid`. Con sig == 0 solo revisa que el hilo exista, como pthread_kill.
+    pub fn kill(&mut self, tid: ThreadId, sig: c_int) -> c_int {
+        if sig != 0 && !is_valid_signal(sig) {
+            return MyError::Invalid as c_int;
+        }
+        if !self.is_alive(tid) {
+            return MyError::NoSuchThread as c_int;
+        }
+        if let Some(t) = self.threads.get_mut(&tid) {
+            t.sig_pending |= my_sigbit(sig);
+        }
+        0
+    }
+
+    // Cambia la máscara del hilo actual igual que pthread_sigmask. `set` y `oldset` pueden ser nulos.
+    pub fn sigmask(&mut self, how: c_int, set: *const MySigSet, oldset: *mut MySigSet) -> c_int {
+        let Some(t) = self.current.and_then(|c| self.threads.get_mut(&c)) else {
+            return MyError::NotPermitted as c_int;
+        };
+        if !oldset.is_null() {
+            unsafe { *oldset = t.sig_mask; }
+        }
+        let Some(&set) = (unsafe { set.as_ref() }) else { return 0 };
+        t.sig_mask = match how {
+            MY_SIG_BLOCK => t.sig_mask | set,
+            MY_SIG_UNBLOCK => t.sig_mask & !set,
+            MY_SIG_SETMASK => set,
+            _ => return MyError::Invalid as c_int,
+        };
+        0
+    }
+
+    fn is_alive(&self, tid: ThreadId) -> bool {
+        self.threads.get(&tid).is_some_and(|t| !t.state.is_finished())
+    }
+
+    // `tid` tiene señales pendientes que puede atender ya.
+    pub(crate) fn has_deliverable(&self, tid: ThreadId) -> bool {
+        self.threads.get(&tid).is_some_and(|t| t.sig_pending & !t.sig_mask != 0)
+    }
+
+    // `tid` está en pause y `sig` no está enmascarada: hay que despertarlo para que la atienda.
+    pub(crate) fn paused_for(&self, tid: ThreadId, sig: c_int) -> bool {
+        self.threads.get(&tid).is_some_and(|t| {
+            t.state == ThreadState::Blocked && t.blocked_on == Some(BlockedOn::Signal) && my_sigbit(sig) & !t.sig_mask != 0
+        })
+    }
+
+    // Saca las señales que `tid` puede atender ahora (pendientes y no enmascaradas) junto con su handler.
+    // Antes reparte las señales del proceso que llegaron a sus hilos destino.
+    pub(crate) fn take_signals(&mut self, tid: ThreadId) -> Vec<(SignalHandler, c_int)> {
+        if let Some(sig) = self.dump_signal
+            && take_process_pending(my_sigbit(sig)) != 0
+        {
+            eprintln!("{}", self.dump());
+        }
+        let routed = self.sig_routes.keys().fold(0, |set, &sig| set | my_sigbit(sig));
+        for sig in signals_in(take_process_pending(routed)) {
+            let target = self.sig_routes[&sig];
+            self.kill(target, sig);
+        }
+
+        let Some(t) = self.threads.get_mut(&tid) else { return Vec::new() };
+        let deliverable = t.sig_pending & !t.sig_mask;
+        t.sig_pending &= !deliverable;
+        signals_in(deliverable)
+            .filter_map(|sig| self.sig_handlers.get(&sig).map(|h| (h.clone(), sig)))
+            .collect()
+    }
+
+    pub(crate) fn deliver_signals(&mut self, tid: ThreadId) {
+        for (handler, sig) in self.take_signals(tid) {
+            handler(sig, tid);
+        }
+    }
 }
@@ -0,0 +1,60 @@
+use std::os::raw::c_int;
+use std::sync::atomic::{AtomicU64, Ordering};
+use crate::mythread::mythread::ThreadId;
+
+// Conjunto de señales de un hilo: el bit `sig - 1` representa a la señal `sig` (1..=64).
+pub type MySigSet = u64;
+
+// Handler de señal del runtime. Recibe la señal y el hilo que la está atendiendo.
+pub type MySigHandler = extern "C" fn(sig: c_int, thread: ThreadId);
+
+// Valores de `how` para my_thread_sigmask, los mismos de pthread_sigmask
+pub const MY_SIG_BLOCK: c_int = libc::SIG_BLOCK;
+pub const MY_SIG_UNBLOCK: c_int = libc::SIG_UNBLOCK;
+pub const MY_SIG_SETMASK: c_int = libc::SIG_SETMASK;
+
+// Señales libres para la aplicación
+pub const MY_SIGUSR1: c_int = libc::SIGUSR1;
+pub const MY_SIGUSR2: c_int = libc::SIGUSR2;
+// Pedido de terminar. El runtime no hace nada por su cuenta: el hilo lo ve al volver de pause
+pub const MY_SIGTERM: c_int = libc::SIGTERM;
+
+pub const MY_SIG_MAX: c_int = 64;
+
+pub fn is_valid_signal(sig: c_int) -> bool {
+    (1..=MY_SIG_MAX).contains(&sig)
+}
+
+// Bit de `sig` dentro de un MySigSet (0 si la señal no es válida).
+pub fn my_sigbit(sig: c_int) -> MySigSet {
+    if is_valid_signal(sig) { 1 << (sig - 1) } else { 0 }
+}
+
+// Señales del proceso que llegaron y todavía no se repartieron a ningún hilo.
+// Solo se toca con operaciones atómicas, así el handler del SO es async-signal-safe.
+static PROCESS_PENDING: AtomicU64 = AtomicU64::new(0);
+
+extern "C" fn on_process_signal(sig: c_int) {
+    PROCESS_PENDING.fetch_or(my_sigbit(sig), Ordering::SeqCst);
+}
+
+// Instala el handler del SO que anota `sig` para que el runtime la reparta en el próximo despacho.
+pub(crate) fn install_process_handler(sig: c_int) -> c_int {
+    unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = on_process_signal as extern "C" fn(c_int) as usize;
+        action.sa_flags = libc::SA_RESTART;
+        libc::sigemptyset(&mut action.sa_mask);
+        libc::sigaction(sig, &action, std::ptr::null_mut())
+    }
+}
+
+// Saca las señales del proceso que pertenecen a `routed` y deja las demás para otros runtimes.
+pub(crate) fn take_process_pending(routed: MySigSet) -> MySigSet {
+    PROCESS_PENDING.fetch_and(!routed, Ordering::SeqCst) & routed
+}
+
+// Señales presentes en `set`, de menor a mayor.
+pub(crate) fn signals_in(set: MySigSet) -> impl Iterator<Item = c_int> {
+    (1..=MY_SIG_MAX).filter(move |&sig| set & my_sigbit(sig) != 0)
+}
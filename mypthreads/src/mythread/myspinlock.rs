@@ -0,0 +1,85 @@
+use std::os::raw::c_int;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use crate::mythread::mystack::{green_owner_tag, held_by_green_here, park};
+use crate::mythread::myerror::MyError;
+
+// Vueltas de espera activa antes de ceder el CPU
+pub const MY_SPIN_LIMIT: u32 = 128;
+
+// Spin lock al estilo pthread_spin_*. Con un solo hilo del SO girar para siempre no sirve (el dueño
+// nunca correría), así que después de MY_SPIN_LIMIT vueltas se cede: un hilo verde se estaciona y
+// deja correr a los demás; fuera de los hilos verdes se cede el hilo del SO si el dueño corre en otro.
+pub struct MySpinLock {
+    locked: AtomicBool,
+    // green_owner_tag de quien lo tiene
+    owner: AtomicUsize,
+}
+
+impl MySpinLock {
+    pub const fn new() -> Self {
+        Self { locked: AtomicBool::new(false), owner: AtomicUsize::new(0) }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::Acquire)
+    }
+
+    // 0 si lo tomó, EBUSY si ya estaba tomado.
+    pub fn try_lock(&self) -> c_int {
+        match self.locked.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed) {
+            Result::Ok(_) => {
+                self.owner.store(green_owner_tag(), Ordering::Relaxed);
+                0
+            }
+            Err(_) => MyError::Busy as c_int,
+        }
+    }
+
+    // Desde el driver no hay runtime que avanzar: si el dueño es un hilo verde de este hilo del SO
+    // devuelve EDEADLK (MyPThread::my_spin_lock sí despacha a los demás).
+    pub fn lock(&self) -> c_int {
+        self.lock_with(|| {
+            if park() {
+                return true;
+            }
+            if held_by_green_here(self.owner.load(Ordering::Relaxed)) {
+                return false;
+            }
+            std::thread::yield_now();
+            true
+        })
+    }
+
+    // `relax` se llama cada MY_SPIN_LIMIT vueltas; si devuelve false nadie puede soltar el lock
+    // y se devuelve EDEADLK en vez de girar para siempre.
+    pub(crate) fn lock_with(&self, mut relax: impl FnMut() -> bool) -> c_int {
+        let mut spins = 0;
+        while self.try_lock() != 0 {
+            spins += 1;
+            if spins < MY_SPIN_LIMIT {
+                std::hint::spin_loop();
+                continue;
+            }
+            spins = 0;
+            if !relax() {
+                return MyError::Deadlock as c_int;
+            }
+        }
+        0
+    }
+
+    // EPERM si no estaba tomado.
+    pub fn unlock(&self) -> c_int {
+        self.owner.store(0, Ordering::Relaxed);
+        match self.locked.compare_exchange(true, false, Ordering::Release, Ordering::Relaxed) {
+            Result::Ok(_) => 0,
+            Err(_) => MyError::NotPermitted as c_int,
+        }
+    }
+}
+
+impl Default for MySpinLock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,306 @@
+use std::cell::Cell;
+use std::io;
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+use std::sync::{Once, OnceLock};
+use crate::mythread::myfault::{my_thread_guard, take_fault};
+use crate::mythread::mythread::{AnyParam, MyTRoutine, ThreadId};
+
+// Pila propia de un hilo: mmap con una página PROT_NONE abajo (las pilas crecen hacia abajo),
+// así un desborde cae en la guarda y se detecta en vez de pisar memoria de otro.
+pub struct MyStack {
+    base: *mut u8,
+    len: usize,
+    guard: usize,
+    context: Box<ThreadContext>,
+}
+
+// Contexto guardado del hilo: dónde retomar si se estacionó (yield) antes de terminar.
+struct ThreadContext {
+    ctx: libc::ucontext_t,
+    started: bool,
+}
+
+// Rango de una pila ya reservada. Es Copy para poder correr el hilo sin tener prestado su MyThread.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct StackRegion {
+    guard_lo: usize,
+    guard_hi: usize,
+    top: usize,
+    context: *mut ThreadContext,
+}
+
+impl MyStack {
+    pub fn new(size: usize) -> io::Result<Self> {
+        let guard = page_size();
+        let size = size.div_ceil(guard) * guard;
+        let len = size + guard;
+
+        let base = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_NORESERVE | libc::MAP_STACK,
+                -1,
+                0,
+            )
+        };
+        if base == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        if unsafe { libc::mprotect(base, guard, libc::PROT_NONE) } != 0 {
+            let err = io::Error::last_os_error();
+            unsafe { libc::munmap(base, len) };
+            return Err(err);
+        }
+        let context = Box::new(ThreadContext { ctx: unsafe { std::mem::zeroed() }, started: false });
+        Ok(Self { base: base as *mut u8, len, guard, context })
+    }
+
+    // Bytes usables (sin contar la página de guarda).
+    pub fn size(&self) -> usize {
+        self.len - self.guard
+    }
+
+    pub(crate) fn region(&self) -> StackRegion {
+        let lo = self.base as usize;
+        let context = &*self.context as *const ThreadContext as *mut ThreadContext;
+        StackRegion { guard_lo: lo, guard_hi: lo + self.guard, top: lo + self.len, context }
+    }
+}
+
+impl Drop for MyStack {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.base as *mut c_void, self.len);
+        }
+    }
+}
+
+fn page_size() -> usize {
+    unsafe { libc::sysconf(libc::_SC_PAGESIZE) as usize }
+}
+
+// Resultado de correr la rutina de un hilo.
+pub(crate) enum RunOutcome {
+    Returned(*mut AnyParam),
+    // Cedió el CPU con park(); hay que volver a encolarlo y retomarlo después
+    Parked,
+    Overflowed,
+    Faulted(String),
+}
+
+// Un despacho de un hilo sobre su pila. Vive en la pila de quien lo despachó; se encadenan cuando
+// un hilo despacha a otros (join, mutex) para que el handler de SIGSEGV los pueda recorrer.
+// Un hilo estacionado se retoma con un Frame nuevo, posiblemente desde otro despachador.
+struct Frame {
+    tid: ThreadId,
+    region: StackRegion,
+    routine: MyTRoutine,
+    arg: *mut AnyParam,
+    ret: *mut AnyParam,
+    overflowed: bool,
+    parked: bool,
+    back: libc::ucontext_t,
+    prev: *mut Frame,
+}
+
+thread_local! {
+    // Hilo verde más reciente despachado desde este hilo del SO
+    static TOP: Cell<*mut Frame> = const { Cell::new(ptr::null_mut()) };
+    static ALT_STACK: AltStack = AltStack::install();
+    // Último hilo que se desbordó en este hilo del SO
+    static OVERFLOWED: Cell<Option<ThreadId>> = const { Cell::new(None) };
+}
+
+// Id del último hilo desbordado en este hilo del SO (lo identifica el handler de SIGSEGV).
+pub fn last_overflowed() -> Option<ThreadId> {
+    OVERFLOWED.get()
+}
+
+// Corre `routine(arg)` sobre la pila `region`, o la retoma donde se estacionó. Si el hilo se
+// desborda, el handler de SIGSEGV vuelve directo aquí y se devuelve Overflowed; lo que quedó en
+// su pila no se limpia.
+pub(crate) fn run_on_stack(tid: ThreadId, region: StackRegion, routine: MyTRoutine, arg: *mut AnyParam) -> RunOutcome {
+    install_segv_handler();
+    ALT_STACK.with(|_| {});
+
+    let mut frame = Box::new(Frame {
+        tid,
+        region,
+        routine,
+        arg,
+        ret: ptr::null_mut(),
+        overflowed: false,
+        parked: false,
+        back: unsafe { std::mem::zeroed() },
+        prev: TOP.get(),
+    });
+    let frame_ptr: *mut Frame = &mut *frame;
+
+    unsafe {
+        let context = region.context;
+        if !(*context).started {
+            let ctx = &mut (*context).ctx;
+            libc::getcontext(ctx);
+            ctx.uc_stack.ss_sp = region.guard_hi as *mut c_void;
+            ctx.uc_stack.ss_size = region.top - region.guard_hi;
+            ctx.uc_link = ptr::null_mut();
+            libc::makecontext(ctx, entry, 0);
+            (*context).started = true;
+        }
+
+        TOP.set(frame_ptr);
+        libc::swapcontext(&mut (*frame_ptr).back, &(*context).ctx);
+        TOP.set((*frame_ptr).prev);
+    }
+
+    if frame.overflowed {
+        OVERFLOWED.set(Some(frame.tid));
+        RunOutcome::Overflowed
+    } else if frame.parked {
+        RunOutcome::Parked
+    } else {
+        RunOutcome::Returned(frame.ret)
+    }
+}
+
+// Estaciona al hilo que corre sobre su pila: vuelve a quien lo despachó (que lo encola de nuevo
+// como Ready) y retorna cuando algún despachador lo retoma. Devuelve false si no hay hilo verde
+// sobre pila propia en este hilo del SO (driver o workers del runtime M:N).
+pub(crate) fn park() -> bool {
+    let frame = TOP.get();
+    if frame.is_null() {
+        return false;
+    }
+    unsafe {
+        (*frame).parked = true;
+        libc::swapcontext(&mut (*(*frame).region.context).ctx, &(*frame).back);
+    }
+    true
+}
+
+// Marca de quien toma un lock de espera activa (MySpinLock, MyOnce): su hilo del SO si corre en un
+// hilo verde sobre pila propia, 0 si no.
+pub(crate) fn green_owner_tag() -> usize {
+    if TOP.get().is_null() { 0 } else { unsafe { libc::pthread_self() as usize } }
+}
+
+// Fuera de los hilos verdes, un lock con la marca de este hilo del SO lo tiene un hilo verde que
+// solo avanza si alguien despacha aquí: esperarlo girando no termina nunca.
+pub(crate) fn held_by_green_here(tag: usize) -> bool {
+    tag != 0 && TOP.get().is_null() && tag == unsafe { libc::pthread_self() as usize }
+}
+
+// Sin pila propia (no se pudo reservar) la rutina corre sobre la pila de quien despacha.
+// Si la rutina hizo panic (la atrapa my_thread_guard aquí o en `entry`), el hilo sale como Faulted.
+pub(crate) fn run_routine(tid: ThreadId, region: Option<StackRegion>, routine: MyTRoutine, arg: *mut AnyParam) -> RunOutcome {
+    let outcome = match region {
+        Some(region) => run_on_stack(tid, region, routine, arg),
+        None => RunOutcome::Returned(my_thread_guard(|| routine(arg))),
+    };
+    match (outcome, take_fault()) {
+        (RunOutcome::Returned(_), Some(message)) => RunOutcome::Faulted(message),
+        (outcome, _) => outcome,
+    }
+}
+
+extern "C" fn entry() {
+    let start = TOP.get();
+    // El panic no puede salir de aquí: arriba de `entry` no hay nada en esta pila
+    let ret = my_thread_guard(|| unsafe { ((*start).routine)((*start).arg) });
+    // Si se estacionó en el camino pudo terminar bajo otro despachador: se vuelve al actual
+    let frame = TOP.get();
+    unsafe {
+        (*frame).ret = ret;
+        libc::setcontext(&(*frame).back);
+    }
+}
+
+// Pila alterna para que el handler de SIGSEGV pueda correr aunque la pila del hilo esté agotada.
+// Si el hilo del SO ya tiene una (std instala la suya) se usa esa.
+struct AltStack {
+    mem: *mut c_void,
+    len: usize,
+}
+
+impl AltStack {
+    fn install() -> Self {
+        unsafe {
+            let mut old: libc::stack_t = std::mem::zeroed();
+            libc::sigaltstack(ptr::null(), &mut old);
+            if old.ss_flags & libc::SS_DISABLE == 0 {
+                return Self { mem: ptr::null_mut(), len: 0 };
+            }
+
+            let len = libc::SIGSTKSZ.max(64 * 1024);
+            let mem = libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            );
+            if mem == libc::MAP_FAILED {
+                return Self { mem: ptr::null_mut(), len: 0 };
+            }
+            let stack = libc::stack_t { ss_sp: mem, ss_flags: 0, ss_size: len };
+            libc::sigaltstack(&stack, ptr::null_mut());
+            Self { mem, len }
+        }
+    }
+}
+
+impl Drop for AltStack {
+    fn drop(&mut self) {
+        if self.mem.is_null() {
+            return;
+        }
+        unsafe {
+            let disable = libc::stack_t { ss_sp: ptr::null_mut(), ss_flags: libc::SS_DISABLE, ss_size: 0 };
+            libc::sigaltstack(&disable, ptr::null_mut());
+            libc::munmap(self.mem, self.len);
+        }
+    }
+}
+
+// Acción que había para SIGSEGV antes de la nuestra; se le devuelve todo fallo que no sea un desborde.
+static PREVIOUS_SEGV: OnceLock<libc::sigaction> = OnceLock::new();
+
+fn install_segv_handler() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = on_segv as extern "C" fn(c_int, *mut libc::siginfo_t, *mut c_void) as usize;
+        action.sa_flags = libc::SA_SIGINFO | libc::SA_ONSTACK;
+        libc::sigemptyset(&mut action.sa_mask);
+
+        let mut previous: libc::sigaction = std::mem::zeroed();
+        libc::sigaction(libc::SIGSEGV, &action, &mut previous);
+        let _ = PREVIOUS_SEGV.set(previous);
+    });
+}
+
+extern "C" fn on_segv(_sig: c_int, info: *mut libc::siginfo_t, _ctx: *mut c_void) {
+    unsafe {
+        let addr = (*info).si_addr() as usize;
+        let mut frame = TOP.get();
+        while !frame.is_null() {
+            let region = (*frame).region;
+            if (region.guard_lo..region.guard_hi).contains(&addr) {
+                // Desborde de este hilo: se abandona su pila y se vuelve a quien lo despachó
+                (*frame).overflowed = true;
+                libc::setcontext(&(*frame).back);
+            }
+            frame = (*frame).prev;
+        }
+
+        // No es nuestro: se restaura la acción anterior y al retornar el fallo se repite con ella
+        match PREVIOUS_SEGV.get() {
+            Some(previous) => libc::sigaction(libc::SIGSEGV, previous, ptr::null_mut()),
+            None => libc::signal(libc::SIGSEGV, libc::SIG_DFL) as c_int,
+        };
+    }
+}
@@ -1,26 +1,69 @@
-use std::os::raw::c_void;
+use std::os::raw::{c_int, c_void};
+use std::time::Duration;
 use libc::pthread_t;
 pub use crate::mythread::mythreadattr::MyThreadAttr;
+use crate::mythread::myinspect::BlockedOn;
+use crate::mythread::mythreadattr::PriorityLevel;
+use crate::mythread::mysignal::MySigSet;
+use crate::mythread::mystack::MyStack;
 use crate::mythread::thread_state::ThreadState;
 use crate::scheduler::SchedulerType;
 
 pub type ThreadId = pthread_t;
 pub type AnyParam = c_void;
-pub type MyTRoutine =  extern "C" fn(*mut AnyParam) -> *mut AnyParam;
+pub type MyTRoutine =  extern "C-unwind" fn(*mut AnyParam) -> *mut AnyParam;
+
+// Lo que devuelve my_thread_self fuera de cualquier hilo verde (desde el driver).
+pub const MY_DRIVER_THREAD: ThreadId = ThreadId::MAX;
 
 pub struct MyThread {
     pub(crate) id: ThreadId,
     pub(crate) state: ThreadState,
-    pub(crate) attr: *mut MyThreadAttr,
+    pub(crate) attr: MyThreadAttr,
     pub(crate) start_routine: MyTRoutine,
     pub(crate) arg: *mut AnyParam,
     pub(crate) ret_val: *mut AnyParam,
     pub(crate) scheduler: SchedulerType,
+    // Señales enviadas con my_thread_kill que esperan a que el hilo se despache
+    pub(crate) sig_pending: MySigSet,
+    pub(crate) sig_mask: MySigSet,
+    // Se reserva al despacharlo por primera vez y se libera al terminar
+    pub(crate) stack: Option<MyStack>,
+    // Código con que terminó (0 salvo que el runtime lo haya tenido que matar: MyError::StackOverflow o Faulted)
+    pub(crate) exit_code: c_int,
+    pub(crate) panic_message: Option<String>,
+    pub(crate) name: Option<String>,
+    // Qué esperaba la última vez que quedó Blocked
+    pub(crate) blocked_on: Option<BlockedOn>,
+    // Veces que se despachó y tiempo de CPU sin contar los hilos que corrió encima (join, lock...)
+    pub(crate) runs: usize,
+    pub(crate) cpu_time: Duration,
+    // Despacho del runtime en que entró a la cola de listos (None si no está encolado)
+    pub(crate) ready_since: Option<usize>,
+    // Máximo de despachos que esperó listo y veces que el aging lo promovió
+    pub(crate) max_wait: usize,
+    pub(crate) promotions: usize,
+}
 
+// Foto de un hilo para my_runtime_list_threads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MyThreadInfo {
+    pub id: ThreadId,
+    pub name: Option<String>,
+    pub state: ThreadState,
+    pub scheduler: SchedulerType,
+    pub priority: PriorityLevel,
+    pub dead_line: usize,
+    // Solo si está Blocked
+    pub blocked_on: Option<BlockedOn>,
+    pub runs: usize,
+    pub cpu_time: Duration,
+    pub max_wait: usize,
+    pub promotions: usize,
 }
 
 impl MyThread {
-    pub fn new(id: ThreadId, attr: *mut MyThreadAttr, routine: MyTRoutine, arg: *mut AnyParam, scheduler: Option<SchedulerType>) -> Self {
+    pub fn new(id: ThreadId, attr: MyThreadAttr, routine: MyTRoutine, arg: *mut AnyParam, scheduler: Option<SchedulerType>) -> Self {
         Self {
             id,
             state: ThreadState::New,
@@ -29,6 +72,18 @@ impl MyThread {
             arg,
             ret_val: std::ptr::null_mut(),
             scheduler: scheduler.unwrap_or_default() ,
+            sig_pending: 0,
+            sig_mask: 0,
+            stack: None,
+            exit_code: 0,
+            panic_message: None,
+            name: None,
+            blocked_on: None,
+            runs: 0,
+            cpu_time: Duration::ZERO,
+            ready_since: None,
+            max_wait: 0,
+            promotions: 0,
         }
     }
     
@@ -48,6 +103,30 @@ impl MyThread {
         self.id
     }
 
+    pub fn attr(&self) -> &MyThreadAttr {
+        &self.attr
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn info(&self) -> MyThreadInfo {
+        MyThreadInfo {
+            id: self.id,
+            name: self.name.clone(),
+            state: self.state,
+            scheduler: self.scheduler,
+            priority: self.attr.priority,
+            dead_line: self.attr.dead_line,
+            blocked_on: self.blocked_on.filter(|_| self.state == ThreadState::Blocked),
+            runs: self.runs,
+            cpu_time: self.cpu_time,
+            max_wait: self.max_wait,
+            promotions: self.promotions,
+        }
+    }
+
 }
 
 
@@ -1,17 +1,29 @@
+use std::os::raw::c_int;
+use crate::scheduler::{GroupId, ServerId};
 use libc::{
     pthread_attr_t,
     pthread_attr_init,
     pthread_attr_destroy,
+    pthread_attr_getstacksize,
+    pthread_attr_setstacksize,
+    EINVAL,
 };
 
-use crate::scheduler::SchedulerType;
-
 pub type PriorityLevel = u8;
+
+// Valores de detachstate, iguales a los de pthread
+pub const MY_THREAD_CREATE_JOINABLE: c_int = libc::PTHREAD_CREATE_JOINABLE;
+pub const MY_THREAD_CREATE_DETACHED: c_int = libc::PTHREAD_CREATE_DETACHED;
+
 pub struct MyThreadAttr {
     inner: pthread_attr_t,
     pub(crate) dead_line: usize,
     pub(crate) priority: PriorityLevel,
     pub(crate) detached: bool,
+    // Grupo de scheduling donde entra el hilo (None: los schedulers normales)
+    pub(crate) group: Option<GroupId>,
+    // Servidor aperiódico de RealTime donde corre el hilo (None: EDF con su propio deadline)
+    pub(crate) server: Option<ServerId>,
 }
 
 impl MyThreadAttr {
@@ -22,7 +34,7 @@ impl MyThreadAttr {
         unsafe {
             let mut attr: pthread_attr_t = std::mem::zeroed();
             pthread_attr_init(&mut attr);
-            Self { inner: attr, dead_line, priority, detached: false }
+            Self { inner: attr, dead_line, priority, detached: false, group: None, server: None }
         }
     }
 
@@ -30,16 +42,154 @@ impl MyThreadAttr {
         self.detached = true;
     }
 
+    pub fn is_detached(&self) -> bool {
+        self.detached
+    }
+
+    pub fn priority(&self) -> PriorityLevel {
+        self.priority
+    }
+
+    pub fn set_priority(&mut self, priority: PriorityLevel) {
+        self.priority = priority;
+    }
+
+    pub fn group(&self) -> Option<GroupId> {
+        self.group
+    }
+
+    pub fn set_group(&mut self, group: Option<GroupId>) {
+        self.group = group;
+    }
+
+    pub fn server(&self) -> Option<ServerId> {
+        self.server
+    }
+
+    pub fn set_server(&mut self, server: Option<ServerId>) {
+        self.server = server;
+    }
+
+    pub fn dead_line(&self) -> usize {
+        self.dead_line
+    }
+
+    pub fn set_dead_line(&mut self, dead_line: usize) {
+        self.dead_line = dead_line;
+    }
+
+    // Tamaño de la pila propia del hilo; se guarda en el pthread_attr_t igual que en pthread.
+    pub fn stack_size(&self) -> usize {
+        let mut size = 0;
+        unsafe {
+            pthread_attr_getstacksize(&self.inner, &mut size);
+        }
+        size
+    }
+
+    // Devuelve EINVAL si es menor que PTHREAD_STACK_MIN.
+    pub fn set_stack_size(&mut self, size: usize) -> c_int {
+        unsafe { pthread_attr_setstacksize(&mut self.inner, size) }
+    }
+
     // Devuelve un puntero al pthread_attr_t interno (para pasar a pthread_create)
     pub fn c_pointer(&self) -> *const pthread_attr_t {
         &self.inner
     }
 }
 
+// Lo que usa my_thread_create cuando recibe un attr nulo: joinable, 1 ticket y sin deadline.
+impl Default for MyThreadAttr {
+    fn default() -> Self {
+        Self::new(usize::MAX, 1)
+    }
+}
+
+// Cada hilo guarda su propia copia; el pthread_attr_t no se puede copiar byte a byte, se inicializa otro.
+impl Clone for MyThreadAttr {
+    fn clone(&self) -> Self {
+        let mut copy = Self::new(self.dead_line, self.priority);
+        copy.detached = self.detached;
+        copy.group = self.group;
+        copy.server = self.server;
+        copy.set_stack_size(self.stack_size());
+        copy
+    }
+}
+
 impl Drop for MyThreadAttr {
     fn drop(&mut self) {
         unsafe {
             pthread_attr_destroy(&mut self.inner);
         }
     }
-}
\ No newline at end of file
+}
+
+// Accesores estilo pthread_attr_get*/set*. Devuelven 0 o EINVAL.
+
+/// # Safety
+/// `attr` debe ser nulo o apuntar a un MyThreadAttr válido.
+pub unsafe extern "C" fn my_attr_setdetachstate(attr: *mut MyThreadAttr, state: c_int) -> c_int {
+    let Some(attr) = (unsafe { attr.as_mut() }) else { return EINVAL };
+    match state {
+        MY_THREAD_CREATE_JOINABLE => attr.detached = false,
+        MY_THREAD_CREATE_DETACHED => attr.detached = true,
+        _ => return EINVAL,
+    }
+    0
+}
+
+/// # Safety
+/// `attr` y `state` deben ser nulos o punteros válidos.
+pub unsafe extern "C" fn my_attr_getdetachstate(attr: *const MyThreadAttr, state: *mut c_int) -> c_int {
+    let (Some(attr), Some(state)) = (unsafe { attr.as_ref() }, unsafe { state.as_mut() }) else { return EINVAL };
+    *state = if attr.detached { MY_THREAD_CREATE_DETACHED } else { MY_THREAD_CREATE_JOINABLE };
+    0
+}
+
+/// # Safety
+/// `attr` debe ser nulo o apuntar a un MyThreadAttr válido.
+pub unsafe extern "C" fn my_attr_setpriority(attr: *mut MyThreadAttr, priority: PriorityLevel) -> c_int {
+    let Some(attr) = (unsafe { attr.as_mut() }) else { return EINVAL };
+    attr.priority = priority;
+    0
+}
+
+/// # Safety
+/// `attr` y `priority` deben ser nulos o punteros válidos.
+pub unsafe extern "C" fn my_attr_getpriority(attr: *const MyThreadAttr, priority: *mut PriorityLevel) -> c_int {
+    let (Some(attr), Some(priority)) = (unsafe { attr.as_ref() }, unsafe { priority.as_mut() }) else { return EINVAL };
+    *priority = attr.priority;
+    0
+}
+
+/// # Safety
+/// `attr` debe ser nulo o apuntar a un MyThreadAttr válido.
+pub unsafe extern "C" fn my_attr_setdeadline(attr: *mut MyThreadAttr, dead_line: usize) -> c_int {
+    let Some(attr) = (unsafe { attr.as_mut() }) else { return EINVAL };
+    attr.dead_line = dead_line;
+    0
+}
+
+/// # Safety
+/// `attr` y `dead_line` deben ser nulos o punteros válidos.
+pub unsafe extern "C" fn my_attr_getdeadline(attr: *const MyThreadAttr, dead_line: *mut usize) -> c_int {
+    let (Some(attr), Some(dead_line)) = (unsafe { attr.as_ref() }, unsafe { dead_line.as_mut() }) else { return EINVAL };
+    *dead_line = attr.dead_line;
+    0
+}
+
+/// # Safety
+/// `attr` debe ser nulo o apuntar a un MyThreadAttr válido.
+pub unsafe extern "C" fn my_attr_setstacksize(attr: *mut MyThreadAttr, size: usize) -> c_int {
+    let Some(attr) = (unsafe { attr.as_mut() }) else { return EINVAL };
+    attr.set_stack_size(size)
+}
+
+/// # Safety
+/// `attr` y `size` deben ser nulos o punteros válidos.
+pub unsafe extern "C" fn my_attr_getstacksize(attr: *const MyThreadAttr, size: *mut usize) -> c_int {
+    let (Some(attr), Some(size)) = (unsafe { attr.as_ref() }, unsafe { size.as_mut() }) else { return EINVAL };
+    *size = attr.stack_size();
+    0
+}
@@ -5,6 +5,14 @@ pub enum ThreadState {
     Running,
     Blocked,
     Terminated,
+    Faulted, // la rutina hizo panic; el resto del runtime sigue
+}
+
+impl ThreadState {
+    // Terminated o Faulted: el hilo ya no va a volver a correr.
+    pub fn is_finished(self) -> bool {
+        matches!(self, ThreadState::Terminated | ThreadState::Faulted)
+    }
 }
 
 
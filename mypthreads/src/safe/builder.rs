@@ -0,0 +1,137 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::rc::Rc;
+use crate::mythread::myfault::my_thread_guard;
+use crate::mythread::mythread::{AnyParam, ThreadId};
+use crate::mythread::mythreadattr::{MyThreadAttr, PriorityLevel};
+use crate::safe::executor::drive;
+use crate::safe::join_handle::JoinHandle;
+use crate::safe::runtime::Runtime;
+use crate::scheduler::{GroupId, SchedulerType, ServerId};
+
+type Body = Box<dyn FnOnce()>;
+
+// Punto de entrada C de todos los hilos creados con closures. Un panic deja al hilo Faulted.
+extern "C-unwind" fn trampoline(arg: *mut AnyParam) -> *mut AnyParam {
+    let body = unsafe { Box::from_raw(arg as *mut Body) };
+    my_thread_guard(|| {
+        body();
+        std::ptr::null_mut()
+    })
+}
+
+// Configuración de un hilo antes de crearlo: scheduler, prioridad (tickets) y deadline.
+pub struct Builder {
+    rt: Runtime,
+    scheduler: SchedulerType,
+    priority: PriorityLevel,
+    deadline: usize,
+    stack_size: Option<usize>,
+    name: Option<String>,
+    group: Option<GroupId>,
+    server: Option<ServerId>,
+}
+
+impl Builder {
+    pub(crate) fn new(rt: Runtime) -> Self {
+        Self {
+            rt,
+            scheduler: SchedulerType::default(),
+            priority: 1,
+            deadline: usize::MAX,
+            stack_size: None,
+            name: None,
+            group: None,
+            server: None,
+        }
+    }
+
+    pub fn scheduler(mut self, kind: SchedulerType) -> Self {
+        self.scheduler = kind;
+        self
+    }
+
+    // En Lottery la prioridad se usa como cantidad de tickets.
+    pub fn priority(mut self, priority: PriorityLevel) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    // Solo lo usa el scheduler RealTime (menor deadline corre primero).
+    pub fn deadline(mut self, deadline: usize) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    // Tamaño de la pila propia del hilo (por defecto el de pthread_attr_init).
+    pub fn stack_size(mut self, size: usize) -> Self {
+        self.stack_size = Some(size);
+        self
+    }
+
+    // Nombre para trazas e introspección, p.ej. "car-17".
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    // Grupo de scheduling (ver Runtime::create_group); su scheduler reemplaza al de `scheduler`.
+    pub fn group(mut self, group: GroupId) -> Self {
+        self.group = Some(group);
+        self
+    }
+
+    // Hilo aperiódico de RealTime: corre con el presupuesto del servidor (ver Runtime::create_server)
+    // en vez de con su propio deadline.
+    pub fn server(mut self, server: ServerId) -> Self {
+        self.scheduler = SchedulerType::RealTime;
+        self.server = Some(server);
+        self
+    }
+
+    pub fn spawn<F, T>(self, f: F) -> JoinHandle<T>
+    where
+        F: FnOnce() -> T + 'static,
+        T: 'static,
+    {
+        let packet: Rc<RefCell<Option<T>>> = Rc::new(RefCell::new(None));
+        let slot = packet.clone();
+        let body: Body = Box::new(move || {
+            *slot.borrow_mut() = Some(f());
+        });
+        let arg = Box::into_raw(Box::new(body)) as *mut AnyParam;
+
+        // my_thread_create copia el attr, puede quedarse en el stack
+        let mut attr = MyThreadAttr::new(self.deadline, self.priority);
+        attr.set_group(self.group);
+        attr.set_server(self.server);
+        if let Some(size) = self.stack_size {
+            assert_eq!(attr.set_stack_size(size), 0, "tamaño de pila inválido: {size}");
+        }
+        let mut tid: ThreadId = 0;
+        let rc = unsafe {
+            self.rt.inner.borrow_mut().pth.my_thread_create(&mut tid, &attr, trampoline, arg, Some(self.scheduler))
+        };
+        if rc != 0 {
+            // El hilo nunca se creó, recuperamos el closure para no filtrarlo
+            drop(unsafe { Box::from_raw(arg as *mut Body) });
+            panic!("my_thread_create falló con código {rc}");
+        }
+
+        if let Some(name) = &self.name {
+            self.rt.set_name(tid, name);
+        }
+        JoinHandle::new(self.rt, tid, packet)
+    }
+
+    // Tarea async en su propio hilo con este scheduler, prioridad y deadline. Con Pending el hilo
+    // queda Blocked hasta que su waker lo despierte; dentro puede usar también Mutex o canales.
+    pub fn spawn_async<F>(self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        let rt = self.rt.clone();
+        self.spawn(move || drive(&rt, future))
+    }
+}
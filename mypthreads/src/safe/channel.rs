@@ -0,0 +1,371 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use crate::mythread::myinspect::BlockedOn;
+use crate::mythread::mythread::ThreadId;
+use crate::safe::runtime::Runtime;
+
+// Canales entre hilos verdes. `send` espera si el canal acotado está lleno y `recv` si está vacío;
+// mientras tanto el hilo queda estacionado Blocked, fuera de las colas, hasta que del otro lado
+// alguien envíe o reciba. Desde el driver (fuera de los hilos verdes) se corren los demás hilos
+// encima, igual que en Mutex.
+//
+// MyChannel es MPMC: cada clon puede enviar y recibir. Con `split` queda MPSC: MySender se puede
+// clonar y MyReceiver no.
+
+struct Chan<T> {
+    queue: VecDeque<T>,
+    // None: sin límite
+    capacity: Option<usize>,
+    senders: usize,
+    receivers: usize,
+    // Hilos estacionados esperando algo que recibir / espacio para enviar
+    waiting_recv: Vec<ThreadId>,
+    waiting_send: Vec<ThreadId>,
+}
+
+impl<T> Chan<T> {
+    fn is_full(&self) -> bool {
+        self.capacity.is_some_and(|cap| self.queue.len() >= cap)
+    }
+}
+
+// Despierta a todos los de `waiting`; cada uno vuelve a revisar el canal al correr.
+fn wake_all(rt: &Runtime, waiting: Vec<ThreadId>) {
+    for tid in waiting {
+        rt.wake(tid);
+    }
+}
+
+// Espera hasta que `ready` se cumpla. En un hilo verde se anota con `watch`, se estaciona y al
+// despertar se borra con `unwatch`: quien lo despertó pudo no dejar nada para él. False si ya nadie
+// puede hacer que se cumpla.
+fn block_until(rt: &Runtime, ready: impl Fn() -> bool, watch: impl Fn(ThreadId), unwatch: impl Fn(ThreadId)) -> bool {
+    while !ready() {
+        let Some(me) = rt.current() else { return rt.wait_until(BlockedOn::Channel, &ready) };
+        watch(me);
+        let parked = rt.park_blocked(BlockedOn::Channel);
+        unwatch(me);
+        if !parked {
+            // Hilo sin pila propia: no se puede estacionar
+            return rt.wait_until(BlockedOn::Channel, &ready);
+        }
+    }
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrySendError<T> {
+    Full(T),
+    // Ya no queda quien reciba
+    Disconnected(T),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    Empty,
+    // Vacío y ya no queda quien envíe
+    Disconnected,
+}
+
+// Punta de envío y recepción compartida por MyChannel, MySender y MyReceiver.
+struct Endpoint<T> {
+    rt: Runtime,
+    chan: Rc<RefCell<Chan<T>>>,
+}
+
+impl<T> Endpoint<T> {
+    fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        let mut chan = self.chan.borrow_mut();
+        if chan.receivers == 0 {
+            return Err(TrySendError::Disconnected(value));
+        }
+        if chan.is_full() {
+            return Err(TrySendError::Full(value));
+        }
+        chan.queue.push_back(value);
+        let waiting = std::mem::take(&mut chan.waiting_recv);
+        drop(chan);
+        wake_all(&self.rt, waiting);
+        Ok(())
+    }
+
+    fn send(&self, mut value: T) -> Result<(), T> {
+        loop {
+            match self.try_send(value) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Disconnected(v)) => return Err(v),
+                Err(TrySendError::Full(v)) => value = v,
+            }
+            let chan = &self.chan;
+            let room = block_until(
+                &self.rt,
+                || {
+                    let chan = chan.borrow();
+                    !chan.is_full() || chan.receivers == 0
+                },
+                |me| chan.borrow_mut().waiting_send.push(me),
+                |me| chan.borrow_mut().waiting_send.retain(|&t| t != me),
+            );
+            if !room {
+                // Nadie más puede correr para hacer espacio
+                return Err(value);
+            }
+        }
+    }
+
+    fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut chan = self.chan.borrow_mut();
+        match chan.queue.pop_front() {
+            Some(value) => {
+                let waiting = std::mem::take(&mut chan.waiting_send);
+                drop(chan);
+                wake_all(&self.rt, waiting);
+                Ok(value)
+            }
+            None if chan.senders == 0 => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    fn recv(&self) -> Option<T> {
+        loop {
+            match self.try_recv() {
+                Ok(value) => return Some(value),
+                Err(TryRecvError::Disconnected) => return None,
+                Err(TryRecvError::Empty) => {}
+            }
+            if !block_until(&self.rt, || self.ready(), |me| self.watch(me), |me| self.unwatch(me)) {
+                // Nadie más puede correr para enviar
+                return None;
+            }
+        }
+    }
+
+    // Hay algo que recibir o ya nunca lo va a haber.
+    fn ready(&self) -> bool {
+        let chan = self.chan.borrow();
+        !chan.queue.is_empty() || chan.senders == 0
+    }
+
+    fn watch(&self, tid: ThreadId) {
+        self.chan.borrow_mut().waiting_recv.push(tid);
+    }
+
+    fn unwatch(&self, tid: ThreadId) {
+        self.chan.borrow_mut().waiting_recv.retain(|&t| t != tid);
+    }
+
+    fn len(&self) -> usize {
+        self.chan.borrow().queue.len()
+    }
+
+    // Suelta una punta; al irse el último de un lado se despierta a los que esperan del otro.
+    fn release(&self, sender: bool, receiver: bool) {
+        let mut chan = self.chan.borrow_mut();
+        chan.senders -= sender as usize;
+        chan.receivers -= receiver as usize;
+        let mut waiting = Vec::new();
+        if sender && chan.senders == 0 {
+            waiting.append(&mut chan.waiting_recv);
+        }
+        if receiver && chan.receivers == 0 {
+            waiting.append(&mut chan.waiting_send);
+        }
+        drop(chan);
+        wake_all(&self.rt, waiting);
+    }
+}
+
+pub struct MyChannel<T> {
+    end: Endpoint<T>,
+}
+
+pub struct MySender<T> {
+    end: Endpoint<T>,
+}
+
+pub struct MyReceiver<T> {
+    end: Endpoint<T>,
+}
+
+impl<T> MyChannel<T> {
+    // Canal que acepta a lo más `capacity` mensajes sin recibir (mínimo 1).
+    pub fn bounded(rt: &Runtime, capacity: usize) -> Self {
+        Self::with_capacity(rt, Some(capacity.max(1)))
+    }
+
+    pub fn unbounded(rt: &Runtime) -> Self {
+        Self::with_capacity(rt, None)
+    }
+
+    fn with_capacity(rt: &Runtime, capacity: Option<usize>) -> Self {
+        let chan = Chan {
+            queue: VecDeque::new(),
+            capacity,
+            senders: 1,
+            receivers: 1,
+            waiting_recv: Vec::new(),
+            waiting_send: Vec::new(),
+        };
+        Self { end: Endpoint { rt: rt.clone(), chan: Rc::new(RefCell::new(chan)) } }
+    }
+
+    // Parte el canal en una punta de envío clonable y una de recepción única (MPSC).
+    pub fn split(self) -> (MySender<T>, MyReceiver<T>) {
+        // Las puntas se cuentan antes de soltar el MyChannel, así el canal nunca se ve desconectado
+        {
+            let mut chan = self.end.chan.borrow_mut();
+            chan.senders += 1;
+            chan.receivers += 1;
+        }
+        let end = || Endpoint { rt: self.end.rt.clone(), chan: self.end.chan.clone() };
+        (MySender { end: end() }, MyReceiver { end: end() })
+    }
+
+    // Devuelve el valor si ya no hay receptores o si nadie más puede correr para hacer espacio.
+    pub fn send(&self, value: T) -> Result<(), T> {
+        self.end.send(value)
+    }
+
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        self.end.try_send(value)
+    }
+
+    // None si el canal se desconectó o si nadie más puede correr para enviar.
+    pub fn recv(&self) -> Option<T> {
+        self.end.recv()
+    }
+
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.end.try_recv()
+    }
+
+    pub fn len(&self) -> usize {
+        self.end.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> MySender<T> {
+    pub fn send(&self, value: T) -> Result<(), T> {
+        self.end.send(value)
+    }
+
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        self.end.try_send(value)
+    }
+}
+
+impl<T> MyReceiver<T> {
+    pub fn recv(&self) -> Option<T> {
+        self.end.recv()
+    }
+
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.end.try_recv()
+    }
+
+    pub fn len(&self) -> usize {
+        self.end.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Recibe lo que haya sin esperar.
+    pub fn try_iter(&self) -> impl Iterator<Item = T> + '_ {
+        std::iter::from_fn(|| self.try_recv().ok())
+    }
+}
+
+impl<T> Clone for MyChannel<T> {
+    fn clone(&self) -> Self {
+        let mut chan = self.end.chan.borrow_mut();
+        chan.senders += 1;
+        chan.receivers += 1;
+        Self { end: Endpoint { rt: self.end.rt.clone(), chan: self.end.chan.clone() } }
+    }
+}
+
+impl<T> Clone for MySender<T> {
+    fn clone(&self) -> Self {
+        self.end.chan.borrow_mut().senders += 1;
+        Self { end: Endpoint { rt: self.end.rt.clone(), chan: self.end.chan.clone() } }
+    }
+}
+
+impl<T> Drop for MyChannel<T> {
+    fn drop(&mut self) {
+        self.end.release(true, true);
+    }
+}
+
+impl<T> Drop for MySender<T> {
+    fn drop(&mut self) {
+        self.end.release(true, false);
+    }
+}
+
+impl<T> Drop for MyReceiver<T> {
+    fn drop(&mut self) {
+        self.end.release(false, true);
+    }
+}
+
+// Lo que puede esperar `select`: algo listo para recibir, o desconectado. `watch`/`unwatch` anotan
+// y borran al hilo que se estaciona esperándolo.
+pub trait Selectable {
+    fn ready(&self) -> bool;
+    fn watch(&self, tid: ThreadId);
+    fn unwatch(&self, tid: ThreadId);
+}
+
+impl<T> Selectable for MyChannel<T> {
+    fn ready(&self) -> bool {
+        self.end.ready()
+    }
+
+    fn watch(&self, tid: ThreadId) {
+        self.end.watch(tid)
+    }
+
+    fn unwatch(&self, tid: ThreadId) {
+        self.end.unwatch(tid)
+    }
+}
+
+impl<T> Selectable for MyReceiver<T> {
+    fn ready(&self) -> bool {
+        self.end.ready()
+    }
+
+    fn watch(&self, tid: ThreadId) {
+        self.end.watch(tid)
+    }
+
+    fn unwatch(&self, tid: ThreadId) {
+        self.end.unwatch(tid)
+    }
+}
+
+// Espera hasta que alguno de `channels` tenga algo que recibir (o se desconecte) y devuelve su
+// índice; si hay varios, el primero. Después `try_recv` en ese canal no espera. None si no hay
+// canales o si nadie más puede correr para enviar.
+pub fn select(rt: &Runtime, channels: &[&dyn Selectable]) -> Option<usize> {
+    if channels.is_empty() {
+        return None;
+    }
+    let ready = || channels.iter().any(|c| c.ready());
+    let found = block_until(
+        rt,
+        ready,
+        |me| channels.iter().for_each(|c| c.watch(me)),
+        |me| channels.iter().for_each(|c| c.unwatch(me)),
+    );
+    if found { channels.iter().position(|c| c.ready()) } else { None }
+}
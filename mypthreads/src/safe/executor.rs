@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, Weak};
+use std::task::{Context, Poll, Wake, Waker};
+use crate::mythread::myinspect::BlockedOn;
+use crate::mythread::mythread::ThreadId;
+use crate::safe::runtime::Runtime;
+
+// Futures sobre el runtime: cada tarea async es un hilo verde con su SchedulerType que hace poll a
+// su future. Con Pending se estaciona Blocked y su waker la vuelve a encolar. Los wakers se pueden
+// mandar a otros hilos del SO: anotan la tarea en WakeQueue y el runtime la despierta en el
+// siguiente despacho.
+
+#[derive(Default)]
+pub(crate) struct WakeQueue {
+    woken: Mutex<Vec<ThreadId>>,
+    ready: Condvar,
+    // Tareas vivas; si nadie más tiene su waker ya no las puede despertar nadie
+    tasks: Mutex<HashMap<ThreadId, Weak<TaskWaker>>>,
+}
+
+impl WakeQueue {
+    // Anota el wake de `tid`; se puede llamar desde cualquier hilo del SO.
+    pub(crate) fn push(&self, tid: ThreadId) {
+        self.woken.lock().unwrap().push(tid);
+        self.ready.notify_all();
+    }
+
+    pub(crate) fn take(&self) -> Vec<ThreadId> {
+        std::mem::take(&mut *self.woken.lock().unwrap())
+    }
+
+    // Alguna de `parked` espera un waker que anda suelto (p.ej. en otro hilo del SO).
+    fn can_be_woken(&self, parked: impl Fn(ThreadId) -> bool) -> bool {
+        let tasks = self.tasks.lock().unwrap();
+        tasks.iter().any(|(&tid, waker)| parked(tid) && waker.strong_count() > 1)
+    }
+
+    // Duerme hasta que llegue un wake. False sin esperar si ninguna tarea estacionada puede recibirlo.
+    pub(crate) fn wait(&self, parked: impl Fn(ThreadId) -> bool) -> bool {
+        if !self.can_be_woken(parked) {
+            return false;
+        }
+        let woken = self.woken.lock().unwrap();
+        drop(self.ready.wait_while(woken, |w| w.is_empty()).unwrap());
+        true
+    }
+}
+
+struct TaskWaker {
+    tid: ThreadId,
+    woken: Arc<AtomicBool>,
+    queue: Arc<WakeQueue>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        // Un solo aviso por cada vez que se estaciona
+        if !self.woken.swap(true, Ordering::AcqRel) {
+            self.queue.push(self.tid);
+        }
+    }
+}
+
+// Cuerpo del hilo de una tarea async: poll hasta Ready, estacionándose entre un wake y otro.
+pub(crate) fn drive<F: Future>(rt: &Runtime, future: F) -> F::Output {
+    let me = rt.current().expect("una tarea async corre en su propio hilo");
+    let queue = rt.wake_queue();
+    let woken = Arc::new(AtomicBool::new(false));
+    let task = Arc::new(TaskWaker { tid: me, woken: woken.clone(), queue: queue.clone() });
+    queue.tasks.lock().unwrap().insert(me, Arc::downgrade(&task));
+    // Aquí queda la única referencia propia: si el future no clona el waker nadie más la tiene
+    let waker = Waker::from(task);
+
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(&waker);
+    let out = loop {
+        woken.store(false, Ordering::Release);
+        if let Poll::Ready(out) = future.as_mut().poll(&mut cx) {
+            break out;
+        }
+        // Se despertó sola mientras corría (p.ej. yield_async): sigue lista, detrás de los demás
+        if woken.load(Ordering::Acquire) {
+            rt.yield_now();
+            continue;
+        }
+        if !rt.park_blocked(BlockedOn::Waker) {
+            // Sin pila propia no se puede estacionar: se corren los demás encima hasta el wake (los que
+            // esperan algo de esta tarea se estacionan y le devuelven el CPU). Si ya no queda nada que
+            // correr se duerme hasta que llegue su wake desde otro hilo del SO
+            while !rt.wait_until(BlockedOn::Waker, || woken.load(Ordering::Acquire)) {
+                assert!(queue.wait(|tid| tid == me), "deadlock: nadie puede despertar a la tarea {me}");
+            }
+        }
+    };
+    queue.tasks.lock().unwrap().remove(&me);
+    out
+}
+
+// Pending una vez y se despierta sola: le da el turno a los demás hilos listos.
+pub async fn yield_async() {
+    let mut yielded = false;
+    std::future::poll_fn(|cx| {
+        if yielded {
+            return Poll::Ready(());
+        }
+        yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    })
+    .await
+}
+
+impl Runtime {
+    // Despierta a las tareas async que recibieron wake desde el último despacho.
+    pub(crate) fn drain_wakes(&self) {
+        for tid in self.wake_queue().take() {
+            self.wake(tid);
+        }
+    }
+
+    // Sin nada listo, espera el wake de alguna tarea estacionada cuyo waker tiene alguien más.
+    pub(crate) fn wait_wakes(&self) -> bool {
+        let queue = self.wake_queue();
+        queue.wait(|tid| self.inner.borrow().parked.contains(&tid))
+    }
+}
@@ -0,0 +1,288 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::mythread::myreplay::{my_replay_log, splitmix64, MyEvent, MyEventLog};
+use crate::mythread::mythread::ThreadId;
+use crate::safe::join_handle::JoinHandle;
+use crate::safe::runtime::Runtime;
+
+// Prueba sistemática de concurrencia: corre el mismo escenario muchas veces escogiendo en cada
+// despacho (yield, espera de un mutex, join) qué hilo listo sigue, y revisa invariantes en cada
+// punto. Si algo falla devuelve la corrida como MyEventLog para repetirla con `replay_schedule`.
+
+type Invariant = Box<dyn Fn() -> Result<(), String>>;
+
+// Lo que arma cada corrida: los hilos a explorar y las invariantes a revisar.
+pub struct Scenario {
+    rt: Runtime,
+    handles: Vec<JoinHandle<()>>,
+    invariants: Vec<Invariant>,
+}
+
+impl Scenario {
+    pub fn runtime(&self) -> &Runtime {
+        &self.rt
+    }
+
+    pub fn spawn(&mut self, f: impl FnOnce() + 'static) {
+        let handle = self.rt.spawn(f);
+        self.handles.push(handle);
+    }
+
+    // Se revisa antes de cada despacho y al final. No debe usar el runtime: corre mientras
+    // el runtime escoge el siguiente hilo.
+    pub fn invariant(&mut self, check: impl Fn() -> Result<(), String> + 'static) {
+        self.invariants.push(Box::new(check));
+    }
+
+    fn check(&self) -> Result<(), String> {
+        self.invariants.iter().try_for_each(|check| check())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    // Recorre todos los órdenes en profundidad, hasta `max_schedules` corridas
+    Dfs { max_schedules: usize },
+    // Probabilistic Concurrency Testing: prioridades al azar con `depth - 1` cambios por corrida
+    Pct { runs: usize, depth: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExploreReport {
+    pub schedules: usize,
+    // Dfs recorrió todos los órdenes posibles
+    pub exhausted: bool,
+    // Corridas cortadas por pasar de `max_steps` despachos
+    pub truncated: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExploreFailure {
+    pub message: String,
+    // Número de corrida (desde 0) en que apareció
+    pub schedule: usize,
+    // Semilla, hilos despachados y sorteos de la corrida que falló
+    pub trace: MyEventLog,
+}
+
+pub struct Explorer {
+    strategy: Strategy,
+    seed: u64,
+    max_steps: usize,
+}
+
+// Cómo terminó una corrida
+enum Outcome {
+    Passed,
+    Truncated,
+    Failed(String),
+}
+
+impl Explorer {
+    pub fn dfs(max_schedules: usize) -> Self {
+        Self::new(Strategy::Dfs { max_schedules })
+    }
+
+    pub fn pct(runs: usize, depth: usize) -> Self {
+        Self::new(Strategy::Pct { runs, depth: depth.max(1) })
+    }
+
+    fn new(strategy: Strategy) -> Self {
+        Self { strategy, seed: 1, max_steps: 10_000 }
+    }
+
+    // Semilla del runtime de cada corrida y de los sorteos de PCT.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    // Despachos máximos por corrida; más que eso se toma como un ciclo y la corrida se descarta.
+    pub fn max_steps(mut self, steps: usize) -> Self {
+        self.max_steps = steps;
+        self
+    }
+
+    pub fn run(&self, mut setup: impl FnMut(&mut Scenario)) -> Result<ExploreReport, ExploreFailure> {
+        match self.strategy {
+            Strategy::Dfs { max_schedules } => self.run_dfs(max_schedules, &mut setup),
+            Strategy::Pct { runs, depth } => self.run_pct(runs, depth, &mut setup),
+        }
+    }
+
+    fn run_dfs(&self, max_schedules: usize, setup: &mut dyn FnMut(&mut Scenario)) -> Result<ExploreReport, ExploreFailure> {
+        let mut report = ExploreReport { schedules: 0, exhausted: false, truncated: 0 };
+        let mut prefix: Vec<usize> = Vec::new();
+
+        while report.schedules < max_schedules {
+            // (escogido, cuántos había) en cada despacho de esta corrida
+            let trail = Rc::new(RefCell::new(Vec::<(usize, usize)>::new()));
+            let forced = prefix.clone();
+            let t = trail.clone();
+            let choose = move |ready: &[ThreadId]| {
+                let mut trail = t.borrow_mut();
+                let choice = forced.get(trail.len()).copied().unwrap_or(0).min(ready.len() - 1);
+                trail.push((choice, ready.len()));
+                choice
+            };
+
+            let (outcome, trace) = self.run_once(setup, choose);
+            self.tally(&mut report, outcome, trace)?;
+
+            // Siguiente orden: el último despacho que todavía tenga otra opción
+            let mut trail = trail.take();
+            while let Some((choice, count)) = trail.pop() {
+                if choice + 1 < count {
+                    trail.push((choice + 1, count));
+                    break;
+                }
+            }
+            if trail.is_empty() {
+                report.exhausted = true;
+                break;
+            }
+            prefix = trail.into_iter().map(|(choice, _)| choice).collect();
+        }
+        Ok(report)
+    }
+
+    fn run_pct(&self, runs: usize, depth: usize, setup: &mut dyn FnMut(&mut Scenario)) -> Result<ExploreReport, ExploreFailure> {
+        let mut report = ExploreReport { schedules: 0, exhausted: false, truncated: 0 };
+        let mut rng = self.seed;
+        // Largo estimado de una corrida, para repartir los puntos de cambio
+        let mut expected_steps = 32usize;
+
+        for _ in 0..runs {
+            let change_points: Vec<usize> = (1..depth)
+                .map(|_| 1 + splitmix64(&mut rng) as usize % expected_steps)
+                .collect();
+            let mut priorities: HashMap<ThreadId, u64> = HashMap::new();
+            let mut run_rng = splitmix64(&mut rng);
+            let steps = Rc::new(RefCell::new(0usize));
+            let s = steps.clone();
+
+            let choose = move |ready: &[ThreadId]| {
+                let mut steps = s.borrow_mut();
+                *steps += 1;
+                // Cada hilo nuevo recibe una prioridad al azar por encima de las de los cambios
+                for &tid in ready {
+                    priorities.entry(tid).or_insert_with(|| depth as u64 + splitmix64(&mut run_rng) % (1 << 32));
+                }
+                let highest = |p: &HashMap<ThreadId, u64>| {
+                    (0..ready.len()).max_by_key(|&i| (p[&ready[i]], ready[i])).unwrap_or(0)
+                };
+                let mut choice = highest(&priorities);
+                if let Some(j) = change_points.iter().position(|&c| c == *steps) {
+                    // Punto de cambio: el hilo que iba a correr baja a la prioridad j + 1 (< depth)
+                    priorities.insert(ready[choice], j as u64 + 1);
+                    choice = highest(&priorities);
+                }
+                choice
+            };
+
+            let (outcome, trace) = self.run_once(setup, choose);
+            self.tally(&mut report, outcome, trace)?;
+            expected_steps = expected_steps.max(*steps.borrow());
+        }
+        Ok(report)
+    }
+
+    fn tally(&self, report: &mut ExploreReport, outcome: Outcome, trace: MyEventLog) -> Result<(), ExploreFailure> {
+        match outcome {
+            Outcome::Passed => {}
+            Outcome::Truncated => report.truncated += 1,
+            Outcome::Failed(message) => return Err(ExploreFailure { message, schedule: report.schedules, trace }),
+        }
+        report.schedules += 1;
+        Ok(())
+    }
+
+    // Una corrida con `choose` escogiendo el índice del siguiente hilo entre los listos.
+    fn run_once(
+        &self,
+        setup: &mut dyn FnMut(&mut Scenario),
+        mut choose: impl FnMut(&[ThreadId]) -> usize + 'static,
+    ) -> (Outcome, MyEventLog) {
+        let rt = Runtime::with_seed(self.seed);
+        let max_steps = self.max_steps;
+        let outcome = drive(rt, setup, max_steps, move |ready| Some(choose(ready)));
+        (outcome, my_replay_log())
+    }
+}
+
+// Corre el escenario una vez con `choose` decidiendo cada despacho.
+fn drive(
+    rt: Runtime,
+    setup: &mut dyn FnMut(&mut Scenario),
+    max_steps: usize,
+    mut choose: impl FnMut(&[ThreadId]) -> Option<usize> + 'static,
+) -> Outcome {
+    let scenario = Rc::new(RefCell::new(Scenario { rt: rt.clone(), handles: Vec::new(), invariants: Vec::new() }));
+    setup(&mut scenario.borrow_mut());
+
+    let verdict: Rc<RefCell<Option<Outcome>>> = Rc::new(RefCell::new(None));
+    let (sc, v) = (scenario.clone(), verdict.clone());
+    let mut steps = 0usize;
+    rt.set_chooser(Some(Box::new(move |ready: &[ThreadId]| {
+        if v.borrow().is_some() {
+            return None;
+        }
+        if let Err(message) = sc.borrow().check() {
+            *v.borrow_mut() = Some(Outcome::Failed(message));
+            return None;
+        }
+        steps += 1;
+        if steps > max_steps {
+            *v.borrow_mut() = Some(Outcome::Truncated);
+            return None;
+        }
+        let choice = choose(ready);
+        if choice.is_none() {
+            *v.borrow_mut() = Some(Outcome::Failed("el orden grabado no coincide con los hilos listos".into()));
+        }
+        choice
+    })));
+
+    rt.run();
+    rt.set_chooser(None);
+
+    if let Some(outcome) = verdict.take() {
+        // Corrida cortada: los hilos que quedaron a medias se abandonan sin join
+        scenario.borrow_mut().handles.clear();
+        return outcome;
+    }
+    let mut scenario = scenario.borrow_mut();
+    let pending: Vec<ThreadId> = scenario.handles.iter().filter(|h| !h.is_finished()).map(|h| h.id()).collect();
+    if !pending.is_empty() {
+        scenario.handles.clear();
+        return Outcome::Failed(format!("deadlock: los hilos {pending:?} no terminaron"));
+    }
+    for handle in scenario.handles.drain(..) {
+        if let Err(message) = handle.try_join() {
+            return Outcome::Failed(message);
+        }
+    }
+    match scenario.check() {
+        Ok(()) => Outcome::Passed,
+        Err(message) => Outcome::Failed(message),
+    }
+}
+
+// Repite la corrida de `trace` (la de un ExploreFailure) con el mismo `setup`. Devuelve el error
+// que vuelve a salir, o Ok si esta vez pasó.
+pub fn replay_schedule(trace: &MyEventLog, mut setup: impl FnMut(&mut Scenario)) -> Result<(), String> {
+    let mut picks = trace.events.iter().filter_map(|e| match e {
+        MyEvent::Pick(tid) => Some(*tid),
+        MyEvent::Draw(_) => None,
+    }).collect::<Vec<_>>().into_iter();
+    let rt = Runtime::replaying(trace.clone());
+    let choose = move |ready: &[ThreadId]| {
+        let tid = picks.next()?;
+        ready.iter().position(|&t| t == tid)
+    };
+    match drive(rt, &mut setup, usize::MAX, choose) {
+        Outcome::Passed | Outcome::Truncated => Ok(()),
+        Outcome::Failed(message) => Err(message),
+    }
+}
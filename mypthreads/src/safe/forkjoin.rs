@@ -0,0 +1,215 @@
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::os::raw::c_int;
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use std::rc::Rc;
+use crate::mythread::myerror::MyError;
+use crate::mythread::mythread::ThreadId;
+use crate::mythread::thread_state::ThreadState;
+use crate::safe::builder::Builder;
+use crate::safe::join_handle::JoinHandle;
+use crate::safe::runtime::Runtime;
+
+// Hilos que pueden tomar prestado lo que vive fuera del scope (como std::thread::scope). `Runtime::scope`
+// no vuelve hasta que todos terminen. Los hijos heredan scheduler, prioridad, deadline y grupo del hilo
+// que abrió el scope (desde el driver, los de por defecto).
+pub struct Scope<'scope, 'env: 'scope> {
+    rt: Runtime,
+    parent: Option<ThreadId>,
+    // Todos los hilos que se crearon en el scope. Se esperan al cerrarlo pase lo que pase con su
+    // handle (aunque se haya hecho mem::forget), porque tienen referencias a lo que el scope prestó
+    spawned: RefCell<Vec<ThreadId>>,
+    // Hilos cuyo handle se soltó sin join; se joinean al cerrar el scope
+    pending: RefCell<Vec<JoinHandle<()>>>,
+    // 'scope invariante, igual que en std
+    _scope: PhantomData<&'scope mut &'scope ()>,
+    _env: PhantomData<&'env mut &'env ()>,
+}
+
+pub struct ScopedJoinHandle<'scope, T> {
+    tid: ThreadId,
+    scope_pending: &'scope RefCell<Vec<JoinHandle<()>>>,
+    handle: Option<JoinHandle<()>>,
+    result: Rc<RefCell<Option<T>>>,
+}
+
+impl<'scope, 'env> Scope<'scope, 'env> {
+    pub fn spawn<F, T>(&'scope self, f: F) -> ScopedJoinHandle<'scope, T>
+    where
+        F: FnOnce() -> T + 'scope,
+        T: 'scope,
+    {
+        let result: Rc<RefCell<Option<T>>> = Rc::new(RefCell::new(None));
+        let slot = result.clone();
+        let body: Box<dyn FnOnce() + 'scope> = Box::new(move || {
+            *slot.borrow_mut() = Some(f());
+        });
+        // El hilo no sobrevive al scope (`Runtime::scope` lo espera antes de volver), así que lo que
+        // tomó prestado sigue vivo mientras corre
+        let body: Box<dyn FnOnce() + 'static> = unsafe { std::mem::transmute(body) };
+        let handle = self.builder().spawn(body);
+        let tid = handle.id();
+        self.spawned.borrow_mut().push(tid);
+        ScopedJoinHandle { tid, scope_pending: &self.pending, handle: Some(handle), result }
+    }
+
+    fn builder(&self) -> Builder {
+        let builder = self.rt.builder();
+        let Some(parent) = self.parent else { return builder };
+        let inner = self.rt.inner.borrow();
+        let Some(t) = inner.pth.runtime.threads.get(&parent) else { return builder };
+        let builder = builder.scheduler(t.scheduler).priority(t.attr.priority).deadline(t.attr.dead_line);
+        match t.attr.group {
+            Some(group) => builder.group(group),
+            None => builder,
+        }
+    }
+
+    // Espera a todos los hilos del scope, incluidos los que se crean mientras tanto, y joinea los que
+    // nadie joineó. Devuelve el mensaje del primero que falló.
+    fn join_pending(&self) -> Option<String> {
+        let mut failure = None;
+        let mut next = 0;
+        loop {
+            if let Some(handle) = self.pending.borrow_mut().pop() {
+                match catch_unwind(AssertUnwindSafe(|| handle.try_join())) {
+                    Ok(Ok(())) => {}
+                    Ok(Err(message)) => {
+                        failure.get_or_insert(message);
+                    }
+                    Err(_) => deadlock(),
+                }
+                continue;
+            }
+            // Solo se espera: si alguien tiene su handle todavía puede joinearlo
+            let Some(tid) = self.spawned.borrow().get(next).copied() else { break };
+            next += 1;
+            let rt = &self.rt;
+            if !rt.wait_thread(tid, || rt.state(tid).is_none_or(ThreadState::is_finished)) {
+                deadlock();
+            }
+        }
+        // Ya no queda ningún handle vivo: los que siguen en el runtime se perdieron con mem::forget
+        for &tid in self.spawned.borrow().iter() {
+            if let Some(message) = self.reap_forgotten(tid) {
+                failure.get_or_insert(message);
+            }
+        }
+        failure
+    }
+
+    fn reap_forgotten(&self, tid: ThreadId) -> Option<String> {
+        let rt = &self.rt;
+        rt.state(tid)?;
+        let exit_code = rt.exit_code(tid);
+        let message = rt.panic_message(tid);
+        rt.forget(tid);
+        if exit_code == Some(MyError::StackOverflow as c_int) {
+            return Some(format!("el hilo {tid} desbordó su pila"));
+        }
+        message
+    }
+}
+
+// El hilo sigue vivo con referencias a lo que el scope prestó y no hay forma segura de volver
+fn deadlock() -> ! {
+    eprintln!("deadlock: un hilo del scope ya no puede terminar");
+    std::process::abort();
+}
+
+impl<T> ScopedJoinHandle<'_, T> {
+    pub fn id(&self) -> ThreadId {
+        self.tid
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    // Igual que JoinHandle::join: si el hilo falló, repite el panic aquí.
+    pub fn join(self) -> T {
+        let tid = self.id();
+        self.try_join().unwrap_or_else(|msg| panic!("el hilo {tid} falló: {msg}"))
+    }
+
+    pub fn try_join(mut self) -> Result<T, String> {
+        let handle = self.handle.take().expect("handle ya joineado");
+        handle.try_join()?;
+        Ok(self
+            .result
+            .borrow_mut()
+            .take()
+            .expect("el hilo terminó sin valor de retorno"))
+    }
+}
+
+impl<T> Drop for ScopedJoinHandle<'_, T> {
+    fn drop(&mut self) {
+        // No se hace detach: el scope lo espera al cerrar
+        if let Some(handle) = self.handle.take() {
+            self.scope_pending.borrow_mut().push(handle);
+        }
+    }
+}
+
+impl Runtime {
+    // Corre `f` con un Scope y espera a todos los hilos que creó. Si `f` hizo panic se repite después
+    // de esperarlos; si no, el de algún hilo que nadie joineó.
+    pub fn scope<'env, F, T>(&self, f: F) -> T
+    where
+        F: for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> T,
+    {
+        let scope = Scope {
+            rt: self.clone(),
+            parent: self.current(),
+            spawned: RefCell::new(Vec::new()),
+            pending: RefCell::new(Vec::new()),
+            _scope: PhantomData,
+            _env: PhantomData,
+        };
+        let result = catch_unwind(AssertUnwindSafe(|| f(&scope)));
+        let failure = scope.join_pending();
+        match (result, failure) {
+            (Err(payload), _) => resume_unwind(payload),
+            (Ok(_), Some(message)) => panic!("un hilo del scope falló: {message}"),
+            (Ok(value), None) => value,
+        }
+    }
+
+    // Fork-join de dos tareas: `b` en un hilo nuevo y `a` en el actual, y devuelve ambos resultados.
+    // Se puede anidar (divide y vencerás) desde cualquier hilo verde.
+    pub fn join2<A, B, RA, RB>(&self, a: A, b: B) -> (RA, RB)
+    where
+        A: FnOnce() -> RA,
+        B: FnOnce() -> RB,
+    {
+        self.scope(|s| {
+            let hb = s.spawn(b);
+            let ra = a();
+            (ra, hb.join())
+        })
+    }
+
+    // Reparte `items` en `threads` tramos seguidos, cada uno en su hilo, y devuelve `f` de cada item
+    // en el orden original.
+    pub fn parallel_for<I, R, F>(&self, items: impl IntoIterator<Item = I>, threads: usize, f: F) -> Vec<R>
+    where
+        F: Fn(I) -> R,
+    {
+        let items: Vec<I> = items.into_iter().collect();
+        let chunk = items.len().div_ceil(threads.max(1)).max(1);
+        let f = &f;
+        self.scope(|s| {
+            let mut handles = Vec::new();
+            let mut items = items.into_iter();
+            loop {
+                let part: Vec<I> = items.by_ref().take(chunk).collect();
+                if part.is_empty() {
+                    break;
+                }
+                handles.push(s.spawn(move || part.into_iter().map(f).collect::<Vec<R>>()));
+            }
+            handles.into_iter().flat_map(ScopedJoinHandle::join).collect()
+        })
+    }
+}
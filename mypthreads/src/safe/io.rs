@@ -0,0 +1,83 @@
+use std::io;
+use std::os::unix::io::RawFd;
+use crate::mythread::myinspect::BlockedOn;
+use crate::mythread::myreactor::{set_nonblocking, would_block, Interest};
+use crate::mythread::mystack::park;
+use crate::mythread::thread_state::ThreadState;
+use crate::safe::runtime::Runtime;
+
+// E/S para hilos del Runtime: si el fd no está listo el hilo se estaciona y corren los demás.
+// Igual que my_read/my_write, el fd queda en modo O_NONBLOCK.
+impl Runtime {
+    pub fn read(&self, fd: RawFd, buf: &mut [u8]) -> io::Result<usize> {
+        self.retry_io(fd, Interest::Read, || unsafe {
+            libc::read(fd, buf.as_mut_ptr().cast(), buf.len())
+        })
+        .map(|n| n as usize)
+    }
+
+    pub fn write(&self, fd: RawFd, buf: &[u8]) -> io::Result<usize> {
+        self.retry_io(fd, Interest::Write, || unsafe {
+            libc::write(fd, buf.as_ptr().cast(), buf.len())
+        })
+        .map(|n| n as usize)
+    }
+
+    // Escribe todo el buffer, esperando cuantas veces haga falta.
+    pub fn write_all(&self, fd: RawFd, mut buf: &[u8]) -> io::Result<()> {
+        while !buf.is_empty() {
+            let n = self.write(fd, buf)?;
+            buf = &buf[n..];
+        }
+        Ok(())
+    }
+
+    // Devuelve el fd de la conexión aceptada.
+    pub fn accept(&self, fd: RawFd) -> io::Result<RawFd> {
+        self.retry_io(fd, Interest::Read, || unsafe {
+            libc::accept4(fd, std::ptr::null_mut(), std::ptr::null_mut(), libc::SOCK_CLOEXEC) as isize
+        })
+        .map(|conn| conn as RawFd)
+    }
+
+    fn retry_io(&self, fd: RawFd, interest: Interest, mut op: impl FnMut() -> isize) -> io::Result<isize> {
+        set_nonblocking(fd);
+        loop {
+            let n = op();
+            if n >= 0 {
+                return Ok(n);
+            }
+            if !would_block() {
+                return Err(io::Error::last_os_error());
+            }
+
+            if self.inner.borrow_mut().pth.runtime.reactor.register(fd, interest) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if self.park_io(fd, interest) {
+                continue;
+            }
+            // Sin pila propia espera corriendo a los demás encima, volviendo a armar el fd en cada
+            // vuelta; si ya no se puede armar, el reintento devuelve el error
+            let inner = self.inner.clone();
+            self.wait_until(BlockedOn::Io(fd), || {
+                let reactor = &mut inner.borrow_mut().pth.runtime.reactor;
+                reactor.take_ready(fd, interest) || reactor.register(fd, interest) != 0
+            });
+        }
+    }
+
+    // Estaciona al hilo actual hasta que el reactor vea `fd` listo, como park_blocked. Devuelve
+    // false (sin hacer nada) si no corre en un hilo verde con pila propia.
+    fn park_io(&self, fd: RawFd, interest: Interest) -> bool {
+        let Some(me) = self.current() else { return false };
+        self.inner.borrow_mut().pth.runtime.block_on_io(me, fd, interest);
+        if park() {
+            return true;
+        }
+        let mut inner = self.inner.borrow_mut();
+        inner.pth.runtime.unblock_io(me, fd, interest);
+        inner.pth.runtime.set_state(me, ThreadState::Running);
+        false
+    }
+}
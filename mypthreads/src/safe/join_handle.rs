@@ -0,0 +1,79 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::os::raw::c_int;
+use crate::mythread::myerror::MyError;
+use crate::mythread::mythread::ThreadId;
+use crate::mythread::thread_state::ThreadState;
+use crate::safe::runtime::Runtime;
+
+// Dueño de un hilo creado con `Runtime::spawn`. Si se suelta sin hacer join el hilo queda detached.
+pub struct JoinHandle<T> {
+    rt: Runtime,
+    tid: ThreadId,
+    packet: Rc<RefCell<Option<T>>>,
+    joined: bool,
+}
+
+impl<T> JoinHandle<T> {
+    pub(crate) fn new(rt: Runtime, tid: ThreadId, packet: Rc<RefCell<Option<T>>>) -> Self {
+        Self { rt, tid, packet, joined: false }
+    }
+
+    pub fn id(&self) -> ThreadId {
+        self.tid
+    }
+
+    pub fn is_finished(&self) -> bool {
+        finished(&self.rt, self.tid, &self.packet)
+    }
+
+    // Espera a que el hilo termine (corriendo los demás mientras tanto) y devuelve su valor.
+    // Si el hilo hizo panic o se desbordó, el panic se repite aquí con su mensaje.
+    pub fn join(self) -> T {
+        let tid = self.tid;
+        self.try_join().unwrap_or_else(|msg| panic!("el hilo {tid} falló: {msg}"))
+    }
+
+    // Como `join`, pero devuelve el mensaje del panic (o del desborde) en vez de repetirlo.
+    pub fn try_join(mut self) -> Result<T, String> {
+        let (rt, tid, packet) = (self.rt.clone(), self.tid, self.packet.clone());
+        // Si lo espera otro hilo verde, le presta sus tickets mientras tanto
+        let waiter = self.rt.current();
+        if let Some(waiter) = waiter {
+            self.rt.lend_tickets(waiter, tid);
+        }
+        let done = self.rt.wait_thread(tid, || finished(&rt, tid, &packet));
+        if let Some(waiter) = waiter {
+            self.rt.return_tickets(waiter);
+        }
+        assert!(done, "deadlock: el hilo {tid} ya no puede terminar");
+
+        self.joined = true;
+        let exit_code = self.rt.exit_code(tid);
+        let message = self.rt.panic_message(tid);
+        self.rt.forget(tid);
+        if exit_code == Some(MyError::StackOverflow as c_int) {
+            return Err(format!("el hilo {tid} desbordó su pila"));
+        }
+        if let Some(message) = message {
+            return Err(message);
+        }
+        Ok(self
+            .packet
+            .borrow_mut()
+            .take()
+            .expect("el hilo terminó sin valor de retorno"))
+    }
+}
+
+fn finished<T>(rt: &Runtime, tid: ThreadId, packet: &Rc<RefCell<Option<T>>>) -> bool {
+    packet.borrow().is_some() || rt.state(tid).is_none_or(ThreadState::is_finished)
+}
+
+impl<T> Drop for JoinHandle<T> {
+    fn drop(&mut self) {
+        if !self.joined {
+            self.rt.detach(self.tid);
+        }
+    }
+}
@@ -0,0 +1,21 @@
+mod tests;
+
+pub mod runtime;
+pub mod builder;
+pub mod join_handle;
+pub mod mutex;
+pub mod io;
+pub mod signal;
+pub mod explore;
+pub mod channel;
+pub mod executor;
+pub mod forkjoin;
+
+pub use runtime::Runtime;
+pub use builder::Builder;
+pub use join_handle::JoinHandle;
+pub use mutex::{Mutex, MutexGuard};
+pub use explore::{Explorer, Scenario};
+pub use channel::{select, MyChannel, MyReceiver, MySender, Selectable, TryRecvError, TrySendError};
+pub use executor::yield_async;
+pub use forkjoin::{Scope, ScopedJoinHandle};
@@ -0,0 +1,105 @@
+use std::cell::{RefCell, UnsafeCell};
+use std::ops::{Deref, DerefMut};
+use crate::mythread::myinspect::{BlockedOn, MutexId};
+use crate::mythread::mymutex::MyMutex;
+use crate::mythread::mythread::ThreadId;
+use crate::safe::runtime::{Runtime, DRIVER_TID};
+
+// Mutex tipado sobre MyMutex: el dato solo se alcanza con el guard y se libera al soltarlo.
+pub struct Mutex<T> {
+    rt: Runtime,
+    raw: RefCell<MyMutex>,
+    data: UnsafeCell<T>,
+}
+
+pub struct MutexGuard<'a, T> {
+    mutex: &'a Mutex<T>,
+    owner: ThreadId,
+}
+
+impl<T> Mutex<T> {
+    pub fn new(rt: &Runtime, value: T) -> Self {
+        let mut raw = MyMutex::new();
+        unsafe {
+            rt.inner.borrow_mut().pth.my_mutex_init(&mut raw, std::ptr::null());
+        }
+        Self {
+            rt: rt.clone(),
+            raw: RefCell::new(raw),
+            data: UnsafeCell::new(value),
+        }
+    }
+
+    // Toma el mutex; si está ocupado el hilo actual se estaciona Blocked hasta que el dueño lo suelte.
+    // Sin pila propia (o desde el driver) corren los demás encima hasta que se libere.
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        let me = self.rt.current().unwrap_or(DRIVER_TID);
+        loop {
+            if self.raw.borrow_mut().lock(me) == 0 {
+                return MutexGuard { mutex: self, owner: me };
+            }
+
+            let owner = self.raw.borrow().owner;
+            assert_ne!(owner, Some(me), "deadlock: el hilo {me} ya tiene este mutex");
+            let id = self.raw.borrow().id();
+            // Ya quedó en la cola del mutex: MutexGuard::drop lo despierta
+            if self.rt.park_blocked(BlockedOn::Mutex(id)) {
+                continue;
+            }
+            let released = self.rt.wait_until(BlockedOn::Mutex(id), || !self.raw.borrow().is_locked());
+            assert!(released, "deadlock: el dueño {owner:?} nunca va a soltar el mutex");
+        }
+    }
+
+    // Igual que `lock` pero sin esperar: None si ya está tomado.
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        let me = self.rt.current().unwrap_or(DRIVER_TID);
+        let rc = self.raw.borrow_mut().try_lock(me);
+        (rc == 0).then_some(MutexGuard { mutex: self, owner: me })
+    }
+
+    // Id y nombre con que aparece en Runtime::dump.
+    pub fn id(&self) -> MutexId {
+        self.raw.borrow().id()
+    }
+
+    pub fn set_name(&self, name: &str) {
+        self.raw.borrow().set_name(name);
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.raw.borrow().is_locked()
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<T> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<T> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        let waiting: Vec<ThreadId> = {
+            let mut raw = self.mutex.raw.borrow_mut();
+            raw.unlock(Some(self.owner));
+            raw.wait_queue.iter().copied().collect()
+        };
+        // Todos vuelven a intentar; el primero que corre se lo queda y los demás se vuelven a estacionar
+        for tid in waiting {
+            self.mutex.rt.wake(tid);
+        }
+    }
+}
@@ -0,0 +1,401 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::os::raw::c_int;
+use std::future::Future;
+use std::rc::Rc;
+use std::sync::Arc;
+use crate::mythread::mypthread::MyPThread;
+use crate::mythread::myaging::{MyAging, MyAgingStats};
+use crate::mythread::myclock::SharedClock;
+use crate::mythread::myinspect::{BlockedOn, MyRuntimeDump};
+use crate::mythread::myruntime::Chooser;
+use crate::mythread::myreplay::{my_replay_diverged, my_replay_log, MyEventLog};
+use crate::mythread::mystack::{park, run_routine};
+use crate::mythread::mythread::{MyThreadInfo, ThreadId, MY_DRIVER_THREAD};
+use crate::mythread::thread_state::ThreadState;
+use crate::safe::builder::Builder;
+use crate::safe::executor::WakeQueue;
+use crate::safe::join_handle::JoinHandle;
+use crate::scheduler::lottery::LotteryScheduler;
+use crate::scheduler::{GroupId, GroupScheduler, RealTimeScheduler, SchedulerType, ServerId};
+use crate::Scheduler;
+
+// Id usado como dueño cuando se toma un Mutex desde fuera de cualquier hilo (el "driver").
+pub(crate) const DRIVER_TID: ThreadId = MY_DRIVER_THREAD;
+
+pub(crate) struct Inner {
+    pub(crate) pth: MyPThread,
+    pub(crate) wakes: Arc<WakeQueue>,
+    // Hilos estacionados con `park_blocked`; solo a esos los puede despertar `wake`
+    pub(crate) parked: HashSet<ThreadId>,
+    // Hilo -> los que esperan estacionados a que termine; `step` los despierta al cerrarlo
+    joiners: HashMap<ThreadId, Vec<ThreadId>>,
+}
+
+// Fachada segura sobre MyPThread: hilos a partir de closures, join tipado y Mutex con guard.
+// Es barato de clonar; todos los clones comparten el mismo runtime.
+#[derive(Clone)]
+pub struct Runtime {
+    pub(crate) inner: Rc<RefCell<Inner>>,
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Runtime {
+    pub fn new() -> Self {
+        Self::from_pth(MyPThread::new())
+    }
+
+    // Runtime que graba su corrida desde `seed`; la bitácora sale con `event_log`.
+    pub fn with_seed(seed: u64) -> Self {
+        Self::from_pth(MyPThread::with_seed(seed))
+    }
+
+    // Runtime que repite una corrida grabada; `replay_diverged` dice si se salió del guion.
+    pub fn replaying(log: MyEventLog) -> Self {
+        Self::from_pth(MyPThread::replaying(log))
+    }
+
+    fn from_pth(pth: MyPThread) -> Self {
+        Self { inner: Rc::new(RefCell::new(Inner { pth, wakes: Arc::default(), parked: HashSet::new(), joiners: HashMap::new() })) }
+    }
+
+    pub(crate) fn wake_queue(&self) -> Arc<WakeQueue> {
+        self.inner.borrow().wakes.clone()
+    }
+
+    // Mientras haya chooser él decide qué hilo corre en cada despacho (ver safe::explore).
+    pub(crate) fn set_chooser(&self, chooser: Option<Chooser>) {
+        self.inner.borrow_mut().pth.runtime.chooser = chooser;
+    }
+
+    pub fn event_log(&self) -> MyEventLog {
+        my_replay_log()
+    }
+
+    pub fn replay_diverged(&self) -> Option<usize> {
+        my_replay_diverged()
+    }
+
+    // Builder para escoger scheduler, prioridad y deadline antes de crear el hilo.
+    pub fn builder(&self) -> Builder {
+        Builder::new(self.clone())
+    }
+
+    // Crea un hilo con los atributos por defecto (RoundRobin).
+    pub fn spawn<F, T>(&self, f: F) -> JoinHandle<T>
+    where
+        F: FnOnce() -> T + 'static,
+        T: 'static,
+    {
+        self.builder().spawn(f)
+    }
+
+    // Tarea async como hilo RoundRobin; con `builder().spawn_async` se escoge el scheduler.
+    pub fn spawn_async<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        self.builder().spawn_async(future)
+    }
+
+    // Corre `future` en su propio hilo hasta que termine, junto con los demás hilos.
+    pub fn block_on<F>(&self, future: F) -> F::Output
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        self.spawn_async(future).join()
+    }
+
+    // Hilo que está corriendo ahora mismo (None desde el driver).
+    pub fn current(&self) -> Option<ThreadId> {
+        self.inner.borrow().pth.runtime.get_current()
+    }
+
+    pub fn state(&self, tid: ThreadId) -> Option<ThreadState> {
+        self.inner.borrow().pth.runtime.get_state(tid)
+    }
+
+    // Grupo hijo de `parent` con su propio scheduler `kind` y `share` frente a sus hermanos.
+    pub fn create_group(&self, parent: GroupId, name: &str, share: u32, kind: SchedulerType) -> Option<GroupId> {
+        self.with_groups(|g| g.create_group(parent, name, share, kind))
+    }
+
+    // Shares, budgets y periodos de los grupos.
+    pub fn with_groups<R>(&self, f: impl FnOnce(&mut GroupScheduler) -> R) -> R {
+        f(self.inner.borrow_mut().pth.my_groups())
+    }
+
+    // Devuelve false si el hilo o el grupo no existen.
+    pub fn set_group(&self, tid: ThreadId, group: Option<GroupId>) -> bool {
+        self.inner.borrow_mut().pth.my_thread_setgroup(tid, group) == 0
+    }
+
+    // Scheduler propio para los hilos creados con el SchedulerType que devuelve (Builder::scheduler,
+    // change_scheduler). None si ya hay uno con ese nombre.
+    pub fn register_scheduler(&self, name: &str, scheduler: impl Scheduler + 'static) -> Option<SchedulerType> {
+        self.inner.borrow_mut().pth.my_sched_register(name, Box::new(scheduler))
+    }
+
+    pub fn scheduler_by_name(&self, name: &str) -> Option<SchedulerType> {
+        self.inner.borrow().pth.runtime.scheduler_by_name(name)
+    }
+
+    pub fn scheduler_name(&self, kind: SchedulerType) -> Option<String> {
+        self.inner.borrow().pth.runtime.scheduler_name(kind).map(str::to_string)
+    }
+
+    // Acceso al scheduler `kind` (con as_any se llega a su API propia). None si no está registrado.
+    pub fn with_scheduler<R>(&self, kind: SchedulerType, f: impl FnOnce(&mut dyn Scheduler) -> R) -> Option<R> {
+        self.inner.borrow_mut().pth.runtime.scheduler_mut(kind).map(f)
+    }
+
+    // Devuelve false si el hilo no existe o `kind` no está registrado.
+    pub fn change_scheduler(&self, tid: ThreadId, kind: SchedulerType) -> bool {
+        unsafe { self.inner.borrow_mut().pth.my_thread_chsched(tid, kind) == 0 }
+    }
+
+    // Monedas, tickets y compensación del scheduler Lottery.
+    pub fn with_lottery<R>(&self, f: impl FnOnce(&mut LotteryScheduler) -> R) -> R {
+        f(self.inner.borrow_mut().pth.my_lottery())
+    }
+
+    // Reserva `budget` despachos cada `period` para hilos aperiódicos de RealTime (Builder::server).
+    // None si no cabe junto a los servidores que ya hay.
+    pub fn create_server(&self, budget: usize, period: usize) -> Option<ServerId> {
+        self.with_real_time(|rt| rt.create_server(budget, period))
+    }
+
+    pub fn with_real_time<R>(&self, f: impl FnOnce(&mut RealTimeScheduler) -> R) -> R {
+        f(self.inner.borrow_mut().pth.my_real_time())
+    }
+
+    // Devuelve false si el hilo ya no está en la tabla.
+    pub fn set_name(&self, tid: ThreadId, name: &str) -> bool {
+        self.inner.borrow_mut().pth.runtime.set_name(tid, name) == 0
+    }
+
+    pub fn name(&self, tid: ThreadId) -> Option<String> {
+        self.inner.borrow().pth.runtime.name(tid).map(str::to_string)
+    }
+
+    // Foto de los hilos vivos (id, nombre, estado, scheduler, prioridad y deadline).
+    pub fn threads(&self) -> Vec<MyThreadInfo> {
+        self.inner.borrow().pth.my_runtime_list_threads()
+    }
+
+    // Lo mismo más qué espera cada hilo bloqueado y los mutex con su dueño y su cola.
+    pub fn dump(&self) -> MyRuntimeDump {
+        self.inner.borrow().pth.my_runtime_dump()
+    }
+
+    // `kill -<sig> <pid>` imprime el dump en stderr. False si la señal no se puede usar.
+    pub fn dump_on_signal(&self, sig: c_int) -> bool {
+        unsafe { self.inner.borrow_mut().pth.my_runtime_dump_on(sig) == 0 }
+    }
+
+    // Aging contra la inanición (ver MyAging); None lo apaga, que es como arranca el runtime.
+    pub fn set_aging(&self, aging: Option<MyAging>) {
+        self.inner.borrow_mut().pth.my_runtime_setaging(aging);
+    }
+
+    // Espera máxima en la cola de listos y promociones; por hilo están en `threads`.
+    pub fn aging_stats(&self) -> MyAgingStats {
+        self.inner.borrow().pth.my_runtime_aging_stats()
+    }
+
+    // Reloj de la corrida: VirtualClock por defecto (avanza con `advance_time`), MonotonicClock
+    // o ScaledClock para seguir al reloj real.
+    pub fn set_clock(&self, clock: SharedClock) {
+        self.inner.borrow_mut().pth.my_runtime_setclock(clock);
+    }
+
+    pub fn clock(&self) -> SharedClock {
+        self.inner.borrow().pth.my_runtime_clock()
+    }
+
+    pub fn time_ms(&self) -> usize {
+        self.inner.borrow().pth.my_runtime_time_ms()
+    }
+
+    pub fn advance_time(&self, ms: usize) {
+        self.inner.borrow_mut().pth.runtime.advance_steps(ms);
+    }
+
+    // Corre hilos hasta que ningún scheduler tenga trabajo ni quede hilo esperando E/S.
+    pub fn run(&self) {
+        while self.step() || (self.inner.borrow().pth.runtime.has_io_waiters() && self.poll_io()) {}
+    }
+
+    // Cede el CPU: el hilo actual se estaciona y el scheduler lo retoma después.
+    // Desde el driver corre un hilo listo (si hay).
+    pub fn yield_now(&self) {
+        if park() {
+            return;
+        }
+        let me = self.current();
+        if let Some(tid) = me {
+            self.inner.borrow_mut().pth.runtime.set_state(tid, ThreadState::Ready);
+        }
+        self.step();
+        self.resume(me);
+    }
+
+    // Ejecuta un solo hilo. La rutina corre sin el runtime prestado, así puede volver a usarlo.
+    pub(crate) fn step(&self) -> bool {
+        self.drain_wakes();
+        let caller = self.current();
+        let job = self.inner.borrow_mut().pth.runtime.begin_next();
+        let Some((tid, routine, arg)) = job else {
+            self.inner.borrow_mut().pth.runtime.current = caller;
+            return false;
+        };
+
+        self.deliver_signals(tid);
+        let stack = self.inner.borrow().pth.runtime.stack_of(tid);
+        let outcome = run_routine(tid, stack, routine, arg);
+
+        // Las tareas que despertó mientras corría van antes que él si cedió el CPU
+        self.drain_wakes();
+        let joiners = {
+            let mut inner = self.inner.borrow_mut();
+            inner.pth.runtime.finish_run(tid, outcome);
+            // Vuelve a correr quien despachó (None desde el driver), no el hilo que se acaba de estacionar
+            inner.pth.runtime.current = caller;
+            let finished = inner.pth.runtime.get_state(tid).is_none_or(ThreadState::is_finished);
+            if finished { inner.joiners.remove(&tid) } else { None }
+        };
+        for waiter in joiners.into_iter().flatten() {
+            self.wake(waiter);
+        }
+        true
+    }
+
+    // Bloquea al hilo actual (esperando `reason`) mientras `done` sea falso, corriendo los demás hilos.
+    // Sin hilos listos duerme en el reactor si alguien espera E/S, o hasta el wake de una tarea async.
+    // Devuelve false si ya no queda nada que correr ni esperar y la condición nunca se cumplió.
+    pub(crate) fn wait_until(&self, reason: BlockedOn, mut done: impl FnMut() -> bool) -> bool {
+        if done() {
+            return true;
+        }
+        let me = self.current();
+        if let Some(tid) = me {
+            self.inner.borrow_mut().pth.runtime.block(tid, reason);
+        }
+
+        let finished = loop {
+            if done() {
+                break true;
+            }
+            if !self.step() && !self.poll_io() && !self.wait_wakes() {
+                break done();
+            }
+        };
+        if let Some(tid) = me {
+            self.inner.borrow_mut().pth.runtime.note_wake(tid);
+        }
+
+        self.resume(me);
+        finished
+    }
+
+    // Estaciona al hilo actual como Blocked, fuera de las colas, hasta que alguien lo despierte con
+    // `wake`. A diferencia de `wait_until` no corre a nadie encima, así quien lo despierta puede estar
+    // más abajo en la pila. Devuelve false (sin hacer nada) si no corre en un hilo verde con pila propia.
+    pub(crate) fn park_blocked(&self, reason: BlockedOn) -> bool {
+        let Some(me) = self.current() else { return false };
+        {
+            let mut inner = self.inner.borrow_mut();
+            inner.pth.runtime.block(me, reason);
+            inner.parked.insert(me);
+        }
+        if park() {
+            return true;
+        }
+        let mut inner = self.inner.borrow_mut();
+        inner.parked.remove(&me);
+        inner.pth.runtime.set_state(me, ThreadState::Running);
+        false
+    }
+
+    // Espera a que `tid` termine (`done` lo confirma). En un hilo verde con pila propia se estaciona
+    // hasta que `step` lo cierre; si no, corre a los demás encima con `wait_until`. False si ya no
+    // puede terminar.
+    pub(crate) fn wait_thread(&self, tid: ThreadId, done: impl Fn() -> bool) -> bool {
+        while !done() {
+            let Some(me) = self.current() else { return self.wait_until(BlockedOn::Join(tid), &done) };
+            self.inner.borrow_mut().joiners.entry(tid).or_default().push(me);
+            if !self.park_blocked(BlockedOn::Join(tid)) {
+                if let Some(waiters) = self.inner.borrow_mut().joiners.get_mut(&tid) {
+                    waiters.retain(|&w| w != me);
+                }
+                return self.wait_until(BlockedOn::Join(tid), &done);
+            }
+        }
+        true
+    }
+
+    // Un hilo bloqueado en `wait_until` sigue más abajo en la pila y no se toca. El runtime puede
+    // estar prestado al soltar un canal durante un panic; ahí no se despierta a nadie.
+    pub(crate) fn wake(&self, tid: ThreadId) {
+        if let Ok(mut inner) = self.inner.try_borrow_mut()
+            && inner.parked.remove(&tid)
+        {
+            inner.pth.runtime.wake_parked(tid);
+        }
+    }
+
+    fn poll_io(&self) -> bool {
+        self.inner.borrow_mut().pth.runtime.poll_io(-1)
+    }
+
+    // Vuelve a dejar a `me` como hilo actual después de correr otros encima suyo.
+    // Es un nuevo despacho de `me`, así que atiende sus señales pendientes.
+    fn resume(&self, me: Option<ThreadId>) {
+        {
+            let mut inner = self.inner.borrow_mut();
+            inner.pth.runtime.current = me;
+            if let Some(tid) = me {
+                inner.pth.runtime.set_state(tid, ThreadState::Running);
+            }
+        }
+        if let Some(tid) = me {
+            self.deliver_signals(tid);
+        }
+    }
+
+    pub(crate) fn exit_code(&self, tid: ThreadId) -> Option<c_int> {
+        self.inner.borrow().pth.runtime.exit_code(tid)
+    }
+
+    pub(crate) fn panic_message(&self, tid: ThreadId) -> Option<String> {
+        self.inner.borrow().pth.runtime.panic_message(tid).map(str::to_string)
+    }
+
+    pub(crate) fn lend_tickets(&self, waiter: ThreadId, target: ThreadId) {
+        self.inner.borrow_mut().pth.runtime.lend_tickets(waiter, target);
+    }
+
+    pub(crate) fn return_tickets(&self, waiter: ThreadId) {
+        self.inner.borrow_mut().pth.runtime.return_tickets(waiter);
+    }
+
+    // Saca de la tabla un hilo ya joineado.
+    pub(crate) fn forget(&self, tid: ThreadId) {
+        self.inner.borrow_mut().pth.runtime.threads.remove(&tid);
+    }
+
+    pub(crate) fn detach(&self, tid: ThreadId) {
+        // Puede pasar durante un panic con el runtime prestado, en ese caso no tocamos nada
+        let Ok(mut inner) = self.inner.try_borrow_mut() else { return };
+        unsafe {
+            inner.pth.my_thread_detach(tid);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::io;
+use std::os::raw::c_int;
+use std::rc::Rc;
+use crate::mythread::myinspect::BlockedOn;
+use crate::mythread::mysignal::MySigSet;
+use crate::mythread::mythread::ThreadId;
+use crate::safe::runtime::Runtime;
+
+fn check(rc: c_int) -> io::Result<()> {
+    if rc == 0 { Ok(()) } else { Err(io::Error::from_raw_os_error(rc)) }
+}
+
+// Señales para hilos del Runtime. Los handlers corren dentro del hilo destino, sin el runtime
+// prestado, así que pueden volver a usarlo (spawn, kill, lock...).
+impl Runtime {
+    pub fn on_signal<F>(&self, sig: c_int, handler: F) -> io::Result<()>
+    where
+        F: Fn(c_int, ThreadId) + 'static,
+    {
+        check(self.inner.borrow_mut().pth.runtime.set_signal_handler(sig, Rc::new(handler)))
+    }
+
+    // Deja `sig` pendiente en `tid`; si `tid` es el hilo actual se atiende antes de retornar y si
+    // está en `pause` vuelve a la cola para atenderla en su próximo despacho.
+    pub fn kill(&self, tid: ThreadId, sig: c_int) -> io::Result<()> {
+        check(self.inner.borrow_mut().pth.runtime.kill(tid, sig))?;
+        if self.current() == Some(tid) {
+            self.deliver_signals(tid);
+        } else if self.inner.borrow().pth.runtime.paused_for(tid, sig) {
+            self.wake(tid);
+        }
+        Ok(())
+    }
+
+    // Como pause(): estaciona al hilo actual hasta que le llegue una señal que no tenga enmascarada.
+    // Su handler (si tiene) ya corrió al volver. False sin esperar si no corre en un hilo verde con
+    // pila propia.
+    pub fn pause(&self) -> bool {
+        let Some(me) = self.current() else { return false };
+        if self.inner.borrow().pth.runtime.has_deliverable(me) {
+            self.deliver_signals(me);
+            return true;
+        }
+        self.park_blocked(BlockedOn::Signal)
+    }
+
+    // pthread_sigmask del hilo actual; devuelve la máscara anterior.
+    pub fn sigmask(&self, how: c_int, set: MySigSet) -> io::Result<MySigSet> {
+        let mut old = 0;
+        check(self.inner.borrow_mut().pth.runtime.sigmask(how, &set, &mut old))?;
+        if let Some(tid) = self.current() {
+            self.deliver_signals(tid);
+        }
+        Ok(old)
+    }
+
+    pub fn route_signal(&self, sig: c_int, tid: ThreadId) -> io::Result<()> {
+        check(self.inner.borrow_mut().pth.runtime.route_signal(sig, tid))
+    }
+
+    pub(crate) fn deliver_signals(&self, tid: ThreadId) {
+        let signals = self.inner.borrow_mut().pth.runtime.take_signals(tid);
+        for (handler, sig) in signals {
+            handler(sig, tid);
+        }
+    }
+}
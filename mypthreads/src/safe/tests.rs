@@ -0,0 +1,1388 @@
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+    use std::any::Any;
+    use std::task::{Poll, Waker};
+    use std::collections::VecDeque;
+    use crate::safe::{select, yield_async, Explorer, JoinHandle, Mutex, MyChannel, Runtime, Scenario, TrySendError};
+    use crate::safe::explore::replay_schedule;
+    use crate::mythread::myaging::MyAging;
+    use crate::mythread::mythreadattr::PriorityLevel;
+    use crate::mythread::myspinlock::MySpinLock;
+    use crate::mythread::myonce::MyOnce;
+    use crate::mythread::myreplay::{my_random_below, my_replay_remaining, MyEvent, MyEventLog};
+    use crate::mythread::mythread::{MyThread, ThreadId};
+    use crate::Scheduler;
+    use crate::scheduler::{SchedulerType, ROOT_GROUP};
+    use crate::mythread::thread_state::ThreadState;
+
+    #[test]
+    fn spawn_and_join_returns_typed_value() {
+        let rt = Runtime::new();
+
+        let h_num = rt.spawn(|| 21 * 2);
+        let h_txt = rt.spawn(|| String::from("car-17"));
+
+        assert_eq!(h_num.join(), 42);
+        assert_eq!(h_txt.join(), "car-17");
+    }
+
+    #[test]
+    fn join_cleans_up_thread_record() {
+        let rt = Runtime::new();
+        let h = rt.spawn(|| 1u8);
+        let tid = h.id();
+
+        assert_eq!(h.join(), 1);
+        assert_eq!(rt.state(tid), None, "el hilo joineado debería salir de la tabla");
+    }
+
+    #[test]
+    fn dropped_handle_detaches_and_frees_after_run() {
+        let rt = Runtime::new();
+        let ran = Rc::new(RefCell::new(false));
+        let flag = ran.clone();
+
+        let tid = rt.spawn(move || *flag.borrow_mut() = true).id();
+        // El handle ya se soltó: el hilo queda detached pero sigue listo para correr
+        assert_eq!(rt.state(tid), Some(ThreadState::Ready));
+
+        rt.run();
+        assert!(*ran.borrow());
+        assert_eq!(rt.state(tid), None, "un hilo detached se limpia al terminar");
+    }
+
+    #[test]
+    fn builder_realtime_runs_before_round_robin() {
+        let rt = Runtime::new();
+        let order = Rc::new(RefCell::new(Vec::new()));
+
+        let o1 = order.clone();
+        let rr = rt.spawn(move || o1.borrow_mut().push("rr"));
+        let o2 = order.clone();
+        let rtt = rt
+            .builder()
+            .scheduler(SchedulerType::RealTime)
+            .deadline(10)
+            .spawn(move || o2.borrow_mut().push("rt"));
+
+        rr.join();
+        rtt.join();
+        assert_eq!(*order.borrow(), vec!["rt", "rr"]);
+    }
+
+    #[test]
+    fn threads_can_spawn_and_join_from_inside() {
+        let rt = Runtime::new();
+        let inner_rt = rt.clone();
+
+        let outer = rt.spawn(move || {
+            let child = inner_rt.spawn(|| 5);
+            child.join() * 2
+        });
+
+        assert_eq!(outer.join(), 10);
+    }
+
+    #[test]
+    fn join_lends_lottery_tickets_while_waiting() {
+        let rt = Runtime::new();
+        let inner_rt = rt.clone();
+
+        let outer = rt.spawn(move || {
+            let waiter = inner_rt.current().unwrap();
+            let child_rt = inner_rt.clone();
+            let child = inner_rt.spawn(move || {
+                let me = child_rt.current().unwrap();
+                child_rt.with_lottery(|l| l.lent_to(waiter)) == Some(me)
+            });
+            let lent_during = child.join();
+            let returned = inner_rt.with_lottery(|l| l.lent_to(waiter)).is_none();
+            lent_during && returned
+        });
+
+        assert!(outer.join());
+    }
+
+    #[test]
+    fn current_is_visible_inside_thread() {
+        let rt = Runtime::new();
+        let inner_rt = rt.clone();
+
+        let h = rt.spawn(move || inner_rt.current());
+        let tid = h.id();
+
+        assert_eq!(rt.current(), None);
+        assert_eq!(h.join(), Some(tid));
+    }
+
+    #[test]
+    fn mutex_guard_unlocks_on_drop() {
+        let rt = Runtime::new();
+        let counter = Rc::new(Mutex::new(&rt, 0));
+
+        let handles: Vec<_> = (0..5)
+            .map(|_| {
+                let c = counter.clone();
+                rt.spawn(move || {
+                    let mut g = c.lock();
+                    *g += 1;
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join();
+        }
+
+        assert!(!counter.is_locked());
+        assert_eq!(*counter.lock(), 5);
+    }
+
+    #[test]
+    fn try_lock_fails_while_guard_alive() {
+        let rt = Runtime::new();
+        let m = Mutex::new(&rt, vec![1, 2]);
+
+        let mut g = m.lock();
+        g.push(3);
+        assert!(m.try_lock().is_none());
+        drop(g);
+
+        let g2 = m.try_lock().expect("el mutex debería estar libre");
+        assert_eq!(*g2, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn lock_waiter_parks_so_thread_above_can_release() {
+        let rt = Runtime::new();
+        let m = Rc::new(Mutex::new(&rt, ()));
+        let m2 = Rc::new(Mutex::new(&rt, ()));
+
+        let (r, a, b) = (rt.clone(), m.clone(), m2.clone());
+        let y = rt.spawn(move || {
+            let g = a.lock();
+            r.yield_now();
+            drop(g);
+            let _g2 = b.lock();
+        });
+        let (a, b) = (m.clone(), m2.clone());
+        let x = rt.spawn(move || {
+            let _g2 = b.lock();
+            // Y suelta `m` y luego espera `m2`, que tiene este hilo: no es un deadlock
+            let _g = a.lock();
+        });
+
+        assert_eq!(y.try_join(), Ok(()));
+        assert_eq!(x.try_join(), Ok(()));
+    }
+
+    #[test]
+    #[should_panic(expected = "deadlock")]
+    fn relock_from_same_thread_is_reported() {
+        let rt = Runtime::new();
+        let m = Mutex::new(&rt, ());
+        let _g = m.lock();
+        let _g2 = m.lock();
+    }
+
+    fn pipe() -> (i32, i32) {
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        (fds[0], fds[1])
+    }
+
+    #[test]
+    fn read_parks_until_writer_thread_runs() {
+        let rt = Runtime::new();
+        let (rx, tx) = pipe();
+
+        let r = rt.clone();
+        let reader = rt.spawn(move || {
+            let mut buf = [0u8; 16];
+            let n = r.read(rx, &mut buf).unwrap();
+            buf[..n].to_vec()
+        });
+        let w = rt.clone();
+        let writer = rt.spawn(move || w.write_all(tx, b"hola").unwrap());
+
+        // El lector corre primero, no encuentra datos y deja correr al escritor
+        assert_eq!(reader.join(), b"hola");
+        writer.join();
+        unsafe {
+            libc::close(rx);
+            libc::close(tx);
+        }
+    }
+
+    #[test]
+    fn reader_and_writer_parked_on_same_socket_both_wake() {
+        let rt = Runtime::new();
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::socketpair(libc::AF_UNIX, libc::SOCK_STREAM, 0, fds.as_mut_ptr()) }, 0);
+        let (ours, peer) = (fds[0], fds[1]);
+
+        // Llena el buffer de `ours` para que la siguiente escritura tenga que esperar
+        unsafe { libc::fcntl(ours, libc::F_SETFL, libc::O_NONBLOCK) };
+        let chunk = [0u8; 4096];
+        let mut queued = 0;
+        loop {
+            let n = unsafe { libc::write(ours, chunk.as_ptr().cast(), chunk.len()) };
+            if n < 0 {
+                break;
+            }
+            queued += n as usize;
+        }
+
+        let r = rt.clone();
+        let reader = rt.spawn(move || {
+            let mut buf = [0u8; 8];
+            let n = r.read(ours, &mut buf).unwrap();
+            buf[..n].to_vec()
+        });
+        let w = rt.clone();
+        let writer = rt.spawn(move || w.write(ours, b"w").unwrap());
+        while rt.step() {}
+
+        // Solo se dispara la lectura: la escritura tiene que seguir armada en el mismo fd
+        unsafe { libc::write(peer, b"x".as_ptr().cast(), 1) };
+        assert_eq!(reader.join(), b"x");
+
+        let mut buf = [0u8; 4096];
+        while queued > 0 {
+            let n = unsafe { libc::read(peer, buf.as_mut_ptr().cast(), buf.len()) };
+            assert!(n > 0);
+            queued -= n as usize;
+        }
+        assert_eq!(writer.join(), 1);
+        unsafe {
+            libc::close(ours);
+            libc::close(peer);
+        }
+    }
+
+    #[test]
+    fn ready_reader_resumes_while_another_still_waits() {
+        let rt = Runtime::new();
+        let (slow_rx, slow_tx) = pipe();
+        let (fast_rx, fast_tx) = pipe();
+
+        let r = rt.clone();
+        let slow = rt.spawn(move || {
+            let mut buf = [0u8; 8];
+            r.read(slow_rx, &mut buf).unwrap()
+        });
+        let r = rt.clone();
+        let fast = rt.spawn(move || {
+            let mut buf = [0u8; 8];
+            r.read(fast_rx, &mut buf).unwrap()
+        });
+        let w = rt.clone();
+        rt.spawn(move || w.write_all(fast_tx, b"ya").unwrap());
+
+        // `slow` se estacionó primero, pero no tapa a `fast` cuando su pipe ya tiene datos
+        assert_eq!(fast.join(), 2);
+        assert!(!slow.is_finished());
+        unsafe { libc::write(slow_tx, b"tarde".as_ptr().cast(), 5) };
+        assert_eq!(slow.join(), 5);
+        for fd in [slow_rx, slow_tx, fast_rx, fast_tx] {
+            unsafe { libc::close(fd) };
+        }
+    }
+
+    #[test]
+    fn loopback_socket_accept_and_read() {
+        use std::io::Write;
+        use std::net::{TcpListener, TcpStream};
+        use std::os::unix::io::AsRawFd;
+
+        let rt = Runtime::new();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let lfd = listener.as_raw_fd();
+
+        let r = rt.clone();
+        let server = rt.spawn(move || {
+            let conn = r.accept(lfd).unwrap();
+            let mut buf = [0u8; 32];
+            let mut got = Vec::new();
+            loop {
+                let n = r.read(conn, &mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                got.extend_from_slice(&buf[..n]);
+            }
+            unsafe { libc::close(conn) };
+            String::from_utf8(got).unwrap()
+        });
+        let client = rt.spawn(move || {
+            let mut s = TcpStream::connect(addr).unwrap();
+            s.write_all(b"telemetria car-17").unwrap();
+        });
+
+        assert_eq!(server.join(), "telemetria car-17");
+        client.join();
+    }
+
+    #[test]
+    fn signal_handler_runs_inside_target_thread() {
+        let rt = Runtime::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+
+        let (r, log) = (rt.clone(), seen.clone());
+        rt.on_signal(libc::SIGUSR1, move |sig, tid| {
+            // Corre como el hilo destino y con el runtime libre
+            assert_eq!(r.current(), Some(tid));
+            log.borrow_mut().push(format!("sig {sig}"));
+        })
+        .unwrap();
+
+        let log = seen.clone();
+        let target = rt.spawn(move || log.borrow_mut().push("cuerpo".to_string()));
+        let r = rt.clone();
+        let tid = target.id();
+        // El emisor corre primero por tener mayor prioridad de scheduler
+        let sender = rt.builder().scheduler(SchedulerType::RealTime).spawn(move || r.kill(tid, libc::SIGUSR1).unwrap());
+
+        sender.join();
+        target.join();
+        assert_eq!(*seen.borrow(), vec![format!("sig {}", libc::SIGUSR1), "cuerpo".to_string()]);
+    }
+
+    #[test]
+    fn sigmask_defers_signal_until_unblocked() {
+        use crate::mythread::mysignal::{my_sigbit, MY_SIG_BLOCK, MY_SIG_UNBLOCK};
+
+        let rt = Runtime::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        rt.on_signal(libc::SIGUSR2, move |_, _| log.borrow_mut().push("handler")).unwrap();
+
+        let (r, log) = (rt.clone(), seen.clone());
+        let h = rt.spawn(move || {
+            let me = r.current().unwrap();
+            assert_eq!(r.sigmask(MY_SIG_BLOCK, my_sigbit(libc::SIGUSR2)).unwrap(), 0);
+            r.kill(me, libc::SIGUSR2).unwrap();
+            log.borrow_mut().push("enmascarada");
+            // Al desenmascarar se atiende antes de que sigmask retorne
+            r.sigmask(MY_SIG_UNBLOCK, my_sigbit(libc::SIGUSR2)).unwrap();
+            log.borrow_mut().push("fin");
+        });
+        h.join();
+
+        assert_eq!(*seen.borrow(), vec!["enmascarada", "handler", "fin"]);
+        assert_eq!(rt.kill(999, libc::SIGUSR2).unwrap_err().raw_os_error(), Some(libc::ESRCH));
+    }
+
+    #[test]
+    fn paused_thread_waits_for_each_signal() {
+        let rt = Runtime::new();
+        let handled = Rc::new(Cell::new(0));
+        let count = handled.clone();
+        rt.on_signal(libc::SIGUSR1, move |_, _| count.set(count.get() + 1)).unwrap();
+
+        let (r, on_duty) = (rt.clone(), Rc::new(Cell::new(true)));
+        let duty = on_duty.clone();
+        let h = rt.spawn(move || {
+            let mut wakeups = 0;
+            while duty.get() && r.pause() {
+                wakeups += 1;
+            }
+            wakeups
+        });
+        let tid = h.id();
+        rt.run();
+        assert_eq!(rt.state(tid), Some(ThreadState::Blocked), "sin señal sigue en pause");
+
+        for expected in 1..=3 {
+            rt.kill(tid, libc::SIGUSR1).unwrap();
+            rt.run();
+            assert_eq!(handled.get(), expected);
+            assert_eq!(rt.state(tid), Some(ThreadState::Blocked));
+        }
+        // Una señal sin handler también lo despierta
+        on_duty.set(false);
+        rt.kill(tid, libc::SIGUSR2).unwrap();
+        assert_eq!(h.join(), 4);
+        assert_eq!(handled.get(), 3);
+    }
+
+    fn recurse(depth: usize) -> usize {
+        let frame = std::hint::black_box([depth as u8; 1024]);
+        if depth == usize::MAX {
+            return 0;
+        }
+        recurse(depth + 1) + frame[0] as usize
+    }
+
+    #[test]
+    fn overflowing_thread_does_not_take_down_the_runtime() {
+        let rt = Runtime::new();
+        let doomed = rt.builder().stack_size(64 * 1024).spawn(|| recurse(0));
+        let tid = doomed.id();
+        drop(doomed);
+        let fine = rt.builder().stack_size(64 * 1024).spawn(|| "sigue vivo");
+
+        assert_eq!(fine.join(), "sigue vivo");
+        rt.run();
+        assert_eq!(rt.state(tid), None, "el hilo desbordado debió limpiarse como detached");
+    }
+
+    #[test]
+    #[should_panic(expected = "desbordó su pila")]
+    fn join_reports_stack_overflow() {
+        let rt = Runtime::new();
+        rt.builder().stack_size(64 * 1024).spawn(|| recurse(0)).join();
+    }
+
+    #[test]
+    fn panicking_thread_is_isolated() {
+        let rt = Runtime::new();
+        let counter = Rc::new(RefCell::new(0));
+
+        let c = counter.clone();
+        let bad = rt.spawn(move || {
+            *c.borrow_mut() += 1;
+            panic!("camión sin frenos");
+        });
+        let c = counter.clone();
+        let good = rt.spawn(move || {
+            *c.borrow_mut() += 1;
+            "ok"
+        });
+
+        let tid = bad.id();
+        rt.run();
+        assert_eq!(rt.state(tid), Some(ThreadState::Faulted));
+        assert_eq!(bad.try_join().unwrap_err(), "camión sin frenos");
+        assert_eq!(good.join(), "ok");
+        assert_eq!(*counter.borrow(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "falló: sin combustible")]
+    fn join_repeats_thread_panic() {
+        let rt = Runtime::new();
+        rt.spawn(|| -> u32 { panic!("sin combustible") }).join();
+    }
+
+    #[test]
+    fn named_threads_show_up_in_snapshot() {
+        let rt = Runtime::new();
+        let r = rt.clone();
+        let car = rt.builder().name("car-17").spawn(move || {
+            // Desde dentro el hilo se ve a sí mismo corriendo
+            let me = r.current().unwrap();
+            let info = r.threads().into_iter().find(|i| i.id == me).unwrap();
+            (info.name, info.state)
+        });
+        let truck = rt.builder().name("truck-plant(14,19)").priority(4).spawn(|| ());
+
+        let names: Vec<_> = rt.threads().into_iter().map(|i| i.name.unwrap()).collect();
+        assert_eq!(names, vec!["car-17", "truck-plant(14,19)"]);
+        assert_eq!(rt.name(truck.id()).as_deref(), Some("truck-plant(14,19)"));
+
+        assert_eq!(car.join(), (Some("car-17".to_string()), ThreadState::Running));
+        truck.join();
+        assert!(rt.threads().is_empty());
+    }
+
+    #[test]
+    fn groups_keep_a_car_flood_from_starving_trucks() {
+        let rt = Runtime::new();
+        let cars = rt.create_group(ROOT_GROUP, "cars", 100, SchedulerType::RoundRobin).unwrap();
+        let trucks = rt.create_group(ROOT_GROUP, "trucks", 100, SchedulerType::RoundRobin).unwrap();
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        let mut handles = Vec::new();
+        for (group, kind, count) in [(cars, "car", 20), (trucks, "truck", 1)] {
+            for _ in 0..count {
+                let (r, g) = (rt.clone(), log.clone());
+                handles.push(rt.builder().group(group).spawn(move || {
+                    for _ in 0..10 {
+                        g.borrow_mut().push(kind);
+                        r.yield_now();
+                    }
+                }));
+            }
+        }
+        rt.run();
+        handles.into_iter().for_each(JoinHandle::join);
+
+        // Sin grupos el camión correría una vez cada 21 despachos; con grupos, uno de cada dos
+        let first: Vec<_> = log.borrow()[..20].to_vec();
+        assert_eq!(first.iter().filter(|&&k| k == "truck").count(), 10, "{first:?}");
+        assert!(!rt.set_group(999, Some(cars)));
+    }
+
+    #[test]
+    fn bounded_channel_blocks_sender_when_full() {
+        let rt = Runtime::new();
+        let chan: MyChannel<u32> = MyChannel::bounded(&rt, 2);
+        let peak = Rc::new(Cell::new(0));
+
+        let tx = chan.clone();
+        let producer = rt.spawn(move || {
+            for i in 0..10 {
+                tx.send(i).unwrap();
+            }
+        });
+        let (rx, p) = (chan.clone(), peak.clone());
+        let consumer = rt.spawn(move || {
+            let mut got = Vec::new();
+            while got.len() < 10 {
+                p.set(p.get().max(rx.len()));
+                got.push(rx.recv().unwrap());
+            }
+            got
+        });
+
+        producer.join();
+        assert_eq!(consumer.join(), (0..10).collect::<Vec<_>>());
+        assert_eq!(peak.get(), 2);
+    }
+
+    #[test]
+    fn mpsc_receiver_sees_disconnect_after_last_sender() {
+        let rt = Runtime::new();
+        let (tx, rx) = MyChannel::unbounded(&rt).split();
+        let handles: Vec<_> = (0..3)
+            .map(|i| {
+                let tx = tx.clone();
+                rt.spawn(move || tx.send(i * 10).unwrap())
+            })
+            .collect();
+        drop(tx);
+
+        let consumer = rt.spawn(move || std::iter::from_fn(|| rx.recv()).collect::<Vec<_>>());
+        let mut got = consumer.join();
+        handles.into_iter().for_each(JoinHandle::join);
+        got.sort_unstable();
+        assert_eq!(got, vec![0, 10, 20]);
+    }
+
+    #[test]
+    fn mpmc_consumers_share_the_work() {
+        let rt = Runtime::new();
+        let chan = MyChannel::bounded(&rt, 1);
+        let consumers: Vec<_> = (0..2)
+            .map(|_| {
+                let (rx, r) = (chan.clone(), rt.clone());
+                rt.spawn(move || {
+                    let mut got = 0;
+                    while rx.recv().is_some_and(|v: u32| v != 0) {
+                        got += 1;
+                        r.yield_now();
+                    }
+                    got
+                })
+            })
+            .collect();
+        for v in (1..=8).chain([0, 0]) {
+            chan.send(v).unwrap();
+        }
+
+        let counts: Vec<u32> = consumers.into_iter().map(JoinHandle::join).collect();
+        assert_eq!(counts.iter().sum::<u32>(), 8);
+        assert!(counts.iter().all(|&c| c > 0), "{counts:?}");
+    }
+
+    #[test]
+    fn select_returns_the_channel_with_data() {
+        let rt = Runtime::new();
+        let orders: MyChannel<&str> = MyChannel::unbounded(&rt);
+        let alerts: MyChannel<u32> = MyChannel::unbounded(&rt);
+
+        let tx = alerts.clone();
+        let sender = rt.spawn(move || tx.send(7).unwrap());
+        assert_eq!(select(&rt, &[&orders, &alerts]), Some(1));
+        assert_eq!(alerts.try_recv(), Ok(7));
+        sender.join();
+
+        // Nadie más va a enviar: select no se queda esperando para siempre
+        assert_eq!(select(&rt, &[&orders]), None);
+        assert_eq!(orders.recv(), None);
+
+        let (tx, rx) = MyChannel::<u8>::bounded(&rt, 1).split();
+        drop(rx);
+        assert_eq!(tx.send(3), Err(3));
+        assert_eq!(tx.try_send(4), Err(TrySendError::Disconnected(4)));
+    }
+
+    // Barrera de un solo uso para los tests async: quien la espera se estaciona hasta `open`.
+    #[derive(Default)]
+    struct Gate {
+        open: Cell<bool>,
+        waiters: RefCell<Vec<Waker>>,
+    }
+
+    impl Gate {
+        fn open(&self) {
+            self.open.set(true);
+            self.waiters.borrow_mut().drain(..).for_each(Waker::wake);
+        }
+
+        async fn wait(&self) {
+            std::future::poll_fn(|cx| {
+                if self.open.get() {
+                    return Poll::Ready(());
+                }
+                self.waiters.borrow_mut().push(cx.waker().clone());
+                Poll::Pending
+            })
+            .await
+        }
+    }
+
+    #[test]
+    fn async_vehicle_awaits_bridge_in_straight_line() {
+        let rt = Runtime::new();
+        let entry = Rc::new(Gate::default());
+        let exit = Rc::new(Gate::default());
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        let (e, x, l) = (entry.clone(), exit.clone(), log.clone());
+        let car = rt.spawn_async(async move {
+            l.borrow_mut().push("drive to bridge");
+            e.wait().await;
+            l.borrow_mut().push("cross");
+            x.wait().await;
+            l.borrow_mut().push("leave");
+            3
+        });
+
+        // El puente abre desde otro hilo verde; mientras tanto el carro no ocupa el CPU
+        let (e, x, l, rt2) = (entry.clone(), exit.clone(), log.clone(), rt.clone());
+        let bridge = rt.spawn(move || {
+            l.borrow_mut().push("bridge busy");
+            rt2.yield_now();
+            e.open();
+            rt2.yield_now();
+            l.borrow_mut().push("bridge clear");
+            x.open();
+        });
+
+        assert_eq!(car.join(), 3);
+        bridge.join();
+        assert_eq!(*log.borrow(), ["drive to bridge", "bridge busy", "cross", "bridge clear", "leave"]);
+    }
+
+    #[test]
+    fn stackless_async_task_lets_threads_above_wait_for_it() {
+        let rt = Runtime::new();
+        let gate = Rc::new(Gate::default());
+        let lane = Rc::new(Mutex::new(&rt, 0));
+
+        // Una pila que no se puede reservar: la tarea corre sobre la de quien la despacha
+        let (g, l, r) = (gate.clone(), lane.clone(), rt.clone());
+        let car = rt.builder().stack_size(1 << 60).spawn_async(async move {
+            let me = r.current().unwrap();
+            assert!(r.inner.borrow().pth.runtime.stack_of(me).is_none());
+            let mut lane = l.lock();
+            g.wait().await;
+            *lane += 1;
+        });
+        let (g, l) = (gate.clone(), lane.clone());
+        let bridge = rt.spawn(move || {
+            g.open();
+            // Corre encima de la tarea, que tiene el carril: se estaciona hasta que ella lo suelte
+            *l.lock() += 10;
+        });
+
+        car.join();
+        bridge.join();
+        assert_eq!(*lane.lock(), 11);
+    }
+
+    #[test]
+    fn async_tasks_follow_their_scheduler_and_yield() {
+        let rt = Runtime::new();
+        let order = Rc::new(RefCell::new(Vec::new()));
+        let mut handles = Vec::new();
+        for (name, deadline) in [("late", 30), ("urgent", 10), ("soon", 20)] {
+            let order = order.clone();
+            handles.push(rt.builder().scheduler(SchedulerType::RealTime).deadline(deadline).spawn_async(async move {
+                order.borrow_mut().push(name);
+            }));
+        }
+        let (a, b) = (order.clone(), order.clone());
+        let ping = rt.spawn_async(async move {
+            for _ in 0..2 {
+                a.borrow_mut().push("ping");
+                yield_async().await;
+            }
+        });
+        let pong = rt.spawn_async(async move {
+            for _ in 0..2 {
+                b.borrow_mut().push("pong");
+                yield_async().await;
+            }
+        });
+
+        rt.run();
+        assert_eq!(*order.borrow(), ["urgent", "soon", "late", "ping", "pong", "ping", "pong"]);
+        handles.into_iter().for_each(JoinHandle::join);
+        ping.join();
+        pong.join();
+    }
+
+    #[test]
+    fn waker_from_another_os_thread_resumes_task() {
+        let rt = Runtime::new();
+        let value = rt.block_on(async {
+            let mut started = false;
+            let (tx, rx) = std::sync::mpsc::channel();
+            std::future::poll_fn(move |cx| {
+                if let Ok(v) = rx.try_recv() {
+                    return Poll::Ready(v);
+                }
+                if !started {
+                    started = true;
+                    let (waker, tx) = (cx.waker().clone(), tx.clone());
+                    std::thread::spawn(move || {
+                        std::thread::sleep(std::time::Duration::from_millis(20));
+                        tx.send(99).unwrap();
+                        waker.wake();
+                    });
+                }
+                Poll::Pending
+            })
+            .await
+        });
+        assert_eq!(value, 99);
+    }
+
+    #[test]
+    fn wake_from_os_thread_right_before_park_queues_task_once() {
+        use crate::mythread::myinspect::BlockedOn;
+
+        let rt = Runtime::new();
+        let resumed = Rc::new(Cell::new(0));
+        let (r, res) = (rt.clone(), resumed.clone());
+        let task = rt.spawn(move || {
+            let me = r.current().unwrap();
+            // Como en `drive`: el wake llega después de revisar `woken` y antes de estacionarse
+            let queue = r.wake_queue();
+            std::thread::spawn(move || queue.push(me)).join().unwrap();
+            assert!(r.park_blocked(BlockedOn::Waker));
+            res.set(1);
+            // Sin un wake nuevo no debe volver a correr
+            assert!(r.park_blocked(BlockedOn::Waker));
+            res.set(2);
+        });
+        let tid = task.id();
+
+        rt.run();
+        assert_eq!(resumed.get(), 1, "el hilo quedó dos veces en la cola");
+        assert_eq!(rt.state(tid), Some(ThreadState::Blocked));
+
+        rt.wake(tid);
+        task.join();
+        assert_eq!(resumed.get(), 2);
+    }
+
+    // FIFO que anota los avisos de bloqueo y despertar que le manda el runtime.
+    struct TracingScheduler {
+        queue: VecDeque<ThreadId>,
+        events: Rc<RefCell<Vec<(&'static str, ThreadId)>>>,
+    }
+
+    impl Scheduler for TracingScheduler {
+        fn enqueue(&mut self, tid: ThreadId, _t: &MyThread) {
+            self.queue.push_back(tid);
+        }
+        fn pick_next(&mut self) -> Option<ThreadId> {
+            self.queue.pop_front()
+        }
+        fn remove(&mut self, tid: ThreadId) -> bool {
+            let before = self.queue.len();
+            self.queue.retain(|&t| t != tid);
+            before != self.queue.len()
+        }
+        fn on_block(&mut self, tid: ThreadId) {
+            self.events.borrow_mut().push(("block", tid));
+        }
+        fn on_wake(&mut self, tid: ThreadId) {
+            self.events.borrow_mut().push(("wake", tid));
+        }
+        fn as_any(&mut self) -> &mut dyn Any { self }
+        fn len(&self) -> usize { self.queue.len() }
+    }
+
+    #[test]
+    fn registered_scheduler_sees_block_and_wake() {
+        let rt = Runtime::new();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let tracing = rt
+            .register_scheduler("tracing", TracingScheduler { queue: VecDeque::new(), events: events.clone() })
+            .unwrap();
+        assert_eq!(rt.scheduler_by_name("tracing"), Some(tracing));
+        assert_eq!(rt.scheduler_name(SchedulerType::RealTime).as_deref(), Some("real-time"));
+
+        let (tx, rx) = MyChannel::<u32>::bounded(&rt, 1).split();
+        let consumer = rt.builder().scheduler(tracing).spawn(move || rx.recv());
+        rt.run();
+        let producer = rt.spawn(move || tx.send(5).unwrap());
+
+        assert_eq!(consumer.join(), Some(5));
+        producer.join();
+        let c = events.borrow()[0].1;
+        assert_eq!(*events.borrow(), [("block", c), ("wake", c)]);
+        assert_eq!(rt.with_scheduler(tracing, |s| s.len()), Some(0));
+    }
+
+    #[test]
+    fn aperiodic_thread_runs_within_its_server_budget() {
+        let rt = Runtime::new();
+        let server = rt.create_server(1, 3).unwrap();
+        assert_eq!(rt.create_server(3, 3), None, "ya no cabe el 100%");
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let worker = |name: &'static str| {
+            let (r, l) = (rt.clone(), log.clone());
+            move || {
+                for _ in 0..3 {
+                    l.borrow_mut().push(name);
+                    r.yield_now();
+                }
+            }
+        };
+        let periodic = rt.builder().scheduler(SchedulerType::RealTime).deadline(1_000).spawn(worker("p"));
+        let aperiodic = rt.builder().server(server).spawn(worker("a"));
+        rt.run();
+        periodic.join();
+        aperiodic.join();
+        // Un despacho por periodo de tres ticks ([0, 3), [3, 6), [6, 9)) aunque el deadline de p sea lejano
+        assert_eq!(*log.borrow(), ["a", "p", "a", "p", "p", "a"]);
+        assert_eq!(rt.with_real_time(|edf| edf.server(server).map(|s| s.pending())), Some(0));
+    }
+
+    #[test]
+    fn yield_interleaves_threads_and_spin_lock_waits_cooperatively() {
+        let rt = Runtime::new();
+        let lock = Rc::new(MySpinLock::new());
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        let (r, l, g) = (rt.clone(), lock.clone(), log.clone());
+        let a = rt.spawn(move || {
+            l.lock();
+            g.borrow_mut().push("a-lock");
+            r.yield_now();
+            g.borrow_mut().push("a-unlock");
+            l.unlock();
+        });
+        let (l, g) = (lock.clone(), log.clone());
+        let b = rt.spawn(move || {
+            g.borrow_mut().push("b-wait");
+            l.lock();
+            g.borrow_mut().push("b-lock");
+            l.unlock();
+        });
+
+        rt.run();
+        a.join();
+        b.join();
+        assert_eq!(*log.borrow(), vec!["a-lock", "b-wait", "a-unlock", "b-lock"]);
+        assert!(!lock.is_locked());
+    }
+
+    #[test]
+    fn driver_spin_lock_against_parked_owner_is_deadlock() {
+        let rt = Runtime::new();
+        let lock = Rc::new(MySpinLock::new());
+
+        let (r, l) = (rt.clone(), lock.clone());
+        let owner = rt.spawn(move || {
+            assert_eq!(l.lock(), 0);
+            r.yield_now();
+            l.unlock();
+        });
+        rt.step();
+        assert!(lock.is_locked());
+
+        // El dueño está estacionado y el driver no lo despacha: girar no terminaría nunca
+        assert_eq!(lock.lock(), libc::EDEADLK);
+
+        owner.join();
+        assert_eq!(lock.lock(), 0);
+        assert_eq!(lock.unlock(), 0);
+    }
+
+    #[test]
+    fn once_retries_init_after_it_panics() {
+        let rt = Runtime::new();
+        let once = Rc::new(MyOnce::new());
+        let calls = Rc::new(Cell::new(0));
+
+        let (o, c) = (once.clone(), calls.clone());
+        let first = rt.spawn(move || {
+            o.call_once(|| {
+                c.set(c.get() + 1);
+                panic!("init roto");
+            })
+        });
+        assert!(first.try_join().is_err());
+        assert!(!once.is_completed(), "un init que hace panic no cuenta como hecho");
+
+        let (o, c) = (once.clone(), calls.clone());
+        rt.spawn(move || o.call_once(|| c.set(c.get() + 1))).join();
+        assert!(once.is_completed());
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn driver_once_against_parked_runner_is_deadlock() {
+        let rt = Runtime::new();
+        let once = Rc::new(MyOnce::new());
+
+        let (r, o) = (rt.clone(), once.clone());
+        let runner = rt.spawn(move || o.call_once(|| r.yield_now()));
+        rt.step();
+        assert!(!once.is_completed());
+
+        assert_eq!(once.try_call_once(|| unreachable!()), libc::EDEADLK);
+
+        runner.join();
+        assert_eq!(once.try_call_once(|| unreachable!()), 0);
+        assert!(once.is_completed());
+    }
+
+    // Hilos de lotería que sacan números y ceden: el orden depende de la semilla
+    fn lottery_trace(rt: &Runtime, threads: u8) -> Vec<(ThreadId, u64)> {
+        let trace = Rc::new(RefCell::new(Vec::new()));
+        for tickets in 1..=threads {
+            let (r, t) = (rt.clone(), trace.clone());
+            rt.builder().scheduler(SchedulerType::Lottery).priority(tickets * 10).spawn(move || {
+                for _ in 0..3 {
+                    let me = r.current().unwrap();
+                    t.borrow_mut().push((me, my_random_below(100)));
+                    r.yield_now();
+                }
+            });
+        }
+        rt.run();
+        trace.take()
+    }
+
+    #[test]
+    fn replay_repeats_recorded_run() {
+        let recorded = lottery_trace(&Runtime::with_seed(7), 5);
+        let log = Runtime::with_seed(7).event_log();
+        assert!(log.events.is_empty(), "un runtime nuevo con semilla empieza a grabar de cero");
+
+        let rt = Runtime::with_seed(7);
+        assert_eq!(lottery_trace(&rt, 5), recorded, "misma semilla, misma corrida");
+        let log = rt.event_log();
+        assert_eq!(log.seed, 7);
+        assert!(log.events.iter().any(|e| matches!(e, MyEvent::Pick(_))));
+
+        let log = MyEventLog::from_text(&log.to_text()).unwrap();
+        let rt = Runtime::replaying(log);
+        assert_eq!(lottery_trace(&rt, 5), recorded);
+        assert_eq!(rt.replay_diverged(), None);
+        assert_eq!(my_replay_remaining(), 0);
+    }
+
+    #[test]
+    fn replay_reports_first_divergence() {
+        let rt = Runtime::with_seed(11);
+        lottery_trace(&rt, 4);
+        let log = rt.event_log();
+
+        // Con un hilo menos la corrida se sale del guion en algún punto
+        let rt = Runtime::replaying(log);
+        lottery_trace(&rt, 3);
+        assert!(rt.replay_diverged().is_some());
+    }
+
+    // Hilos RoundRobin que anotan su id y ceden
+    fn round_robin_trace(rt: &Runtime, threads: usize) -> Vec<ThreadId> {
+        let trace = Rc::new(RefCell::new(Vec::new()));
+        for _ in 0..threads {
+            let (r, t) = (rt.clone(), trace.clone());
+            rt.spawn(move || {
+                for _ in 0..3 {
+                    t.borrow_mut().push(r.current().unwrap());
+                    r.yield_now();
+                }
+            });
+        }
+        rt.run();
+        trace.take()
+    }
+
+    #[test]
+    fn replay_forces_recorded_picks() {
+        let rt = Runtime::with_seed(3);
+        let recorded = round_robin_trace(&rt, 3);
+        let mut log = rt.event_log();
+        assert!(log.events.iter().all(|e| matches!(e, MyEvent::Pick(_))));
+
+        // Un orden que RoundRobin nunca elegiría: cada hilo de corrido, el último primero
+        log.events.sort_by_key(|e| match e {
+            MyEvent::Pick(tid) => std::cmp::Reverse(*tid),
+            MyEvent::Draw(_) => unreachable!(),
+        });
+        let mut expected = recorded.clone();
+        expected.sort_by_key(|&tid| std::cmp::Reverse(tid));
+        assert_ne!(expected, recorded);
+
+        let rt = Runtime::replaying(log);
+        assert_eq!(round_robin_trace(&rt, 3), expected);
+        assert_eq!(rt.replay_diverged(), None);
+    }
+
+    // Dos hilos suman 1 a un contador leyendo y escribiendo por separado. Con `guarded` la suma va
+    // dentro de un Mutex; si no, hay órdenes en que se pierde una suma.
+    fn counter_scenario(sc: &mut Scenario, guarded: bool) {
+        let rt = sc.runtime().clone();
+        let lock = Rc::new(Mutex::new(&rt, ()));
+        let counter = Rc::new(RefCell::new(0usize));
+        let done = Rc::new(RefCell::new(0usize));
+        for _ in 0..2 {
+            let (r, l, c, d) = (rt.clone(), lock.clone(), counter.clone(), done.clone());
+            sc.spawn(move || {
+                let guard = guarded.then(|| l.lock());
+                let seen = *c.borrow();
+                r.yield_now();
+                *c.borrow_mut() = seen + 1;
+                drop(guard);
+                *d.borrow_mut() += 1;
+            });
+        }
+        sc.invariant(move || {
+            let total = *counter.borrow();
+            if *done.borrow() == 2 && total != 2 {
+                return Err(format!("se perdió una suma: contador = {total}"));
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn dfs_finds_lost_update_and_trace_replays_it() {
+        let failure = Explorer::dfs(100)
+            .run(|sc| counter_scenario(sc, false))
+            .expect_err("sin mutex hay un orden que pierde la suma");
+        assert_eq!(failure.message, "se perdió una suma: contador = 1");
+        assert!(failure.trace.events.iter().any(|e| matches!(e, MyEvent::Pick(_))));
+
+        let again = replay_schedule(&failure.trace, |sc| counter_scenario(sc, false));
+        assert_eq!(again, Err(failure.message));
+    }
+
+    #[test]
+    fn dfs_exhausts_all_orders_when_mutex_protects_counter() {
+        let report = Explorer::dfs(1000).run(|sc| counter_scenario(sc, true)).unwrap();
+        assert!(report.exhausted);
+        assert!(report.schedules > 1, "hay más de un orden posible: {report:?}");
+        assert_eq!(report.truncated, 0);
+    }
+
+    #[test]
+    fn pct_finds_lost_update() {
+        let failure = Explorer::pct(50, 2).seed(3).run(|sc| counter_scenario(sc, false)).unwrap_err();
+        assert!(failure.message.starts_with("se perdió una suma"));
+    }
+
+    #[test]
+    fn dfs_reports_lock_order_deadlock() {
+        let failure = Explorer::dfs(100)
+            .run(|sc| {
+                let rt = sc.runtime().clone();
+                let a = Rc::new(Mutex::new(&rt, ()));
+                let b = Rc::new(Mutex::new(&rt, ()));
+                for flip in [false, true] {
+                    let (r, a, b) = (rt.clone(), a.clone(), b.clone());
+                    sc.spawn(move || {
+                        let (first, second) = if flip { (&b, &a) } else { (&a, &b) };
+                        let _g1 = first.lock();
+                        r.yield_now();
+                        let _g2 = second.lock();
+                    });
+                }
+            })
+            .unwrap_err();
+        assert!(failure.message.contains("deadlock"), "{}", failure.message);
+    }
+
+    #[test]
+    fn dump_shows_what_each_thread_is_blocked_on() {
+        use crate::mythread::myinspect::BlockedOn;
+        let rt = Runtime::new();
+        let m = Rc::new(Mutex::new(&rt, ()));
+        m.set_name("compartido");
+        let seen = Rc::new(RefCell::new(None));
+
+        let mm = m.clone();
+        let r = rt.clone();
+        let holder = rt.builder().name("holder").spawn(move || {
+            let _g = mm.lock();
+            r.yield_now();
+        });
+        let holder_tid = holder.id();
+        let mm = m.clone();
+        rt.builder().name("waiter").spawn(move || drop(mm.lock()));
+        rt.builder().name("joiner").spawn(move || holder.join());
+        let (r, s) = (rt.clone(), seen.clone());
+        rt.builder().name("observer").spawn(move || *s.borrow_mut() = Some(r.dump()));
+        rt.run();
+
+        let dump = seen.borrow_mut().take().expect("observer no corrió");
+        let by_name = |name: &str| dump.threads.iter().find(|t| t.name.as_deref() == Some(name)).unwrap().clone();
+        assert_eq!(by_name("waiter").blocked_on, Some(BlockedOn::Mutex(m.id())));
+        assert_eq!(by_name("joiner").blocked_on, Some(BlockedOn::Join(holder_tid)));
+        assert_eq!(by_name("holder").blocked_on, None);
+        assert_eq!(by_name("holder").runs, 1);
+        assert_eq!(dump.current, Some(by_name("observer").id));
+
+        let mutex = dump.mutexes.iter().find(|i| i.id == m.id()).unwrap();
+        assert_eq!(mutex.name.as_deref(), Some("compartido"));
+        assert_eq!(mutex.owner, Some(holder_tid));
+        assert_eq!(mutex.waiters, vec![by_name("waiter").id]);
+
+        let text = dump.to_string();
+        assert!(text.contains(&format!("join {holder_tid} (holder)")), "{text}");
+        assert!(text.contains("compartido"), "{text}");
+        assert!(rt.dump().threads.is_empty());
+    }
+
+    fn fib(rt: &Runtime, n: u64) -> u64 {
+        if n < 2 {
+            return n;
+        }
+        let (a, b) = rt.join2(|| fib(rt, n - 1), || fib(rt, n - 2));
+        a + b
+    }
+
+    #[test]
+    fn nested_join2_computes_fib_from_driver_and_thread() {
+        let rt = Runtime::new();
+        assert_eq!(fib(&rt, 10), 55);
+
+        let r = rt.clone();
+        assert_eq!(rt.spawn(move || fib(&r, 12)).join(), 144);
+        assert!(rt.threads().is_empty(), "todos los hilos del fork-join se joinean");
+    }
+
+    #[test]
+    fn parallel_for_keeps_order_and_splits_in_threads() {
+        let rt = Runtime::new();
+        let tids = RefCell::new(Vec::new());
+
+        let squares = rt.parallel_for(0..10u32, 3, |i| {
+            tids.borrow_mut().push(rt.current().unwrap());
+            i * i
+        });
+
+        assert_eq!(squares, (0..10).map(|i| i * i).collect::<Vec<_>>());
+        let mut tids = tids.into_inner();
+        tids.dedup();
+        assert_eq!(tids.len(), 3, "10 items en 3 tramos de 4, 4 y 2");
+        assert!(rt.parallel_for(Vec::<u32>::new(), 4, |i| i).is_empty());
+    }
+
+    #[test]
+    fn scope_threads_borrow_and_mutate_local_data() {
+        let rt = Runtime::new();
+        let mut lanes = vec![1, 2, 3, 4, 5, 6];
+        let total = Cell::new(0);
+
+        rt.scope(|s| {
+            for lane in lanes.chunks_mut(2) {
+                let r = &rt;
+                let total = &total;
+                // Sin join: el scope los espera igual
+                s.spawn(move || {
+                    for car in lane.iter_mut() {
+                        *car *= 10;
+                        r.yield_now();
+                    }
+                    total.set(total.get() + 1);
+                });
+            }
+        });
+
+        assert_eq!(lanes, vec![10, 20, 30, 40, 50, 60]);
+        assert_eq!(total.get(), 3);
+    }
+
+    #[test]
+    fn scope_waits_for_every_thread_before_repeating_a_panic() {
+        let rt = Runtime::new();
+        let finished = Cell::new(false);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            rt.scope(|s| {
+                s.spawn(|| panic!("ruta bloqueada"));
+                s.spawn(|| {
+                    rt.yield_now();
+                    finished.set(true);
+                });
+            })
+        }));
+
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("ruta bloqueada"), "{message}");
+        assert!(finished.get(), "el otro hilo terminó antes de salir del scope");
+        assert!(rt.scope(|s| s.spawn(|| 7).try_join()).is_ok());
+    }
+
+    #[test]
+    fn scope_waits_for_threads_whose_handle_was_forgotten() {
+        let rt = Runtime::new();
+        let mut lanes = vec![1, 2];
+        let ids = rt.scope(|s| {
+            let r = &rt;
+            let mut ids = Vec::new();
+            for lane in lanes.iter_mut() {
+                let handle = s.spawn(move || {
+                    r.yield_now();
+                    *lane *= 10;
+                });
+                ids.push(handle.id());
+                std::mem::forget(handle);
+            }
+            ids
+        });
+
+        assert_ne!(ids[0], ids[1]);
+        assert_eq!(lanes, vec![10, 20]);
+        assert!(ids.iter().all(|&tid| rt.state(tid).is_none()), "el scope limpió los hilos olvidados");
+
+        // El panic de un hilo olvidado también sale del scope
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            rt.scope(|s| std::mem::forget(s.spawn(|| panic!("sin salida"))))
+        }));
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("sin salida"), "{message}");
+    }
+
+    #[test]
+    fn scope_children_inherit_scheduler_of_parent() {
+        let rt = Runtime::new();
+        let r = rt.clone();
+        let parent = rt.builder().scheduler(SchedulerType::Lottery).priority(5).spawn(move || {
+            r.scope(|s| {
+                let r = &r;
+                s.spawn(move || {
+                    let me = r.current().unwrap();
+                    r.threads().into_iter().find(|t| t.id == me).unwrap()
+                })
+                .join()
+            })
+        });
+
+        let child = parent.join();
+        assert_eq!(child.scheduler, SchedulerType::Lottery);
+        assert_eq!(child.priority, 5);
+        assert_eq!(rt.scope(|s| s.spawn(|| 0).join()), 0);
+    }
+
+    // Hilos que ceden sin parar hasta que `starved` corra (o se cansen) y devuelven cuántas veces cedieron
+    fn flood(rt: &Runtime, kind: SchedulerType, priority: PriorityLevel, starved_ran: &Rc<Cell<bool>>) -> Vec<JoinHandle<usize>> {
+        (0..3)
+            .map(|i| {
+                let (r, ran) = (rt.clone(), starved_ran.clone());
+                rt.builder().scheduler(kind).priority(priority).deadline(i).spawn(move || {
+                    let mut yields = 0;
+                    while !ran.get() && yields < 500 {
+                        r.yield_now();
+                        yields += 1;
+                    }
+                    yields
+                })
+            })
+            .collect()
+    }
+
+    fn spawn_starved(rt: &Runtime, kind: SchedulerType, priority: PriorityLevel, ran: &Rc<Cell<bool>>) -> JoinHandle<usize> {
+        let (r, ran) = (rt.clone(), ran.clone());
+        rt.builder().scheduler(kind).priority(priority).spawn(move || {
+            ran.set(true);
+            let me = r.current().unwrap();
+            r.threads().into_iter().find(|t| t.id == me).unwrap().promotions
+        })
+    }
+
+    #[test]
+    fn round_robin_waits_for_whole_realtime_flood_without_aging() {
+        let rt = Runtime::new();
+        let ran = Rc::new(Cell::new(false));
+        let flood = flood(&rt, SchedulerType::RealTime, 1, &ran);
+        let rr = spawn_starved(&rt, SchedulerType::RoundRobin, 1, &ran);
+
+        assert_eq!(rr.join(), 0);
+        assert!(flood.into_iter().all(|h| h.join() == 500), "RealTime nunca le dejó el CPU");
+        let stats = rt.aging_stats();
+        assert!(stats.max_wait >= 1500, "{stats:?}");
+        assert_eq!(stats.promotions, 0);
+    }
+
+    #[test]
+    fn aging_bounds_round_robin_wait_behind_realtime() {
+        let rt = Runtime::new();
+        rt.set_aging(Some(MyAging::new(8)));
+        let ran = Rc::new(Cell::new(false));
+        let flood = flood(&rt, SchedulerType::RealTime, 1, &ran);
+        let rr = spawn_starved(&rt, SchedulerType::RoundRobin, 1, &ran);
+
+        assert!(rr.join() >= 1, "el RoundRobin corrió por promoción");
+        for h in flood {
+            assert!(h.join() < 50, "el flujo de RealTime se cortó pronto");
+        }
+        // Seis escalones para subir de RoundRobin a RealTime (los deadlines del flujo ya vencieron), más
+        // los demás hambrientos que se promovieron antes
+        let stats = rt.aging_stats();
+        assert!(stats.max_wait <= 8 * 6 + 3, "{stats:?}");
+        assert!(stats.promotions > 0);
+    }
+
+    #[test]
+    fn aging_keeps_realtime_deadlines_in_flight() {
+        let rt = Runtime::new();
+        rt.set_aging(Some(MyAging::new(2)));
+        let ran = Rc::new(Cell::new(false));
+        let background = flood(&rt, SchedulerType::RoundRobin, 1, &ran);
+
+        // Seis despachos cada uno y deadlines justos para EDF: 6, 12 y 18 más un tick de holgura
+        let tasks: Vec<_> = [7, 13, 19]
+            .into_iter()
+            .map(|deadline| {
+                let r = rt.clone();
+                rt.builder().scheduler(SchedulerType::RealTime).deadline(deadline).spawn(move || {
+                    for _ in 0..5 {
+                        r.yield_now();
+                    }
+                    (r.with_real_time(|edf| edf.now()), deadline)
+                })
+            })
+            .collect();
+        let background_ran = spawn_starved(&rt, SchedulerType::RoundRobin, 1, &ran);
+
+        for (finished, deadline) in tasks.into_iter().map(JoinHandle::join) {
+            assert!(finished <= deadline, "terminó en {finished}, deadline {deadline}");
+        }
+        background_ran.join();
+        for h in background {
+            h.join();
+        }
+        assert!(rt.aging_stats().promotions > 0, "el aging siguió activo para los RoundRobin");
+    }
+
+    #[test]
+    fn aging_bounds_low_ticket_wait_in_lottery() {
+        let rt = Runtime::new();
+        rt.set_aging(Some(MyAging::new(16)));
+        let ran = Rc::new(Cell::new(false));
+        let flood = flood(&rt, SchedulerType::Lottery, 200, &ran);
+        let poor = spawn_starved(&rt, SchedulerType::Lottery, 1, &ran);
+
+        poor.join();
+        for h in flood {
+            assert!(h.join() < 40);
+        }
+        assert!(rt.aging_stats().max_wait <= 16 + 3, "{:?}", rt.aging_stats());
+    }
+}
@@ -0,0 +1,65 @@
+mod tests;
+pub mod rma;
+
+use std::any::Any;
+use std::collections::VecDeque;
+use crate::mythread::mythread::{MyThread, ThreadId};
+use crate::mythread::mythreadattr::PriorityLevel;
+use crate::Scheduler;
+
+pub use rma::{rate_monotonic_priorities, response_time_analysis, PeriodicTask, RtaReport, TaskResponse};
+
+const LEVELS: usize = PriorityLevel::MAX as usize + 1;
+
+// Prioridad fija con 256 niveles (attr.priority, 255 es la más alta) y FIFO dentro de cada nivel,
+// como SCHED_FIFO. El runtime es cooperativo: un hilo más prioritario que se vuelve listo le gana
+// el CPU al actual en su siguiente yield, no antes.
+pub struct FixedPriorityScheduler {
+    levels: Vec<VecDeque<ThreadId>>,
+    len: usize,
+}
+
+impl FixedPriorityScheduler {
+    pub fn new() -> Self {
+        Self { levels: vec![VecDeque::new(); LEVELS], len: 0 }
+    }
+
+    // Nivel más alto con hilos listos.
+    pub fn highest_ready(&self) -> Option<PriorityLevel> {
+        (0..LEVELS).rev().find(|&p| !self.levels[p].is_empty()).map(|p| p as PriorityLevel)
+    }
+}
+
+impl Default for FixedPriorityScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scheduler for FixedPriorityScheduler {
+    fn enqueue(&mut self, tid: ThreadId, t: &MyThread) {
+        self.levels[t.attr.priority as usize].push_back(tid);
+        self.len += 1;
+    }
+
+    fn pick_next(&mut self) -> Option<ThreadId> {
+        let level = self.highest_ready()?;
+        self.len -= 1;
+        self.levels[level as usize].pop_front()
+    }
+
+    fn remove(&mut self, tid: ThreadId) -> bool {
+        for level in &mut self.levels {
+            if let Some(i) = level.iter().position(|&t| t == tid) {
+                level.remove(i);
+                self.len -= 1;
+                return true;
+            }
+        }
+        false
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any { self }
+
+    fn len(&self) -> usize { self.len }
+}
@@ -0,0 +1,113 @@
+use crate::mythread::mythreadattr::PriorityLevel;
+
+// Análisis de tareas periódicas para FixedPriorityScheduler: prioridades rate monotonic y
+// análisis de tiempo de respuesta (RTA). Todo en ticks, igual que el bench.
+
+// Cada `period` ticks libera un trabajo de `wcet` ticks que debe terminar a lo más `deadline`
+// ticks después de liberado.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeriodicTask {
+    pub period: usize,
+    pub wcet: usize,
+    pub deadline: usize,
+}
+
+impl PeriodicTask {
+    // Deadline implícito: el final del periodo.
+    pub fn new(period: usize, wcet: usize) -> Self {
+        Self { period, wcet, deadline: period }
+    }
+
+    pub fn with_deadline(mut self, deadline: usize) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    pub fn utilization(&self) -> f64 {
+        self.wcet as f64 / self.period.max(1) as f64
+    }
+}
+
+// Rate monotonic: a menor periodo mayor prioridad, desde 255 hacia abajo. Tareas con el mismo
+// periodo comparten nivel (FIFO entre ellas). None si hay más periodos distintos que niveles.
+pub fn rate_monotonic_priorities(tasks: &[PeriodicTask]) -> Option<Vec<PriorityLevel>> {
+    let mut periods: Vec<usize> = tasks.iter().map(|t| t.period).collect();
+    periods.sort_unstable();
+    periods.dedup();
+    if periods.len() > PriorityLevel::MAX as usize + 1 {
+        return None;
+    }
+    Some(
+        tasks
+            .iter()
+            .map(|t| {
+                let rank = periods.binary_search(&t.period).unwrap_or(0);
+                PriorityLevel::MAX - rank as PriorityLevel
+            })
+            .collect(),
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskResponse {
+    // Peor tiempo de respuesta; None si pasa de su deadline
+    pub response: Option<usize>,
+    pub schedulable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RtaReport {
+    pub tasks: Vec<TaskResponse>,
+    pub utilization: f64,
+    // Cota de Liu y Layland n(2^(1/n) - 1): por debajo RM siempre cumple (con deadline = periodo)
+    pub liu_layland_bound: f64,
+    pub schedulable: bool,
+}
+
+// Tiempo de respuesta de cada tarea con prioridad fija:
+//   R = C_i + sum_{j en hp(i)} ceil(R / T_j) * C_j
+// iterado hasta que no cambie o pase del deadline. hp(i) son las demás tareas con prioridad mayor
+// o igual (con igual prioridad el FIFO puede dejar a i detrás de ellas).
+pub fn response_time_analysis(tasks: &[PeriodicTask], priorities: &[PriorityLevel]) -> RtaReport {
+    assert_eq!(tasks.len(), priorities.len(), "una prioridad por tarea");
+    let responses: Vec<TaskResponse> = (0..tasks.len())
+        .map(|i| {
+            let response = response_time(tasks, priorities, i);
+            TaskResponse { response, schedulable: response.is_some() }
+        })
+        .collect();
+
+    let n = tasks.len().max(1) as f64;
+    RtaReport {
+        schedulable: responses.iter().all(|r| r.schedulable),
+        tasks: responses,
+        utilization: tasks.iter().map(PeriodicTask::utilization).sum(),
+        liu_layland_bound: n * (2f64.powf(1.0 / n) - 1.0),
+    }
+}
+
+fn response_time(tasks: &[PeriodicTask], priorities: &[PriorityLevel], i: usize) -> Option<usize> {
+    let task = tasks[i];
+    if task.period == 0 {
+        return None;
+    }
+    let higher: Vec<PeriodicTask> = (0..tasks.len())
+        .filter(|&j| j != i && priorities[j] >= priorities[i])
+        .map(|j| tasks[j])
+        .collect();
+    if higher.iter().any(|t| t.period == 0) {
+        return None;
+    }
+
+    let mut response = task.wcet;
+    loop {
+        let next = task.wcet + higher.iter().map(|t| response.div_ceil(t.period) * t.wcet).sum::<usize>();
+        if next > task.deadline {
+            return None;
+        }
+        if next == response {
+            return Some(response);
+        }
+        response = next;
+    }
+}
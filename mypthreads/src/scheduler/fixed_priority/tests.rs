@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use std::ffi::c_void;
+    use crate::mythread::mythread::{AnyParam, MyThread, ThreadId};
+    use crate::mythread::mythreadattr::MyThreadAttr;
+    use crate::scheduler::fixed_priority::{rate_monotonic_priorities, response_time_analysis, FixedPriorityScheduler, PeriodicTask};
+    use crate::scheduler::SchedulerType;
+    use crate::Scheduler;
+
+    extern "C-unwind" fn dummy(_arg: *mut AnyParam) -> *mut c_void {
+        std::ptr::null_mut()
+    }
+
+    fn make_thread(id: ThreadId, priority: u8) -> MyThread {
+        MyThread::new(id, MyThreadAttr::new(usize::MAX, priority), dummy, std::ptr::null_mut(), Some(SchedulerType::FixedPriority))
+    }
+
+    #[test]
+    fn highest_level_first_and_fifo_within_level() {
+        let mut fp = FixedPriorityScheduler::new();
+        for (id, priority) in [(1, 10), (2, 200), (3, 10), (4, 0), (5, 200)] {
+            fp.enqueue(id, &make_thread(id, priority));
+        }
+        assert_eq!(fp.highest_ready(), Some(200));
+
+        let order: Vec<ThreadId> = std::iter::from_fn(|| fp.pick_next()).collect();
+        assert_eq!(order, vec![2, 5, 1, 3, 4]);
+        assert!(fp.is_empty());
+        assert_eq!(fp.highest_ready(), None);
+    }
+
+    #[test]
+    fn rate_monotonic_orders_by_period() {
+        let tasks = [PeriodicTask::new(50, 5), PeriodicTask::new(10, 1), PeriodicTask::new(20, 2), PeriodicTask::new(10, 3)];
+        let priorities = rate_monotonic_priorities(&tasks).unwrap();
+        assert_eq!(priorities, vec![253, 255, 254, 255]);
+    }
+
+    #[test]
+    fn rta_matches_textbook_example() {
+        // U = 0.93, sobre la cota de Liu y Layland, pero el RTA muestra que sí cumple
+        let tasks = [PeriodicTask::new(7, 3), PeriodicTask::new(12, 3), PeriodicTask::new(20, 5)];
+        let priorities = rate_monotonic_priorities(&tasks).unwrap();
+        let report = response_time_analysis(&tasks, &priorities);
+
+        let responses: Vec<_> = report.tasks.iter().map(|t| t.response).collect();
+        assert_eq!(responses, vec![Some(3), Some(6), Some(20)]);
+        assert!(report.schedulable);
+        assert!(report.utilization > report.liu_layland_bound);
+    }
+
+    #[test]
+    fn rta_reports_missed_deadline() {
+        let tasks = [PeriodicTask::new(4, 2), PeriodicTask::new(6, 3)];
+        let report = response_time_analysis(&tasks, &rate_monotonic_priorities(&tasks).unwrap());
+        assert_eq!(report.tasks[0].response, Some(2));
+        assert!(!report.tasks[1].schedulable);
+        assert!(!report.schedulable);
+
+        // Con deadline más corto que el periodo tampoco alcanza
+        let tasks = [PeriodicTask::new(10, 2), PeriodicTask::new(20, 5).with_deadline(6)];
+        let report = response_time_analysis(&tasks, &[255, 254]);
+        assert_eq!(report.tasks[1].response, None);
+    }
+}
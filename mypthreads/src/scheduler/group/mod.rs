@@ -0,0 +1,284 @@
+mod tests;
+
+use std::any::Any;
+use std::collections::HashMap;
+use crate::mythread::mythread::{MyThread, ThreadId};
+use crate::scheduler::{new_scheduler, SchedulerType};
+use crate::Scheduler;
+
+// Grupos de hilos al estilo de los cgroups: cada grupo tiene su propio scheduler para sus hilos y
+// un `share` frente a sus hermanos; los grupos se anidan sin límite. En cada nivel se escoge con
+// stride scheduling (determinista), así un grupo con 300 de share corre 3 veces más que uno con 100
+// sin importar cuántos hilos tenga cada uno.
+pub type GroupId = usize;
+pub const ROOT_GROUP: GroupId = 0;
+pub const DEFAULT_SHARE: u32 = 100;
+// Despachos por periodo de un grupo padre, para los `budget` de sus hijos
+pub const DEFAULT_PERIOD: u32 = 100;
+
+const STRIDE1: u64 = 1 << 20;
+
+struct Group {
+    name: String,
+    parent: Option<GroupId>,
+    children: Vec<GroupId>,
+    kind: SchedulerType,
+    // Hilos puestos directamente en este grupo
+    inner: Box<dyn Scheduler>,
+    share: u32,
+    // Despachos máximos por periodo del padre; se pasa solo si ningún hermano tiene trabajo
+    budget: Option<u32>,
+    used: u32,
+    pass: u64,
+    // Los hilos propios compiten con los subgrupos como otro hijo de DEFAULT_SHARE
+    own_pass: u64,
+    // Pass del último escogido aquí, para que quien despierta no traiga crédito acumulado
+    vtime: u64,
+    period: u32,
+    picks: u32,
+}
+
+impl Group {
+    fn new(name: &str, parent: Option<GroupId>, share: u32, kind: SchedulerType) -> Self {
+        Self {
+            name: name.into(),
+            parent,
+            children: Vec::new(),
+            kind,
+            inner: new_scheduler(kind),
+            share: share.max(1),
+            budget: None,
+            used: 0,
+            pass: 0,
+            own_pass: 0,
+            vtime: 0,
+            period: DEFAULT_PERIOD,
+            picks: 0,
+        }
+    }
+}
+
+// Quién gana un turno dentro de un grupo
+#[derive(Clone, Copy)]
+enum Turn {
+    Own,
+    Child(GroupId),
+}
+
+pub struct GroupScheduler {
+    groups: Vec<Group>,
+    // Grupo en que quedó encolado cada hilo, para avisarle a su scheduler
+    members: HashMap<ThreadId, GroupId>,
+}
+
+impl GroupScheduler {
+    pub fn new() -> Self {
+        Self { groups: vec![Group::new("root", None, DEFAULT_SHARE, SchedulerType::RoundRobin)], members: HashMap::new() }
+    }
+
+    // Grupo hijo de `parent` cuyos hilos se reparten con un scheduler `kind`. None si `parent` no existe.
+    pub fn create_group(&mut self, parent: GroupId, name: &str, share: u32, kind: SchedulerType) -> Option<GroupId> {
+        self.groups.get(parent)?;
+        let id = self.groups.len();
+        let mut group = Group::new(name, Some(parent), share, kind);
+        // Entra al reparto al nivel de sus hermanos, sin crédito ni deuda
+        group.pass = self.groups[parent].vtime;
+        self.groups.push(group);
+        self.groups[parent].children.push(id);
+        Some(id)
+    }
+
+    pub fn contains(&self, group: GroupId) -> bool {
+        group < self.groups.len()
+    }
+
+    pub fn name(&self, group: GroupId) -> Option<&str> {
+        self.groups.get(group).map(|g| g.name.as_str())
+    }
+
+    pub fn kind(&self, group: GroupId) -> Option<SchedulerType> {
+        self.groups.get(group).map(|g| g.kind)
+    }
+
+    pub fn parent(&self, group: GroupId) -> Option<GroupId> {
+        self.groups.get(group)?.parent
+    }
+
+    pub fn find(&self, name: &str) -> Option<GroupId> {
+        self.groups.iter().position(|g| g.name == name)
+    }
+
+    pub fn set_share(&mut self, group: GroupId, share: u32) -> bool {
+        match self.groups.get_mut(group) {
+            Some(g) if group != ROOT_GROUP => {
+                g.share = share.max(1);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // None quita el límite.
+    pub fn set_budget(&mut self, group: GroupId, budget: Option<u32>) -> bool {
+        match self.groups.get_mut(group) {
+            Some(g) if group != ROOT_GROUP => {
+                g.budget = budget;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Largo (en despachos) del periodo en que se cuentan los budgets de los hijos de `group`.
+    pub fn set_period(&mut self, group: GroupId, period: u32) -> bool {
+        let Some(g) = self.groups.get_mut(group) else { return false };
+        g.period = period.max(1);
+        g.picks = 0;
+        true
+    }
+
+    // Scheduler de los hilos propios del grupo (p.ej. para las monedas de un grupo Lottery).
+    pub fn inner(&mut self, group: GroupId) -> Option<&mut dyn Scheduler> {
+        Some(self.groups.get_mut(group)?.inner.as_mut())
+    }
+
+    fn subtree_empty(&self, group: GroupId) -> bool {
+        let g = &self.groups[group];
+        g.inner.is_empty() && g.children.iter().all(|&c| self.subtree_empty(c))
+    }
+
+    fn pick_in(&mut self, group: GroupId) -> Option<ThreadId> {
+        let own_stride = STRIDE1 / DEFAULT_SHARE as u64;
+        // (turno, pass, pasado de budget)
+        let mut turns: Vec<(Turn, u64, bool)> = Vec::new();
+        let g = &self.groups[group];
+        if !g.inner.is_empty() {
+            turns.push((Turn::Own, g.own_pass, false));
+        }
+        for &c in &g.children {
+            if !self.subtree_empty(c) {
+                let child = &self.groups[c];
+                turns.push((Turn::Child(c), child.pass, child.budget.is_some_and(|b| child.used >= b)));
+            }
+        }
+        let (turn, pass, _) = turns.into_iter().min_by_key(|&(_, pass, over)| (over, pass))?;
+
+        let g = &mut self.groups[group];
+        g.vtime = pass;
+        g.picks += 1;
+        let new_period = g.picks >= g.period;
+        if new_period {
+            g.picks = 0;
+            for c in g.children.clone() {
+                self.groups[c].used = 0;
+            }
+        }
+        match turn {
+            Turn::Own => {
+                let g = &mut self.groups[group];
+                g.own_pass += own_stride;
+                g.inner.pick_next()
+            }
+            Turn::Child(c) => {
+                let child = &mut self.groups[c];
+                child.pass += STRIDE1 / child.share as u64;
+                if !new_period {
+                    child.used += 1;
+                }
+                self.pick_in(c)
+            }
+        }
+    }
+
+    // Antes de encolar en `group`: cada nivel que estaba vacío se pone al día con el vtime de su padre.
+    fn catch_up(&mut self, group: GroupId) {
+        if self.groups[group].inner.is_empty() {
+            let g = &mut self.groups[group];
+            g.own_pass = g.own_pass.max(g.vtime);
+        }
+        let mut node = group;
+        while let Some(parent) = self.groups[node].parent {
+            if !self.subtree_empty(node) {
+                break;
+            }
+            let vtime = self.groups[parent].vtime;
+            let g = &mut self.groups[node];
+            g.pass = g.pass.max(vtime);
+            node = parent;
+        }
+    }
+}
+
+impl Default for GroupScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scheduler for GroupScheduler {
+    fn enqueue(&mut self, tid: ThreadId, t: &MyThread) {
+        // Un grupo que no existe cae en la raíz
+        let group = t.attr.group.filter(|&g| self.contains(g)).unwrap_or(ROOT_GROUP);
+        self.catch_up(group);
+        self.members.insert(tid, group);
+        self.groups[group].inner.enqueue(tid, t);
+    }
+
+    fn pick_next(&mut self) -> Option<ThreadId> {
+        self.pick_in(ROOT_GROUP)
+    }
+
+    fn remove(&mut self, tid: ThreadId) -> bool {
+        match self.members.get(&tid) {
+            Some(&g) => self.groups[g].inner.remove(tid),
+            None => false,
+        }
+    }
+
+    fn on_block(&mut self, tid: ThreadId) {
+        if let Some(&g) = self.members.get(&tid) {
+            self.groups[g].inner.on_block(tid);
+        }
+    }
+
+    fn on_wake(&mut self, tid: ThreadId) {
+        if let Some(&g) = self.members.get(&tid) {
+            self.groups[g].inner.on_wake(tid);
+        }
+    }
+
+    fn on_tick(&mut self) {
+        for g in &mut self.groups {
+            g.inner.on_tick();
+        }
+    }
+
+    fn on_exit(&mut self, tid: ThreadId) {
+        if let Some(g) = self.members.remove(&tid) {
+            self.groups[g].inner.on_exit(tid);
+        }
+    }
+
+    fn on_wait(&mut self, waiter: ThreadId, target: ThreadId) {
+        // Solo tiene sentido prestar tickets dentro del mismo grupo
+        if let (Some(&g), Some(&h)) = (self.members.get(&waiter), self.members.get(&target))
+            && g == h
+        {
+            self.groups[g].inner.on_wait(waiter, target);
+        }
+    }
+
+    fn on_wait_done(&mut self, waiter: ThreadId) {
+        if let Some(&g) = self.members.get(&waiter) {
+            self.groups[g].inner.on_wait_done(waiter);
+        }
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any { self }
+
+    fn len(&self) -> usize {
+        self.groups.iter().map(|g| g.inner.len()).sum()
+    }
+
+    fn is_empty(&self) -> bool { self.subtree_empty(ROOT_GROUP) }
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::ffi::c_void;
+    use crate::mythread::mythread::{AnyParam, MyThread, ThreadId};
+    use crate::mythread::mythreadattr::MyThreadAttr;
+    use crate::scheduler::group::{GroupId, GroupScheduler, ROOT_GROUP};
+    use crate::scheduler::SchedulerType;
+    use crate::Scheduler;
+
+    extern "C-unwind" fn dummy(_arg: *mut AnyParam) -> *mut c_void {
+        std::ptr::null_mut()
+    }
+
+    fn make_thread(id: ThreadId, group: GroupId, deadline: usize) -> MyThread {
+        let mut attr = MyThreadAttr::new(deadline, 1);
+        attr.set_group(Some(group));
+        MyThread::new(id, attr, dummy, std::ptr::null_mut(), None)
+    }
+
+    // Despacha `picks` veces devolviendo cada hilo a su cola (como un yield) y cuenta por hilo.
+    fn dispatch(gs: &mut GroupScheduler, threads: &HashMap<ThreadId, MyThread>, picks: usize) -> HashMap<ThreadId, usize> {
+        let mut counts = HashMap::new();
+        for _ in 0..picks {
+            let tid = gs.pick_next().unwrap();
+            *counts.entry(tid).or_default() += 1;
+            gs.enqueue(tid, &threads[&tid]);
+        }
+        counts
+    }
+
+    fn spawn(gs: &mut GroupScheduler, threads: &mut HashMap<ThreadId, MyThread>, ids: std::ops::Range<ThreadId>, group: GroupId) {
+        for id in ids {
+            let t = make_thread(id, group, usize::MAX);
+            gs.enqueue(id, &t);
+            threads.insert(id, t);
+        }
+    }
+
+    fn total(counts: &HashMap<ThreadId, usize>, ids: std::ops::Range<ThreadId>) -> usize {
+        ids.map(|id| counts.get(&id).copied().unwrap_or(0)).sum()
+    }
+
+    #[test]
+    fn shares_split_cpu_regardless_of_thread_count() {
+        let mut gs = GroupScheduler::new();
+        let cars = gs.create_group(ROOT_GROUP, "cars", 100, SchedulerType::RoundRobin).unwrap();
+        let trucks = gs.create_group(ROOT_GROUP, "trucks", 300, SchedulerType::RoundRobin).unwrap();
+        let mut threads = HashMap::new();
+        spawn(&mut gs, &mut threads, 0..20, cars);
+        spawn(&mut gs, &mut threads, 20..21, trucks);
+
+        let counts = dispatch(&mut gs, &threads, 400);
+        assert_eq!(total(&counts, 20..21), 300);
+        assert_eq!(total(&counts, 0..20), 100);
+        // Dentro de cars el RoundRobin reparte parejo
+        assert!((0..20).all(|id| counts[&id] == 5), "{counts:?}");
+    }
+
+    #[test]
+    fn nested_groups_split_their_parent_share() {
+        let mut gs = GroupScheduler::new();
+        let fleet = gs.create_group(ROOT_GROUP, "fleet", 200, SchedulerType::RoundRobin).unwrap();
+        let cars = gs.create_group(fleet, "cars", 100, SchedulerType::RoundRobin).unwrap();
+        let trucks = gs.create_group(fleet, "trucks", 100, SchedulerType::Lottery).unwrap();
+        let ships = gs.create_group(ROOT_GROUP, "ships", 200, SchedulerType::RoundRobin).unwrap();
+        assert_eq!(gs.parent(trucks), Some(fleet));
+        assert_eq!(gs.find("ships"), Some(ships));
+
+        let mut threads = HashMap::new();
+        spawn(&mut gs, &mut threads, 0..30, cars);
+        spawn(&mut gs, &mut threads, 30..33, trucks);
+        spawn(&mut gs, &mut threads, 33..35, ships);
+
+        let counts = dispatch(&mut gs, &threads, 800);
+        assert_eq!(total(&counts, 0..30), 200);
+        assert_eq!(total(&counts, 30..33), 200);
+        assert_eq!(total(&counts, 33..35), 400);
+    }
+
+    #[test]
+    fn budget_caps_group_while_siblings_have_work() {
+        let mut gs = GroupScheduler::new();
+        let hog = gs.create_group(ROOT_GROUP, "hog", 900, SchedulerType::RoundRobin).unwrap();
+        let other = gs.create_group(ROOT_GROUP, "other", 100, SchedulerType::RoundRobin).unwrap();
+        assert!(gs.set_budget(hog, Some(10)));
+        assert!(gs.set_period(ROOT_GROUP, 50));
+
+        let mut threads = HashMap::new();
+        spawn(&mut gs, &mut threads, 0..1, hog);
+        spawn(&mut gs, &mut threads, 1..2, other);
+        let counts = dispatch(&mut gs, &threads, 500);
+        assert_eq!(counts[&0], 100);
+
+        // Solo, el grupo limitado sigue corriendo: el budget no deja el CPU ocioso
+        let mut gs = GroupScheduler::new();
+        let hog = gs.create_group(ROOT_GROUP, "hog", 100, SchedulerType::RoundRobin).unwrap();
+        gs.set_budget(hog, Some(1));
+        let mut threads = HashMap::new();
+        spawn(&mut gs, &mut threads, 0..1, hog);
+        assert_eq!(dispatch(&mut gs, &threads, 20)[&0], 20);
+    }
+
+    #[test]
+    fn idle_group_does_not_bank_credit() {
+        let mut gs = GroupScheduler::new();
+        let a = gs.create_group(ROOT_GROUP, "a", 100, SchedulerType::RoundRobin).unwrap();
+        let b = gs.create_group(ROOT_GROUP, "b", 100, SchedulerType::RoundRobin).unwrap();
+        let mut threads = HashMap::new();
+        spawn(&mut gs, &mut threads, 0..1, a);
+        dispatch(&mut gs, &threads, 100);
+
+        // b llega tarde: se reparten a mitades desde ahí, no le debe 100 turnos
+        spawn(&mut gs, &mut threads, 1..2, b);
+        let counts = dispatch(&mut gs, &threads, 20);
+        assert_eq!(counts[&0], 10);
+        assert_eq!(counts[&1], 10);
+    }
+
+    #[test]
+    fn group_uses_its_own_scheduler_kind() {
+        let mut gs = GroupScheduler::new();
+        let rt = gs.create_group(ROOT_GROUP, "ambulances", 100, SchedulerType::RealTime).unwrap();
+        assert_eq!(gs.kind(rt), Some(SchedulerType::RealTime));
+        for (id, deadline) in [(0, 30), (1, 10), (2, 20)] {
+            gs.enqueue(id, &make_thread(id, rt, deadline));
+        }
+        assert_eq!(gs.pick_next(), Some(1));
+        assert_eq!(gs.pick_next(), Some(2));
+        assert_eq!(gs.pick_next(), Some(0));
+        assert!(gs.is_empty());
+        assert_eq!(gs.create_group(99, "x", 1, SchedulerType::RoundRobin), None);
+    }
+}
@@ -1,27 +1,174 @@
 mod tests;
 
-use rand::Rng;
+use std::any::Any;
+use std::collections::HashMap;
+use crate::mythread::myreplay::my_replay_seed;
 use crate::mythread::mythread::{MyThread, ThreadId};
 use super::Scheduler;
 
 
 
+// Moneda de tickets (p.ej. una por flota de vehículos). Se financia con tickets de la moneda base;
+// sus tickets valen `funding / tickets activos emitidos en ella`, así inflar una moneda no le
+// quita parte a las demás.
+pub type CurrencyId = usize;
+pub const BASE_CURRENCY: CurrencyId = 0;
+
+struct Currency {
+    name: String,
+    funding: u64,
+}
+
+// Tickets de un hilo. Si vienen de `priority` se recalculan en cada enqueue.
+#[derive(Debug, Clone, Copy)]
+struct Holding {
+    currency: CurrencyId,
+    amount: u64,
+    from_priority: bool,
+}
+
+// Compensación máxima (un hilo que usó 1% de su quantum o menos)
+const MAX_COMPENSATION: f64 = 100.0;
+
 pub struct LotteryScheduler {
-    entries: Vec<(ThreadId, u32)>, // (tid, tickets)
+    entries: Vec<ThreadId>, // hilos listos
+    holdings: HashMap<ThreadId, Holding>,
+    currencies: Vec<Currency>, // [BASE_CURRENCY] es la base, 1:1
+    // Multiplicador de quien cedió el CPU antes de acabar su quantum, hasta que vuelva a ganar
+    compensation: HashMap<ThreadId, f64>,
+    // Quien espera -> a quién le prestó sus tickets
+    lent: HashMap<ThreadId, ThreadId>,
     rng_state: u64,
 }
 impl LotteryScheduler {
     pub fn new() -> Self {
-        // Semilla
-        Self { entries: Vec::new(), rng_state: 0x9E3779B97F4A7C15 }
+        // Semilla de la sesión de replay del hilo, así toda la corrida sale de una sola semilla
+        Self::with_seed(my_replay_seed())
     }
 
 
-    #[cfg(test)]
     pub fn with_seed(seed: u64) -> Self {
-        Self { entries: Vec::new(), rng_state: seed }
+        Self {
+            entries: Vec::new(),
+            holdings: HashMap::new(),
+            currencies: vec![Currency { name: "base".into(), funding: 0 }],
+            compensation: HashMap::new(),
+            lent: HashMap::new(),
+            rng_state: seed,
+        }
+    }
+
+    // Crea una moneda financiada con `funding` tickets base.
+    pub fn create_currency(&mut self, name: &str, funding: u64) -> CurrencyId {
+        self.currencies.push(Currency { name: name.into(), funding });
+        self.currencies.len() - 1
+    }
+
+    pub fn currency_name(&self, currency: CurrencyId) -> Option<&str> {
+        self.currencies.get(currency).map(|c| c.name.as_str())
+    }
+
+    // Infla o desinfla una moneda: cambia cuánto vale en tickets base. La base no se puede cambiar.
+    pub fn set_currency_funding(&mut self, currency: CurrencyId, funding: u64) -> bool {
+        match self.currencies.get_mut(currency) {
+            Some(c) if currency != BASE_CURRENCY => {
+                c.funding = funding;
+                true
+            }
+            _ => false,
+        }
     }
 
+    // Le da a `tid` `amount` tickets de `currency` en vez de los de su prioridad.
+    pub fn fund_thread(&mut self, tid: ThreadId, currency: CurrencyId, amount: u64) -> bool {
+        if currency >= self.currencies.len() {
+            return false;
+        }
+        self.holdings.insert(tid, Holding { currency, amount, from_priority: false });
+        true
+    }
+
+    // Inflación/deflación de un hilo: cambia sus tickets sin cambiar de moneda.
+    pub fn set_tickets(&mut self, tid: ThreadId, amount: u64) -> bool {
+        match self.holdings.get_mut(&tid) {
+            Some(h) => {
+                h.amount = amount;
+                h.from_priority = false;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // `from` le presta todos sus tickets a `to` (p.ej. mientras lo espera en un join).
+    pub fn transfer(&mut self, from: ThreadId, to: ThreadId) {
+        if from != to {
+            self.lent.insert(from, to);
+        }
+    }
+
+    // Devuelve lo que `from` había prestado.
+    pub fn return_transfer(&mut self, from: ThreadId) {
+        self.lent.remove(&from);
+    }
+
+    // A quién le está prestando `from` sus tickets, si se los prestó a alguien.
+    pub fn lent_to(&self, from: ThreadId) -> Option<ThreadId> {
+        self.lent.get(&from).copied()
+    }
+
+    // Ticket de compensación: `tid` cedió el CPU habiendo usado `used_fraction` de su quantum, así que
+    // sus tickets valen 1/used_fraction hasta que vuelva a ganar.
+    pub fn compensate(&mut self, tid: ThreadId, used_fraction: f64) {
+        if used_fraction > 0.0 && used_fraction < 1.0 {
+            self.compensation.insert(tid, (1.0 / used_fraction).min(MAX_COMPENSATION));
+        } else if used_fraction <= 0.0 {
+            self.compensation.insert(tid, MAX_COMPENSATION);
+        }
+    }
+
+    // Valor en tickets base con que `tid` entraría al próximo sorteo (0 si no está listo).
+    pub fn value_of(&self, tid: ThreadId) -> f64 {
+        if !self.entries.contains(&tid) {
+            return 0.0;
+        }
+        let active = self.active_amounts();
+        self.weight(tid, &active)
+    }
+
+    // Tickets activos emitidos en cada moneda: los de hilos listos y los prestados a hilos listos.
+    fn active_amounts(&self) -> Vec<u64> {
+        let mut active = vec![0u64; self.currencies.len()];
+        let lenders = self.lent.iter().filter(|(_, to)| self.entries.contains(to)).map(|(from, _)| from);
+        for tid in self.entries.iter().chain(lenders) {
+            if let Some(h) = self.holdings.get(tid) {
+                active[h.currency] += h.amount;
+            }
+        }
+        active
+    }
+
+    fn base_value(&self, tid: ThreadId, active: &[u64]) -> f64 {
+        let Some(h) = self.holdings.get(&tid) else { return 0.0 };
+        if h.currency == BASE_CURRENCY {
+            return h.amount as f64;
+        }
+        match active[h.currency] {
+            0 => 0.0,
+            issued => h.amount as f64 * self.currencies[h.currency].funding as f64 / issued as f64,
+        }
+    }
+
+    fn weight(&self, tid: ThreadId, active: &[u64]) -> f64 {
+        let own = self.base_value(tid, active) * self.compensation.get(&tid).copied().unwrap_or(1.0);
+        let borrowed: f64 = self
+            .lent
+            .iter()
+            .filter(|&(_, &to)| to == tid)
+            .map(|(&from, _)| self.base_value(from, active))
+            .sum();
+        own + borrowed
+    }
 
     #[inline]
     fn next_u64(&mut self) -> u64 {
@@ -32,53 +179,74 @@ impl LotteryScheduler {
         z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
         z ^ (z >> 31)
     }
-
-    #[inline]
-    fn sample_u64_below(&mut self, n: u64) -> u64 {
-        // toma la parte alta de (rand * n) para evitar sesgo
-        let r = self.next_u64();
-        (((r as u128) * (n as u128)) >> 64) as u64
-    }
 }
 
 
 impl Scheduler for LotteryScheduler {
     fn enqueue(&mut self, tid: ThreadId, t: &MyThread) {
-        let tickets = unsafe {
-            // Usa priority como tickets (mín 1)
-            let p = (*t.attr).priority as u32;
-            p.max(1)
-        };
-        self.entries.push((tid, tickets));
+        // Sin fondos explícitos usa priority como tickets base (mín 1)
+        let tickets = (t.attr.priority as u64).max(1);
+        let h = self.holdings.entry(tid).or_insert(Holding { currency: BASE_CURRENCY, amount: tickets, from_priority: true });
+        if h.from_priority {
+            h.amount = tickets;
+        }
+        self.entries.push(tid);
     }
 
     fn pick_next(&mut self) -> Option<ThreadId> {
         if self.entries.is_empty() { return None; }
 
-        let total: u64 = self.entries.iter().map(|&(_, tk)| tk as u64).sum();
-        if total == 0 { return None; }
-
-        let r = (self.next_u64() % total) as u64;
-        let mut acc = 0u64;
-        let mut idx = 0usize;
-
-        for (i, &(_, tk)) in self.entries.iter().enumerate() {
-            acc += tk as u64;
-            if r < acc {
-                idx = i;
-                break;
+        let active = self.active_amounts();
+        let weights: Vec<f64> = self.entries.iter().map(|&tid| self.weight(tid, &active)).collect();
+        let total: f64 = weights.iter().sum();
+
+        let mut idx = self.entries.len() - 1;
+        if total <= 0.0 {
+            // Nadie tiene tickets (p.ej. set_tickets(.., 0)): sorteo parejo para no dejar hilos colgados
+            idx = (self.next_u64() % self.entries.len() as u64) as usize;
+        } else {
+            // 53 bits del generador -> uniforme en [0, total)
+            let r = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64 * total;
+            let mut acc = 0.0;
+            for (i, w) in weights.iter().enumerate() {
+                acc += w;
+                if r < acc {
+                    idx = i;
+                    break;
+                }
             }
         }
-        let (tid, _) = self.entries.swap_remove(idx);
+        let tid = self.entries.swap_remove(idx);
+        // Ganó: se le acaba la compensación
+        self.compensation.remove(&tid);
         Some(tid)
     }
 
+    fn remove(&mut self, tid: ThreadId) -> bool {
+        let Some(i) = self.entries.iter().position(|&t| t == tid) else { return false };
+        self.entries.remove(i);
+        true
+    }
+
     fn on_block(&mut self, _tid: ThreadId) {
 
     }
 
-    fn on_exit(&mut self, _tid: ThreadId) {  }
+    fn on_exit(&mut self, tid: ThreadId) {
+        self.holdings.remove(&tid);
+        self.compensation.remove(&tid);
+        self.lent.retain(|&from, &mut to| from != tid && to != tid);
+    }
 
-    fn is_empty(&self) -> bool { self.entries.is_empty() }
-}
+    fn on_wait(&mut self, waiter: ThreadId, target: ThreadId) {
+        self.transfer(waiter, target);
+    }
 
+    fn on_wait_done(&mut self, waiter: ThreadId) {
+        self.return_transfer(waiter);
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any { self }
+
+    fn len(&self) -> usize { self.entries.len() }
+}
@@ -1,15 +1,14 @@
 // scheduler/lottery.rs (o en tests/lottery_tests.rs)
 #[cfg(test)]
 mod tests {
-    use super::*;
     use std::ffi::c_void;
     use crate::scheduler::scheduler_type::SchedulerType;
     use crate::mythread::mythread::{MyThread, ThreadId, AnyParam};
     use crate::mythread::mythreadattr::MyThreadAttr;
     use crate::Scheduler;
-    use crate::scheduler::lottery::LotteryScheduler;
+    use crate::scheduler::lottery::{LotteryScheduler, BASE_CURRENCY};
 
-    extern "C" fn dummy(_arg: *mut AnyParam) -> *mut c_void {
+    extern "C-unwind" fn dummy(_arg: *mut AnyParam) -> *mut c_void {
         std::ptr::null_mut()
     }
 
@@ -18,10 +17,8 @@ mod tests {
         tickets: u8,
         deadline: usize,
         sched: SchedulerType,
-    ) -> (MyThread, Box<MyThreadAttr>) {
-        let mut attr = Box::new(MyThreadAttr::new(deadline, tickets));
-        let t = MyThread::new(id, &mut *attr, dummy, std::ptr::null_mut(), Some(sched));
-        (t, attr)
+    ) -> MyThread {
+        MyThread::new(id, MyThreadAttr::new(deadline, tickets), dummy, std::ptr::null_mut(), Some(sched))
     }
 
     #[test]
@@ -29,8 +26,8 @@ mod tests {
         let mut lot = LotteryScheduler::new();
 
         // t1: 1 ticket, t2: 9 tickets => ~10% vs ~90%
-        let (t1, _a1) = make_thread(1, 1, usize::MAX, SchedulerType::Lottery);
-        let (t2, _a2) = make_thread(2, 9, usize::MAX, SchedulerType::Lottery);
+        let t1 = make_thread(1, 1, usize::MAX, SchedulerType::Lottery);
+        let t2 = make_thread(2, 9, usize::MAX, SchedulerType::Lottery);
 
         let trials = 20_000;
         let mut c1 = 0usize;
@@ -64,8 +61,8 @@ mod tests {
         let mut lot = LotteryScheduler::new();
 
         // priority=0 -> tickets=1 por max(1)
-        let (t1, _a1) = make_thread(1, 0, usize::MAX, SchedulerType::Lottery);
-        let (t2, _a2) = make_thread(2, 0, usize::MAX, SchedulerType::Lottery);
+        let t1 = make_thread(1, 0, usize::MAX, SchedulerType::Lottery);
+        let t2 = make_thread(2, 0, usize::MAX, SchedulerType::Lottery);
 
         let trials = 6_000;
         let mut c1 = 0usize;
@@ -88,6 +85,131 @@ mod tests {
         assert!(p1 > 0.45 && p1 < 0.55, "p1={p1}, c1={c1}, c2={c2}");
     }
 
+    // Frecuencia con que gana cada hilo de `ready` en `trials` sorteos. `before` corre antes de cada
+    // sorteo (para renovar compensaciones, etc.).
+    fn win_rates(
+        lot: &mut LotteryScheduler,
+        ready: &[&MyThread],
+        trials: usize,
+        mut before: impl FnMut(&mut LotteryScheduler),
+    ) -> Vec<f64> {
+        let mut wins = vec![0usize; ready.len()];
+        for _ in 0..trials {
+            for t in ready {
+                lot.enqueue(t.id, t);
+            }
+            before(lot);
+            let winner = lot.pick_next().unwrap();
+            while lot.pick_next().is_some() {}
+            let i = ready.iter().position(|t| t.id == winner).unwrap();
+            wins[i] += 1;
+        }
+        wins.iter().map(|&w| w as f64 / trials as f64).collect()
+    }
+
+    fn near(p: f64, expected: f64) -> bool {
+        (p - expected).abs() < 0.02
+    }
+
+    #[test]
+    fn currencies_are_valued_by_their_funding() {
+        let mut lot = LotteryScheduler::with_seed(7);
+        let t1 = make_thread(1, 1, usize::MAX, SchedulerType::Lottery);
+        let t2 = make_thread(2, 1, usize::MAX, SchedulerType::Lottery);
+        let t3 = make_thread(3, 1, usize::MAX, SchedulerType::Lottery);
+
+        // Flota A (100) reparte 1:3 entre t1 y t2, flota B (100) es toda de t3
+        let fleet_a = lot.create_currency("flota-a", 100);
+        let fleet_b = lot.create_currency("flota-b", 100);
+        assert!(lot.fund_thread(1, fleet_a, 1));
+        assert!(lot.fund_thread(2, fleet_a, 3));
+        assert!(lot.fund_thread(3, fleet_b, 1));
+        assert_eq!(lot.currency_name(fleet_b), Some("flota-b"));
+
+        let p = win_rates(&mut lot, &[&t1, &t2, &t3], 20_000, |_| {});
+        assert!(near(p[0], 0.125) && near(p[1], 0.375) && near(p[2], 0.5), "{p:?}");
+
+        // Inflar dentro de la flota A no le quita nada a la B
+        assert!(lot.set_tickets(2, 99));
+        let p = win_rates(&mut lot, &[&t1, &t2, &t3], 20_000, |_| {});
+        assert!(near(p[0], 0.005) && near(p[2], 0.5), "{p:?}");
+    }
+
+    #[test]
+    fn currency_inflation_changes_exchange_rate() {
+        let mut lot = LotteryScheduler::with_seed(11);
+        let t1 = make_thread(1, 1, usize::MAX, SchedulerType::Lottery);
+        let t2 = make_thread(2, 1, usize::MAX, SchedulerType::Lottery);
+        let fleet_a = lot.create_currency("flota-a", 100);
+        let fleet_b = lot.create_currency("flota-b", 100);
+        lot.fund_thread(1, fleet_a, 5);
+        lot.fund_thread(2, fleet_b, 50);
+
+        // Mismo fondo: da igual cuántos tickets emitió cada una
+        let p = win_rates(&mut lot, &[&t1, &t2], 20_000, |_| {});
+        assert!(near(p[0], 0.5), "{p:?}");
+
+        assert!(lot.set_currency_funding(fleet_b, 300));
+        let p = win_rates(&mut lot, &[&t1, &t2], 20_000, |_| {});
+        assert!(near(p[1], 0.75), "{p:?}");
+
+        // La moneda base siempre es 1:1
+        assert!(!lot.set_currency_funding(BASE_CURRENCY, 10));
+    }
+
+    #[test]
+    fn transferred_tickets_boost_the_waited_thread() {
+        let mut lot = LotteryScheduler::with_seed(3);
+        let t1 = make_thread(1, 1, usize::MAX, SchedulerType::Lottery);
+        let t2 = make_thread(2, 9, usize::MAX, SchedulerType::Lottery);
+        let t3 = make_thread(3, 10, usize::MAX, SchedulerType::Lottery);
+
+        // t2 se registra y luego se bloquea esperando a t1
+        lot.enqueue(2, &t2);
+        while lot.pick_next().is_some() {}
+        lot.on_wait(2, 1);
+        assert_eq!(lot.lent_to(2), Some(1));
+
+        let p = win_rates(&mut lot, &[&t1, &t3], 20_000, |_| {});
+        assert!(near(p[0], 0.5), "{p:?}");
+
+        lot.on_wait_done(2);
+        let p = win_rates(&mut lot, &[&t1, &t3], 20_000, |_| {});
+        assert!(near(p[0], 1.0 / 11.0), "{p:?}");
+    }
+
+    #[test]
+    fn compensation_lasts_until_next_win() {
+        let mut lot = LotteryScheduler::with_seed(5);
+        let t1 = make_thread(1, 10, usize::MAX, SchedulerType::Lottery);
+        let t2 = make_thread(2, 10, usize::MAX, SchedulerType::Lottery);
+
+        // t1 siempre cede tras usar 1/4 de su quantum: sus 10 tickets valen 40
+        let p = win_rates(&mut lot, &[&t1, &t2], 20_000, |l| l.compensate(1, 0.25));
+        assert!(near(p[0], 0.8), "{p:?}");
+
+        lot.enqueue(1, &t1);
+        lot.enqueue(2, &t2);
+        lot.compensate(1, 0.25);
+        assert_eq!(lot.value_of(1), 40.0);
+        while lot.pick_next() != Some(1) {
+            lot.enqueue(2, &t2);
+        }
+        lot.enqueue(1, &t1);
+        assert_eq!(lot.value_of(1), 10.0);
+    }
+
+    #[test]
+    fn exit_clears_holdings_and_loans() {
+        let mut lot = LotteryScheduler::with_seed(1);
+        let t1 = make_thread(1, 4, usize::MAX, SchedulerType::Lottery);
+        lot.enqueue(1, &t1);
+        lot.transfer(2, 1);
+        lot.on_exit(1);
+        assert_eq!(lot.lent_to(2), None);
+        assert!(!lot.set_tickets(1, 3));
+    }
+
     #[test]
     fn lottery_empty_returns_none() {
         let mut lot = LotteryScheduler::new();
@@ -1,14 +1,19 @@
 pub mod round_robin;
 pub mod lottery;
+pub mod group;
 pub mod scheduler_type;
 pub mod scheduler_params;
 pub(crate) mod real_time;
+pub mod fixed_priority;
 
 
-pub use real_time::RealTimeScheduler;
-pub use scheduler_type::SchedulerType;
+pub use real_time::{AperiodicServer, RealTimeScheduler, ServerId};
+pub use fixed_priority::FixedPriorityScheduler;
+pub use group::{GroupId, GroupScheduler, ROOT_GROUP};
+pub use scheduler_type::{SchedulerId, SchedulerType};
 pub use scheduler_params::SchedulerParams;
 
+use std::any::Any;
 use crate::{mythread::mythread::MyThread};
 use crate::mythread::mythread::ThreadId;
 
@@ -20,10 +25,40 @@ pub trait Scheduler {
     // Saca el siguiente hilo a ejecutar (None si vacío)
     fn pick_next(&mut self) -> Option<ThreadId>;
 
+    // Saca de la cola a un hilo listo sin despacharlo (cambio de scheduler o de grupo).
+    // False si no estaba encolado aquí.
+    fn remove(&mut self, tid: ThreadId) -> bool;
+
     // Eventos
     fn on_block(&mut self, _tid: ThreadId) {}
+    // `tid` estaba bloqueado y vuelve a estar listo; si va a la cola, se encola después de esto
+    fn on_wake(&mut self, _tid: ThreadId) {}
     fn on_exit(&mut self, _tid: ThreadId) {}
+    // Una vez por despacho del runtime, a todos los schedulers aunque no les toque escoger
+    fn on_tick(&mut self) {}
+    // `waiter` se bloquea esperando a `target` (join) y cuando deja de esperarlo
+    fn on_wait(&mut self, _waiter: ThreadId, _target: ThreadId) {}
+    fn on_wait_done(&mut self, _waiter: ThreadId) {}
+
+    // Para llegar a la API propia de cada scheduler (monedas de Lottery, etc.)
+    fn as_any(&mut self) -> &mut dyn Any;
 
-    fn is_empty(&self) -> bool;
+    // Hilos encolados
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
+// Scheduler vacío del tipo pedido (lo usan los grupos para sus hilos propios). Un scheduler
+// registrado es una sola instancia del runtime, así que dentro de un grupo se reparte con RoundRobin.
+pub(crate) fn new_scheduler(kind: SchedulerType) -> Box<dyn Scheduler> {
+    match kind {
+        SchedulerType::RoundRobin => Box::new(round_robin::RRScheduler::new()),
+        SchedulerType::Lottery => Box::new(lottery::LotteryScheduler::new()),
+        SchedulerType::RealTime => Box::new(RealTimeScheduler::new()),
+        SchedulerType::FixedPriority => Box::new(FixedPriorityScheduler::new()),
+        SchedulerType::Custom(_) => Box::new(round_robin::RRScheduler::new()),
+    }
+}
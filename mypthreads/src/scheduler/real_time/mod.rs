@@ -1,23 +1,114 @@
 mod tests;
+mod server;
+
+pub use server::{AperiodicServer, ServerId};
 
 use std::cmp::Reverse;
-use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::collections::BinaryHeap;
+use std::any::Any;
 use crate::mythread::mythread::{MyThread, ThreadId};
+use crate::scheduler::fixed_priority::PeriodicTask;
 use crate::Scheduler;
 
 pub struct RealTimeScheduler {
     heap: BinaryHeap<Reverse<(usize, ThreadId)>>, // (deadline, tid)
+    // Servidores para hilos aperiódicos (MyThreadAttr::set_server)
+    servers: Vec<AperiodicServer>,
+    // Tiempo virtual: despachos desde que se creó (on_tick)
+    now: usize,
+}
+impl RealTimeScheduler { pub fn new() -> Self { Self { heap: BinaryHeap::new(), servers: Vec::new(), now: 0 } } }
+
+impl Default for RealTimeScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RealTimeScheduler {
+    // Reserva `budget` despachos cada `period` para hilos aperiódicos. None si budget es 0, pasa
+    // del periodo o el ancho de banda de todos los servidores pasaría de 1.
+    pub fn create_server(&mut self, budget: usize, period: usize) -> Option<ServerId> {
+        if budget == 0 || budget > period {
+            return None;
+        }
+        if self.server_bandwidth() + budget as f64 / period as f64 > 1.0 + f64::EPSILON {
+            return None;
+        }
+        self.servers.push(AperiodicServer::new(budget, period));
+        Some(self.servers.len() - 1)
+    }
+
+    pub fn server(&self, id: ServerId) -> Option<&AperiodicServer> {
+        self.servers.get(id)
+    }
+
+    // Fracción del CPU reservada por los servidores
+    pub fn server_bandwidth(&self) -> f64 {
+        self.servers.iter().map(AperiodicServer::bandwidth).sum()
+    }
+
+    // Prueba de EDF: las tareas periódicas (con densidad wcet / min(deadline, period)) más los
+    // servidores caben en el CPU. Si pasa, ninguna tarea periódica pierde su deadline por culpa
+    // de los aperiódicos.
+    pub fn admits(&self, tasks: &[PeriodicTask]) -> bool {
+        let density: f64 = tasks.iter().map(|t| t.wcet as f64 / t.deadline.min(t.period).max(1) as f64).sum();
+        density + self.server_bandwidth() <= 1.0 + f64::EPSILON
+    }
+
+    pub fn now(&self) -> usize {
+        self.now
+    }
+
+    // Algún hilo encolado todavía puede cumplir su deadline (o un servidor tiene presupuesto para
+    // los suyos). El aging no pasa por encima de RealTime mientras sea así.
+    pub fn deadlines_in_flight(&self) -> bool {
+        self.heap.iter().any(|Reverse((dl, _))| *dl >= self.now) || self.servers.iter().any(AperiodicServer::eligible)
+    }
+
+    // Servidor con presupuesto y el deadline más cercano
+    fn earliest_server(&self) -> Option<ServerId> {
+        (0..self.servers.len()).filter(|&s| self.servers[s].eligible()).min_by_key(|&s| self.servers[s].deadline())
+    }
 }
-impl RealTimeScheduler { pub fn new() -> Self { Self { heap: BinaryHeap::new() } } }
 
 impl Scheduler for RealTimeScheduler {
     fn enqueue(&mut self, tid: ThreadId, t: &MyThread) {
-        let dl = unsafe { (*t.attr).dead_line };
+        // Un servidor que no existe aquí (p.ej. en un worker de MultiRuntime) no cuenta
+        if let Some(server) = t.attr.server.and_then(|s| self.servers.get_mut(s)) {
+            server.push(tid, self.now);
+            return;
+        }
+        let dl = t.attr.dead_line;
         self.heap.push(Reverse((dl, tid)));
     }
     fn pick_next(&mut self) -> Option<ThreadId> {
-        self.heap.pop().map(|Reverse((_dl, tid))| tid)
+        let now = self.now;
+        for server in &mut self.servers {
+            server.replenish(now);
+        }
+        let thread = self.heap.peek().map(|Reverse((dl, _))| *dl);
+        // En empate gana el periódico
+        if let Some(s) = self.earliest_server().filter(|&s| thread.is_none_or(|dl| self.servers[s].deadline() < dl)) {
+            return self.servers[s].dispatch(now, true);
+        }
+        if let Some(Reverse((_dl, tid))) = self.heap.pop() {
+            return Some(tid);
+        }
+        // Nada más listo: los servidores frenados corren de fondo sin gastar presupuesto
+        self.servers.iter_mut().find(|s| s.pending() > 0)?.dispatch(now, false)
+    }
+    fn remove(&mut self, tid: ThreadId) -> bool {
+        let before = self.len();
+        self.heap.retain(|Reverse((_dl, t))| *t != tid);
+        for server in &mut self.servers {
+            server.queue.retain(|&t| t != tid);
+        }
+        self.len() != before
     }
-    fn is_empty(&self) -> bool { self.heap.is_empty() }
+    fn on_tick(&mut self) {
+        self.now += 1;
+    }
+    fn as_any(&mut self) -> &mut dyn Any { self }
+    fn len(&self) -> usize { self.heap.len() + self.servers.iter().map(AperiodicServer::pending).sum::<usize>() }
 }
-
@@ -0,0 +1,94 @@
+use std::collections::VecDeque;
+use crate::mythread::mythread::ThreadId;
+
+// Índice de un servidor en su RealTimeScheduler (ver RealTimeScheduler::create_server)
+pub type ServerId = usize;
+
+// Servidor de ancho de banda constante (CBS duro) para hilos aperiódicos. Reserva `budget`
+// despachos cada `period` y compite en EDF con su propio deadline; sus hilos corren en FIFO.
+// Al gastar el presupuesto se frena hasta su deadline: ahí recupera el presupuesto y el deadline
+// avanza un periodo. Así nunca usa más de budget / period del CPU y los hilos periódicos siguen
+// cumpliendo mientras la suma de utilizaciones no pase de 1.
+#[derive(Debug, Clone)]
+pub struct AperiodicServer {
+    budget: usize,
+    period: usize,
+    remaining: usize,
+    deadline: usize,
+    // Frenado hasta este tick
+    replenish_at: Option<usize>,
+    pub(crate) queue: VecDeque<ThreadId>,
+}
+
+impl AperiodicServer {
+    pub(crate) fn new(budget: usize, period: usize) -> Self {
+        Self { budget, period, remaining: budget, deadline: 0, replenish_at: None, queue: VecDeque::new() }
+    }
+
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    // Despachos que le quedan en el periodo actual
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    pub fn deadline(&self) -> usize {
+        self.deadline
+    }
+
+    pub fn bandwidth(&self) -> f64 {
+        self.budget as f64 / self.period as f64
+    }
+
+    pub fn is_throttled(&self) -> bool {
+        self.replenish_at.is_some()
+    }
+
+    // Hilos esperando turno en el servidor
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+
+    // Llega un hilo. Si el servidor estaba vacío y con lo que le queda pasaría de su ancho de banda
+    // antes del deadline actual, arranca un periodo nuevo (regla de activación de CBS).
+    pub(crate) fn push(&mut self, tid: ThreadId, now: usize) {
+        if self.queue.is_empty() && !self.is_throttled() && self.remaining * self.period >= self.deadline.saturating_sub(now) * self.budget {
+            self.remaining = self.budget;
+            self.deadline = now + self.period;
+        }
+        self.queue.push_back(tid);
+    }
+
+    pub(crate) fn replenish(&mut self, now: usize) {
+        if let Some(at) = self.replenish_at.filter(|&at| at <= now) {
+            self.replenish_at = None;
+            self.remaining = self.budget;
+            self.deadline = at + self.period;
+        }
+    }
+
+    // Puede competir en EDF: tiene hilos y presupuesto
+    pub(crate) fn eligible(&self) -> bool {
+        !self.queue.is_empty() && !self.is_throttled()
+    }
+
+    // Saca el siguiente hilo y le cobra un despacho. `charge` es false cuando corre de fondo (nada
+    // más listo), que no le quita nada a los periódicos.
+    pub(crate) fn dispatch(&mut self, now: usize, charge: bool) -> Option<ThreadId> {
+        let tid = self.queue.pop_front()?;
+        if charge {
+            self.remaining -= 1;
+            if self.remaining == 0 {
+                // Si ya se le pasó el deadline se recarga en el siguiente tick
+                self.replenish_at = Some(self.deadline.max(now + 1));
+            }
+        }
+        Some(tid)
+    }
+}
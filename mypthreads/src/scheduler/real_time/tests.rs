@@ -1,15 +1,16 @@
 // scheduler/real_time.rs (o en tests/real_time_tests.rs)
 #[cfg(test)]
 mod tests {
-    use super::*;
     use std::ffi::c_void;
     use crate::scheduler::scheduler_type::SchedulerType;
     use crate::mythread::mythread::{MyThread, ThreadId, AnyParam};
     use crate::mythread::mythreadattr::MyThreadAttr;
     use crate::Scheduler;
     use crate::scheduler::RealTimeScheduler;
+    use crate::scheduler::fixed_priority::PeriodicTask;
+    use std::collections::HashMap;
 
-    extern "C" fn dummy(_arg: *mut AnyParam) -> *mut c_void {
+    extern "C-unwind" fn dummy(_arg: *mut AnyParam) -> *mut c_void {
         std::ptr::null_mut()
     }
 
@@ -18,19 +19,17 @@ mod tests {
         priority: u8,
         deadline: usize,
         sched: SchedulerType,
-    ) -> (MyThread, Box<MyThreadAttr>) {
-        let mut attr = Box::new(MyThreadAttr::new(deadline, priority));
-        let t = MyThread::new(id, &mut *attr, dummy, std::ptr::null_mut(), Some(sched));
-        (t, attr)
+    ) -> MyThread {
+        MyThread::new(id, MyThreadAttr::new(deadline, priority), dummy, std::ptr::null_mut(), Some(sched))
     }
 
     #[test]
     fn edf_picks_earliest_deadline_first() {
         let mut edf = RealTimeScheduler::new();
 
-        let (t1, _a1) = make_thread(1, 0, 50, SchedulerType::RealTime);
-        let (t2, _a2) = make_thread(2, 0, 10, SchedulerType::RealTime);
-        let (t3, _a3) = make_thread(3, 0, 30, SchedulerType::RealTime);
+        let t1 = make_thread(1, 0, 50, SchedulerType::RealTime);
+        let t2 = make_thread(2, 0, 10, SchedulerType::RealTime);
+        let t3 = make_thread(3, 0, 30, SchedulerType::RealTime);
 
         edf.enqueue(1, &t1);
         edf.enqueue(2, &t2);
@@ -47,8 +46,8 @@ mod tests {
     fn edf_tie_breaker_by_tid_when_same_deadline() {
         let mut edf = RealTimeScheduler::new();
 
-        let (t1, _a1) = make_thread(10, 0, 100, SchedulerType::RealTime);
-        let (t2, _a2) = make_thread(5,  0, 100, SchedulerType::RealTime); // mismo deadline, menor tid
+        let t1 = make_thread(10, 0, 100, SchedulerType::RealTime);
+        let t2 = make_thread(5,  0, 100, SchedulerType::RealTime); // mismo deadline, menor tid
 
         edf.enqueue(10, &t1);
         edf.enqueue(5,  &t2);
@@ -65,4 +64,114 @@ mod tests {
         assert!(edf.is_empty());
         assert_eq!(edf.pick_next(), None);
     }
+
+    #[test]
+    fn server_spends_budget_then_waits_for_replenishment() {
+        let mut edf = RealTimeScheduler::new();
+        let server = edf.create_server(2, 5).unwrap();
+        assert_eq!(edf.create_server(4, 5), None, "0.4 + 0.8 no cabe");
+
+        let mut aperiodic = make_thread(1, 0, 0, SchedulerType::RealTime);
+        aperiodic.attr.set_server(Some(server));
+        let periodic = make_thread(2, 0, 100, SchedulerType::RealTime);
+
+        // El servidor arranca con deadline 0 + 5, antes que el periódico
+        edf.enqueue(1, &aperiodic);
+        edf.enqueue(2, &periodic);
+        assert_eq!(edf.pick_next(), Some(1));
+        edf.on_tick();
+        edf.enqueue(1, &aperiodic);
+        assert_eq!(edf.pick_next(), Some(1));
+        assert!(edf.server(server).unwrap().is_throttled());
+
+        // Sin presupuesto le toca al periódico aunque su deadline sea mayor
+        edf.on_tick();
+        edf.enqueue(1, &aperiodic);
+        assert_eq!(edf.pick_next(), Some(2));
+        // Con nada más listo el aperiódico corre de fondo sin gastar
+        assert_eq!(edf.pick_next(), Some(1));
+        assert_eq!(edf.server(server).unwrap().remaining(), 0);
+
+        // En su deadline (5) recupera el presupuesto y el deadline pasa a 10
+        while edf.now() < 5 {
+            edf.on_tick();
+        }
+        edf.enqueue(1, &aperiodic);
+        assert_eq!(edf.pick_next(), Some(1));
+        let s = edf.server(server).unwrap();
+        assert_eq!((s.remaining(), s.deadline(), s.is_throttled()), (1, 10, false));
+        assert_eq!(edf.len(), 0);
+    }
+
+    // Un tick por despacho: libera los trabajos periódicos, corre una unidad del que escoge el
+    // scheduler y devuelve cuántos deadlines periódicos se perdieron y cuántos ticks les tocaron a
+    // los aperiódicos, que siempre tienen trabajo.
+    fn run_periodic_with_flood(edf: &mut RealTimeScheduler, server: Option<crate::scheduler::ServerId>, ticks: usize) -> (usize, usize) {
+        let periodic = [(10, 3), (20, 6)];
+        let mut jobs: HashMap<ThreadId, (MyThread, usize)> = HashMap::new();
+        let mut flood = Vec::new();
+        for tid in 1..=5 {
+            // Sin servidor se comportan como el dispatcher de thread-city: deadline 0
+            let mut t = make_thread(tid, 0, 0, SchedulerType::RealTime);
+            t.attr.set_server(server);
+            edf.enqueue(tid, &t);
+            flood.push(t);
+        }
+        let (mut misses, mut aperiodic) = (0, 0);
+        let mut next_job: ThreadId = 100;
+        for now in 0..ticks {
+            for &(period, wcet) in &periodic {
+                if now % period == 0 {
+                    next_job += 1;
+                    let job = make_thread(next_job, 0, now + period, SchedulerType::RealTime);
+                    edf.enqueue(next_job, &job);
+                    jobs.insert(next_job, (job, wcet));
+                }
+            }
+            match edf.pick_next() {
+                Some(tid) if tid <= 5 => {
+                    aperiodic += 1;
+                    edf.enqueue(tid, &flood[tid as usize - 1]);
+                }
+                Some(tid) => {
+                    let (thread, left) = jobs.get_mut(&tid).unwrap();
+                    *left -= 1;
+                    if *left > 0 {
+                        edf.enqueue(tid, thread);
+                    } else {
+                        misses += usize::from(now + 1 > thread.attr.dead_line);
+                        jobs.remove(&tid);
+                    }
+                }
+                None => {}
+            }
+            edf.on_tick();
+        }
+        // Los que siguen pendientes con el deadline vencido también cuentan
+        misses += jobs.values().filter(|(t, _)| t.attr.dead_line < ticks).count();
+        (misses, aperiodic)
+    }
+
+    #[test]
+    fn periodic_deadlines_hold_under_aperiodic_flood() {
+        let tasks = [PeriodicTask::new(10, 3), PeriodicTask::new(20, 6)];
+
+        let mut edf = RealTimeScheduler::new();
+        let server = edf.create_server(3, 10).unwrap();
+        assert!(edf.admits(&tasks));
+        let (misses, aperiodic) = run_periodic_with_flood(&mut edf, Some(server), 200);
+        assert_eq!(misses, 0);
+        // Su 30% más lo que dejan libre los periódicos (10%)
+        assert!(aperiodic >= 60, "{aperiodic}");
+
+        // Con deadline 0 y sin servidor los aperiódicos se quedan con todo el CPU
+        let mut plain = RealTimeScheduler::new();
+        let (misses, _) = run_periodic_with_flood(&mut plain, None, 200);
+        assert!(misses > 0);
+
+        // Un servidor que se come lo que necesitan los periódicos no pasa la prueba
+        let mut greedy = RealTimeScheduler::new();
+        greedy.create_server(5, 10).unwrap();
+        assert!(!greedy.admits(&tasks));
+    }
 }
@@ -1,6 +1,7 @@
 mod tests;
 
 use std::collections::VecDeque;
+use std::any::Any;
 use crate::mythread::mythread::{MyThread, ThreadId};
 use crate::Scheduler;
 
@@ -21,7 +22,13 @@ impl Scheduler for RRScheduler {
     fn pick_next(&mut self) -> Option<ThreadId> {
         self.q.pop_front()
     }
-    fn is_empty(&self) -> bool { self.q.is_empty() }
+    fn remove(&mut self, tid: ThreadId) -> bool {
+        let Some(i) = self.q.iter().position(|&t| t == tid) else { return false };
+        self.q.remove(i);
+        true
+    }
+    fn as_any(&mut self) -> &mut dyn Any { self }
+    fn len(&self) -> usize { self.q.len() }
 }
 
 
@@ -8,7 +8,7 @@ mod tests {
     use crate::Scheduler;
     use crate::scheduler::round_robin::RRScheduler;
 
-    extern "C" fn dummy(_arg: *mut AnyParam) -> *mut c_void {
+    extern "C-unwind" fn dummy(_arg: *mut AnyParam) -> *mut c_void {
         std::ptr::null_mut()
     }
 
@@ -17,19 +17,17 @@ mod tests {
         priority: u8,
         deadline: usize,
         sched: SchedulerType,
-    ) -> (MyThread, Box<MyThreadAttr>) {
-        let mut attr = Box::new(MyThreadAttr::new(deadline, priority));
-        let t = MyThread::new(id, &mut *attr, dummy, std::ptr::null_mut(), Some(sched));
-        (t, attr)
+    ) -> MyThread {
+        MyThread::new(id, MyThreadAttr::new(deadline, priority), dummy, std::ptr::null_mut(), Some(sched))
     }
 
     #[test]
     fn rr_basic_fifo() {
         let mut rr = RRScheduler::new();
 
-        let (t1, _a1) = make_thread(1, 10, usize::MAX, SchedulerType::RoundRobin);
-        let (t2, _a2) = make_thread(2, 20, usize::MAX, SchedulerType::RoundRobin);
-        let (t3, _a3) = make_thread(3, 30, usize::MAX, SchedulerType::RoundRobin);
+        let t1 = make_thread(1, 10, usize::MAX, SchedulerType::RoundRobin);
+        let t2 = make_thread(2, 20, usize::MAX, SchedulerType::RoundRobin);
+        let t3 = make_thread(3, 30, usize::MAX, SchedulerType::RoundRobin);
 
         rr.enqueue(1, &t1);
         rr.enqueue(2, &t2);
@@ -45,8 +43,8 @@ mod tests {
     #[test]
     fn rr_cycle_with_reenqueue() {
         let mut rr = RRScheduler::new();
-        let (t1, _a1) = make_thread(1, 10, usize::MAX, SchedulerType::RoundRobin);
-        let (t2, _a2) = make_thread(2, 10, usize::MAX, SchedulerType::RoundRobin);
+        let t1 = make_thread(1, 10, usize::MAX, SchedulerType::RoundRobin);
+        let t2 = make_thread(2, 10, usize::MAX, SchedulerType::RoundRobin);
 
         rr.enqueue(1, &t1);
         rr.enqueue(2, &t2);
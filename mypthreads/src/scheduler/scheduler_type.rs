@@ -1,4 +1,7 @@
 
+// Índice de un scheduler registrado en el runtime (ver MyTRuntime::register_scheduler)
+pub type SchedulerId = u16;
+
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 
@@ -6,9 +9,23 @@ pub enum SchedulerType {
     RoundRobin,
     Lottery,
     RealTime,
+    // Prioridad fija: 256 niveles, FIFO dentro de cada nivel
+    FixedPriority,
+    // Implementación propia registrada en el runtime
+    Custom(SchedulerId),
 }
 
 
 impl Default for SchedulerType {
     fn default() -> Self { SchedulerType::RoundRobin }
 }
+
+impl SchedulerType {
+    // Schedulers que trae el runtime, con el nombre con que se registran
+    pub const BUILTIN: [(&'static str, SchedulerType); 4] = [
+        ("round-robin", SchedulerType::RoundRobin),
+        ("lottery", SchedulerType::Lottery),
+        ("real-time", SchedulerType::RealTime),
+        ("fixed-priority", SchedulerType::FixedPriority),
+    ];
+}
@@ -0,0 +1,306 @@
+mod tests;
+pub mod taskset;
+
+pub use taskset::{load_task_set, parse_csv, parse_toml, SimTask};
+
+use std::fmt::Write as _;
+use crate::mythread::mythread::{AnyParam, MyThread, ThreadId};
+use crate::mythread::mythreadattr::MyThreadAttr;
+use crate::scheduler::lottery::LotteryScheduler;
+use crate::scheduler::{new_scheduler, Scheduler, SchedulerType};
+
+// Simulador fuera de línea: corre un conjunto de tareas sobre un Scheduler en tiempo virtual, sin
+// hilos ni código de verdad. Igual que el runtime es cooperativo: el hilo escogido corre hasta
+// `quantum` ticks, hasta su siguiente bloqueo o hasta terminar, y lo que llegó mientras tanto queda
+// en la cola antes que él.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Pending,
+    Ready,
+    Blocked { until: usize },
+    Done,
+}
+
+// Resultado de una tarea. Los tiempos son ticks absolutos.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskMetrics {
+    pub name: String,
+    pub arrival: usize,
+    pub burst: usize,
+    // Primer tick en que corrió
+    pub start: Option<usize>,
+    // Tick en que terminó (None si el scheduler nunca la terminó de correr)
+    pub finish: Option<usize>,
+    // Ticks que pasó bloqueada
+    pub blocked: usize,
+    pub deadline: Option<usize>,
+}
+
+impl TaskMetrics {
+    pub fn response(&self) -> Option<usize> {
+        self.start.map(|s| s - self.arrival)
+    }
+
+    pub fn turnaround(&self) -> Option<usize> {
+        self.finish.map(|f| f - self.arrival)
+    }
+
+    // Ticks lista en la cola sin correr
+    pub fn waiting(&self) -> Option<usize> {
+        self.turnaround().map(|t| t - self.burst - self.blocked)
+    }
+
+    // None si no tiene deadline
+    pub fn met_deadline(&self) -> Option<bool> {
+        self.deadline.map(|d| self.finish.is_some_and(|f| f <= d))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SimReport {
+    pub tasks: Vec<TaskMetrics>,
+    // Qué tarea corrió en cada tick (None: CPU ociosa)
+    pub timeline: Vec<Option<usize>>,
+    // Intervalos [desde, hasta) bloqueada, por tarea
+    pub blocked: Vec<Vec<(usize, usize)>>,
+    pub dispatches: usize,
+    pub context_switches: usize,
+}
+
+// Rutina de los hilos simulados; nunca se llama
+extern "C-unwind" fn sim_routine(_: *mut AnyParam) -> *mut AnyParam {
+    std::ptr::null_mut()
+}
+
+// Scheduler del runtime por su nombre (ver SchedulerType::BUILTIN). Lottery usa `seed`.
+pub fn builtin_scheduler(name: &str, seed: u64) -> Option<(SchedulerType, Box<dyn Scheduler>)> {
+    let &(_, kind) = SchedulerType::BUILTIN.iter().find(|(n, _)| *n == name)?;
+    let scheduler: Box<dyn Scheduler> = match kind {
+        SchedulerType::Lottery => Box::new(LotteryScheduler::with_seed(seed)),
+        _ => new_scheduler(kind),
+    };
+    Some((kind, scheduler))
+}
+
+// Simula `tasks` sobre `scheduler`. `kind` es el tipo con que se crean los hilos: con Lottery el
+// nivel de prioridad que ve el scheduler son los tickets de la tarea.
+pub fn simulate(tasks: &[SimTask], scheduler: &mut dyn Scheduler, kind: SchedulerType, quantum: usize) -> SimReport {
+    let quantum = quantum.max(1);
+    let threads: Vec<MyThread> = tasks
+        .iter()
+        .enumerate()
+        .map(|(i, task)| {
+            let level = if kind == SchedulerType::Lottery { task.tickets } else { task.priority };
+            let attr = MyThreadAttr::new(task.absolute_deadline().unwrap_or(usize::MAX), level);
+            MyThread::new(i as ThreadId, attr, sim_routine, std::ptr::null_mut(), Some(kind))
+        })
+        .collect();
+
+    let mut phase = vec![Phase::Pending; tasks.len()];
+    let mut done = vec![0usize; tasks.len()];
+    let mut report = SimReport {
+        tasks: tasks
+            .iter()
+            .map(|t| TaskMetrics { name: t.name.clone(), arrival: t.arrival, burst: t.burst, start: None, finish: None, blocked: 0, deadline: t.absolute_deadline() })
+            .collect(),
+        timeline: Vec::new(),
+        blocked: vec![Vec::new(); tasks.len()],
+        dispatches: 0,
+        context_switches: 0,
+    };
+
+    let mut now = 0;
+    let mut last = None;
+    // El que cedió el turno vuelve a la cola detrás de lo que llegó mientras corría
+    let mut yielded = None;
+    loop {
+        // Llegadas y desbloqueos hasta ahora, en el orden en que pasaron
+        let mut due: Vec<(usize, usize)> = phase
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| match *p {
+                Phase::Pending if tasks[i].arrival <= now => Some((tasks[i].arrival, i)),
+                Phase::Blocked { until } if until <= now => Some((until, i)),
+                _ => None,
+            })
+            .collect();
+        due.sort_unstable();
+        for (_, i) in due {
+            if matches!(phase[i], Phase::Blocked { .. }) {
+                scheduler.on_wake(i as ThreadId);
+            }
+            phase[i] = Phase::Ready;
+            scheduler.enqueue(i as ThreadId, &threads[i]);
+        }
+        if let Some(i) = yielded.take() {
+            scheduler.enqueue(i as ThreadId, &threads[i]);
+        }
+        if phase.iter().all(|p| *p == Phase::Done) {
+            break;
+        }
+
+        scheduler.on_tick();
+        let picked = scheduler.pick_next().map(|tid| tid as usize);
+        let Some(i) = picked.filter(|&i| phase.get(i) == Some(&Phase::Ready)) else {
+            if picked.is_some() {
+                // Un scheduler propio devolvió algo que no estaba listo: se ignora
+                continue;
+            }
+            // Nada listo: el reloj salta al siguiente evento
+            let next = phase
+                .iter()
+                .enumerate()
+                .filter_map(|(i, p)| match *p {
+                    Phase::Pending => Some(tasks[i].arrival),
+                    Phase::Blocked { until } => Some(until),
+                    _ => None,
+                })
+                .min();
+            match next {
+                Some(next) => {
+                    report.timeline.resize(next, None);
+                    now = next;
+                    continue;
+                }
+                // Quedan listas que el scheduler perdió
+                None => break,
+            }
+        };
+
+        report.dispatches += 1;
+        if last.is_some_and(|l| l != i) {
+            report.context_switches += 1;
+        }
+        last = Some(i);
+        let task = &tasks[i];
+        let metrics = &mut report.tasks[i];
+        metrics.start.get_or_insert(now);
+
+        let next_block = task.blocks.iter().find(|&&(at, _)| at > done[i]);
+        let until = next_block.map_or(task.burst, |&(at, _)| at);
+        let slice = quantum.min(until - done[i]);
+        report.timeline.extend(std::iter::repeat_n(Some(i), slice));
+        now += slice;
+        done[i] += slice;
+
+        if done[i] == task.burst {
+            phase[i] = Phase::Done;
+            metrics.finish = Some(now);
+            scheduler.on_exit(i as ThreadId);
+        } else if let Some(&(_, ticks)) = next_block.filter(|&&(at, _)| at == done[i]) {
+            phase[i] = Phase::Blocked { until: now + ticks };
+            metrics.blocked += ticks;
+            report.blocked[i].push((now, now + ticks));
+            scheduler.on_block(i as ThreadId);
+        } else {
+            yielded = Some(i);
+        }
+    }
+    report
+}
+
+impl SimReport {
+    pub fn makespan(&self) -> usize {
+        self.timeline.len()
+    }
+
+    // Fracción de ticks con alguna tarea corriendo
+    pub fn utilization(&self) -> f64 {
+        let busy = self.timeline.iter().filter(|t| t.is_some()).count();
+        busy as f64 / self.makespan().max(1) as f64
+    }
+
+    pub fn deadline_misses(&self) -> usize {
+        self.tasks.iter().filter(|t| t.met_deadline() == Some(false)).count()
+    }
+
+    fn average(&self, metric: impl Fn(&TaskMetrics) -> Option<usize>) -> f64 {
+        let values: Vec<usize> = self.tasks.iter().filter_map(metric).collect();
+        values.iter().sum::<usize>() as f64 / values.len().max(1) as f64
+    }
+
+    pub fn avg_response(&self) -> f64 {
+        self.average(TaskMetrics::response)
+    }
+
+    pub fn avg_turnaround(&self) -> f64 {
+        self.average(TaskMetrics::turnaround)
+    }
+
+    pub fn avg_waiting(&self) -> f64 {
+        self.average(TaskMetrics::waiting)
+    }
+
+    // Símbolo de la tarea `i` en los ticks [from, to): corre (#), bloqueada (-), lista (.) o nada.
+    fn cell(&self, i: usize, from: usize, to: usize) -> char {
+        let task = &self.tasks[i];
+        if self.timeline[from..to].contains(&Some(i)) {
+            '#'
+        } else if self.blocked[i].iter().any(|&(s, e)| s < to && from < e) {
+            '-'
+        } else if task.arrival < to && task.finish.is_none_or(|f| from < f) {
+            '.'
+        } else {
+            ' '
+        }
+    }
+
+    // Diagrama de Gantt en texto, una fila por tarea y a lo sumo `width` columnas. Si la corrida
+    // es más larga cada columna junta varios ticks.
+    pub fn gantt(&self, width: usize) -> String {
+        let ticks = self.makespan();
+        let scale = ticks.div_ceil(width.max(1)).max(1);
+        let columns = ticks.div_ceil(scale);
+        let label = self.tasks.iter().map(|t| t.name.chars().count()).max().unwrap_or(0);
+
+        let mut out = String::new();
+        for i in 0..self.tasks.len() {
+            let row: String = (0..columns).map(|c| self.cell(i, c * scale, ((c + 1) * scale).min(ticks))).collect();
+            let _ = writeln!(out, "{:>label$} |{row}|", self.tasks[i].name);
+        }
+        // Eje: una marca cada 10 columnas con el tick donde empieza
+        let mut axis = String::new();
+        for c in (0..columns).step_by(10) {
+            let mark = (c * scale).to_string();
+            if axis.len() <= c {
+                axis.push_str(&" ".repeat(c - axis.len()));
+                axis.push_str(&mark);
+            }
+        }
+        let _ = writeln!(out, "{:>label$}  {axis}", "");
+        let _ = writeln!(out, "# corre  - bloqueada  . lista   (1 columna = {scale} tick{})", if scale == 1 { "" } else { "s" });
+        out
+    }
+}
+
+fn or_dash(value: Option<usize>) -> String {
+    value.map_or("-".to_string(), |v| v.to_string())
+}
+
+// Tabla de métricas por tarea y resumen de la corrida.
+pub fn print_report(title: &str, report: &SimReport) {
+    println!("== {title}");
+    println!("{:<12} {:>7} {:>5} {:>5} {:>6} {:>8} {:>10} {:>7} {:>8}", "task", "arrival", "burst", "start", "finish", "response", "turnaround", "waiting", "deadline");
+    for t in &report.tasks {
+        let deadline = match t.met_deadline() {
+            Some(true) => "ok",
+            Some(false) => "MISS",
+            None => "-",
+        };
+        println!(
+            "{:<12} {:>7} {:>5} {:>5} {:>6} {:>8} {:>10} {:>7} {:>8}",
+            t.name, t.arrival, t.burst, or_dash(t.start), or_dash(t.finish), or_dash(t.response()), or_dash(t.turnaround()), or_dash(t.waiting()), deadline
+        );
+    }
+    println!(
+        "makespan {}  cpu {:.1}%  response {:.2}  turnaround {:.2}  waiting {:.2}  deadline misses {}  context switches {}",
+        report.makespan(),
+        report.utilization() * 100.0,
+        report.avg_response(),
+        report.avg_turnaround(),
+        report.avg_waiting(),
+        report.deadline_misses(),
+        report.context_switches
+    );
+}
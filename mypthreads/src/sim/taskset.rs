@@ -0,0 +1,172 @@
+use std::io;
+use std::path::Path;
+use crate::mythread::mythreadattr::PriorityLevel;
+
+// Una tarea del archivo. Los tiempos van en ticks de reloj virtual; cada tick es una unidad de
+// trabajo, igual que en el bench.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimTask {
+    pub name: String,
+    pub arrival: usize,
+    pub burst: usize,
+    // (at, ticks): después de correr `at` unidades se bloquea `ticks` (E/S, un lock, etc.)
+    pub blocks: Vec<(usize, usize)>,
+    // Tickets en Lottery
+    pub tickets: PriorityLevel,
+    // Nivel en FixedPriority
+    pub priority: PriorityLevel,
+    // Relativo a la llegada; sin deadline va al final en RealTime
+    pub deadline: Option<usize>,
+}
+
+impl SimTask {
+    pub fn new(name: &str, arrival: usize, burst: usize) -> Self {
+        Self { name: name.to_string(), arrival, burst, blocks: Vec::new(), tickets: 1, priority: 0, deadline: None }
+    }
+
+    // Deadline absoluto (en el tick en que debe haber terminado)
+    pub fn absolute_deadline(&self) -> Option<usize> {
+        self.deadline.map(|d| self.arrival + d)
+    }
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("{key} inválido: {value:?}"))
+}
+
+// Los bloqueos se escriben "3:5;7:2" en CSV y [[3, 5], [7, 2]] en TOML: en los dos casos son los
+// números en orden, de dos en dos.
+fn parse_blocks(value: &str) -> Result<Vec<(usize, usize)>, String> {
+    let numbers: Vec<usize> = value
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|s| !s.is_empty())
+        .map(|s| number("blocks", s))
+        .collect::<Result<_, _>>()?;
+    if !numbers.len().is_multiple_of(2) {
+        return Err(format!("blocks incompleto: {value:?}"));
+    }
+    Ok(numbers.chunks(2).map(|pair| (pair[0], pair[1])).collect())
+}
+
+fn set_field(task: &mut SimTask, key: &str, value: &str) -> Result<(), String> {
+    match key {
+        "name" => task.name = value.to_string(),
+        "arrival" => task.arrival = number(key, value)?,
+        "burst" => task.burst = number(key, value)?,
+        "tickets" => task.tickets = number(key, value)?,
+        "priority" => task.priority = number(key, value)?,
+        "deadline" => task.deadline = Some(number(key, value)?),
+        "blocks" => task.blocks = parse_blocks(value)?,
+        _ => return Err(format!("campo desconocido: {key}")),
+    }
+    Ok(())
+}
+
+// Revisa lo que no se puede simular y pone nombre a las tareas que no traen.
+fn check(tasks: &mut [SimTask]) -> io::Result<()> {
+    for (i, task) in tasks.iter_mut().enumerate() {
+        if task.name.is_empty() {
+            task.name = format!("t{i}");
+        }
+        if task.burst == 0 {
+            return Err(invalid(format!("{}: burst tiene que ser mayor que 0", task.name)));
+        }
+        if task.tickets == 0 {
+            return Err(invalid(format!("{}: necesita al menos un ticket", task.name)));
+        }
+        // Cada bloqueo cae entre dos unidades de trabajo, en orden
+        let mut last = 0;
+        for &(at, _) in &task.blocks {
+            if at <= last || at >= task.burst {
+                return Err(invalid(format!("{}: bloqueo en {at} fuera de 1..{} o desordenado", task.name, task.burst)));
+            }
+            last = at;
+        }
+    }
+    Ok(())
+}
+
+// CSV con encabezado; las columnas van en cualquier orden y solo name, arrival y burst son
+// obligatorias. Una celda vacía deja el valor por defecto.
+//   name,arrival,burst,tickets,priority,deadline,blocks
+//   sensor,0,6,5,10,20,2:4
+pub fn parse_csv(text: &str) -> io::Result<Vec<SimTask>> {
+    let bad = |n: usize, msg: String| invalid(format!("línea {}: {msg}", n + 1));
+    let mut lines = text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty() && !l.trim_start().starts_with('#'));
+    let (_, header) = lines.next().ok_or_else(|| invalid("falta el encabezado".to_string()))?;
+    let columns: Vec<&str> = header.split(',').map(str::trim).collect();
+    for required in ["name", "arrival", "burst"] {
+        if !columns.contains(&required) {
+            return Err(invalid(format!("falta la columna {required}")));
+        }
+    }
+
+    let mut tasks = Vec::new();
+    for (n, line) in lines {
+        let cells: Vec<&str> = line.split(',').map(str::trim).collect();
+        if cells.len() > columns.len() {
+            return Err(bad(n, format!("{} columnas, el encabezado tiene {}", cells.len(), columns.len())));
+        }
+        let mut task = SimTask::new("", 0, 0);
+        for (key, value) in columns.iter().zip(&cells) {
+            if !value.is_empty() {
+                set_field(&mut task, key, value).map_err(|e| bad(n, e))?;
+            }
+        }
+        tasks.push(task);
+    }
+    check(&mut tasks)?;
+    Ok(tasks)
+}
+
+// El pedazo de TOML que hace falta: una tabla [[task]] por tarea con `clave = valor`, donde el
+// valor es un entero, un string entre comillas o la lista de bloqueos.
+//   [[task]]
+//   name = "sensor"
+//   burst = 6
+//   blocks = [[2, 4]]
+pub fn parse_toml(text: &str) -> io::Result<Vec<SimTask>> {
+    let bad = |n: usize, msg: String| invalid(format!("línea {}: {msg}", n + 1));
+    let mut tasks: Vec<SimTask> = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        // Comentario desde el primer # que no esté dentro de un string
+        let mut quoted = false;
+        let end = line
+            .char_indices()
+            .find(|&(_, c)| {
+                quoted ^= c == '"';
+                c == '#' && !quoted
+            })
+            .map_or(line.len(), |(i, _)| i);
+        let line = line[..end].trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line == "[[task]]" {
+            tasks.push(SimTask::new("", 0, 0));
+            continue;
+        }
+        let (key, value) = line.split_once('=').ok_or_else(|| bad(n, format!("se esperaba clave = valor: {line:?}")))?;
+        let task = tasks.last_mut().ok_or_else(|| bad(n, "clave antes del primer [[task]]".to_string()))?;
+        let value = value.trim();
+        let value = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value);
+        set_field(task, key.trim(), value).map_err(|e| bad(n, e))?;
+    }
+    check(&mut tasks)?;
+    Ok(tasks)
+}
+
+// Escoge el formato por la extensión (.toml o .csv).
+pub fn load_task_set(path: impl AsRef<Path>) -> io::Result<Vec<SimTask>> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)?;
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("toml") => parse_toml(&text),
+        Some("csv") => parse_csv(&text),
+        _ => Err(invalid(format!("{}: se esperaba un .toml o .csv", path.display()))),
+    }
+}
@@ -0,0 +1,163 @@
+#[cfg(test)]
+mod tests {
+    use std::any::Any;
+    use std::collections::VecDeque;
+    use crate::mythread::mythread::{MyThread, ThreadId};
+    use crate::scheduler::{Scheduler, SchedulerType};
+    use crate::sim::{builtin_scheduler, parse_csv, parse_toml, simulate, SimTask};
+
+    fn run(tasks: &[SimTask], name: &str, quantum: usize) -> crate::sim::SimReport {
+        let (kind, mut scheduler) = builtin_scheduler(name, 7).unwrap();
+        simulate(tasks, scheduler.as_mut(), kind, quantum)
+    }
+
+    #[test]
+    fn csv_and_toml_describe_the_same_task_set() {
+        let csv = "\
+# tareas de ejemplo
+name,arrival,burst,tickets,priority,deadline,blocks
+sensor,0,6,5,10,20,2:4;4:1
+logger,3,2,,,,
+";
+        let toml = r#"
+[[task]]
+name = "sensor"   # lee el puerto
+burst = 6
+tickets = 5
+priority = 10
+deadline = 20
+blocks = [[2, 4], [4, 1]]
+
+[[task]]
+name = "logger"
+arrival = 3
+burst = 2
+"#;
+        let from_csv = parse_csv(csv).unwrap();
+        assert_eq!(from_csv, parse_toml(toml).unwrap());
+        assert_eq!(from_csv[0].blocks, vec![(2, 4), (4, 1)]);
+        assert_eq!(from_csv[0].absolute_deadline(), Some(20));
+        assert_eq!(from_csv[1].deadline, None);
+    }
+
+    #[test]
+    fn malformed_task_sets_report_the_line() {
+        let err = parse_csv("name,arrival,burst\na,0,zero\n").unwrap_err();
+        assert!(err.to_string().contains("línea 2"), "{err}");
+        assert!(parse_csv("name,burst\na,3\n").is_err());
+        assert!(parse_toml("burst = 3\n").is_err());
+        // El bloqueo tiene que caer antes de terminar
+        assert!(parse_toml("[[task]]\nburst = 3\nblocks = [[3, 1]]\n").is_err());
+    }
+
+    #[test]
+    fn round_robin_interleaves_and_metrics_add_up() {
+        let tasks = vec![SimTask::new("a", 0, 3), SimTask::new("b", 0, 2)];
+        let report = run(&tasks, "round-robin", 1);
+        assert_eq!(report.timeline, vec![Some(0), Some(1), Some(0), Some(1), Some(0)]);
+        let (a, b) = (&report.tasks[0], &report.tasks[1]);
+        assert_eq!((a.finish, b.finish), (Some(5), Some(4)));
+        assert_eq!((a.waiting(), b.waiting()), (Some(2), Some(2)));
+        assert_eq!(b.response(), Some(1));
+        assert_eq!(report.context_switches, 4);
+
+        // Con quantum grande cada una corre de un tirón
+        let report = run(&tasks, "round-robin", 10);
+        assert_eq!(report.timeline, vec![Some(0), Some(0), Some(0), Some(1), Some(1)]);
+    }
+
+    #[test]
+    fn blocked_task_gives_up_the_cpu_until_it_wakes() {
+        let mut io = SimTask::new("io", 0, 2);
+        io.blocks = vec![(1, 3)];
+        let tasks = vec![io, SimTask::new("cpu", 0, 2)];
+        let report = run(&tasks, "round-robin", 1);
+        // io corre, se bloquea 3 ticks; cpu termina y la CPU queda ociosa hasta que io despierta
+        assert_eq!(report.timeline, vec![Some(0), Some(1), Some(1), None, Some(0)]);
+        assert_eq!(report.tasks[0].blocked, 3);
+        assert_eq!(report.tasks[0].waiting(), Some(0));
+        assert_eq!(report.blocked[0], vec![(1, 4)]);
+        assert!((report.utilization() - 0.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn edf_meets_deadlines_that_round_robin_misses() {
+        let mut urgent = SimTask::new("urgent", 1, 3);
+        urgent.deadline = Some(4);
+        let mut relaxed = SimTask::new("relaxed", 0, 6);
+        relaxed.deadline = Some(20);
+        let tasks = vec![relaxed, urgent];
+        assert_eq!(run(&tasks, "real-time", 1).deadline_misses(), 0);
+        assert_eq!(run(&tasks, "round-robin", 1).deadline_misses(), 1);
+    }
+
+    #[test]
+    fn fixed_priority_and_lottery_use_their_own_field() {
+        let mut low = SimTask::new("low", 0, 3);
+        low.tickets = 200;
+        let mut high = SimTask::new("high", 0, 3);
+        high.priority = 9;
+        let tasks = vec![low, high];
+        let fp = run(&tasks, "fixed-priority", 1);
+        assert_eq!(fp.tasks[1].finish, Some(3));
+        // 200 tickets contra 1: low casi siempre gana el sorteo
+        let lottery = run(&tasks, "lottery", 1);
+        assert!(lottery.tasks[0].finish < lottery.tasks[1].finish, "{:?}", lottery.timeline);
+    }
+
+    // Pila: el último en llegar corre primero. Cuenta los eventos que le llegan.
+    #[derive(Default)]
+    struct Lifo {
+        stack: VecDeque<ThreadId>,
+        events: Vec<&'static str>,
+    }
+
+    impl Scheduler for Lifo {
+        fn enqueue(&mut self, tid: ThreadId, _t: &MyThread) {
+            self.stack.push_back(tid);
+        }
+        fn pick_next(&mut self) -> Option<ThreadId> {
+            self.stack.pop_back()
+        }
+        fn remove(&mut self, tid: ThreadId) -> bool {
+            let before = self.stack.len();
+            self.stack.retain(|&t| t != tid);
+            before != self.stack.len()
+        }
+        fn on_block(&mut self, _tid: ThreadId) {
+            self.events.push("block");
+        }
+        fn on_wake(&mut self, _tid: ThreadId) {
+            self.events.push("wake");
+        }
+        fn on_exit(&mut self, _tid: ThreadId) {
+            self.events.push("exit");
+        }
+        fn as_any(&mut self) -> &mut dyn Any {
+            self
+        }
+        fn len(&self) -> usize {
+            self.stack.len()
+        }
+    }
+
+    #[test]
+    fn custom_scheduler_gets_events_and_gantt_shows_states() {
+        let mut io = SimTask::new("io", 0, 2);
+        io.blocks = vec![(1, 2)];
+        let tasks = vec![io, SimTask::new("late", 2, 1)];
+        let mut lifo = Lifo::default();
+        let report = simulate(&tasks, &mut lifo, SchedulerType::Custom(0), 1);
+        assert_eq!(lifo.events, vec!["block", "exit", "wake", "exit"]);
+        // late llega en 2 y corre mientras io sigue bloqueada
+        assert_eq!(report.timeline, vec![Some(0), None, Some(1), Some(0)]);
+
+        let gantt = report.gantt(80);
+        let rows: Vec<&str> = gantt.lines().collect();
+        assert_eq!(rows[0], "  io |#--#|");
+        assert_eq!(rows[1], "late |  # |");
+
+        // Más ticks que columnas: cada columna junta dos
+        assert_eq!(report.gantt(2).lines().next(), Some("  io |##|"));
+    }
+}
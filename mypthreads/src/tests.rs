@@ -5,12 +5,10 @@ mod tests {
     use crate::mythread::mythreadattr::MyThreadAttr;
     use crate::mythread::thread_state::ThreadState;
     use std::ptr;
-    use libc::c_int;
     use crate::mythread::mymutex::MyMutex;
     use crate::scheduler::{SchedulerType};
-    use crate::mythread::mypthreadexits::Exits;
 
-    extern "C" fn test_thread_function(arg: *mut AnyParam) -> *mut AnyParam {
+    extern "C-unwind" fn test_thread_function(arg: *mut AnyParam) -> *mut AnyParam {
         unsafe {
             let value = arg as *mut i32;
             *value += 10;
@@ -18,7 +16,7 @@ mod tests {
         }
     }
 
-    extern "C" fn test_thread_returns_static(arg: *mut AnyParam) -> *mut AnyParam {
+    extern "C-unwind" fn test_thread_returns_static(arg: *mut AnyParam) -> *mut AnyParam {
         println!("Ejecutando hilo con argumento: {:?}", arg);
         let static_value: &'static mut i32 = Box::leak(Box::new(42));
         static_value as *mut i32 as *mut AnyParam
@@ -33,7 +31,7 @@ mod tests {
         use crate::mythread::mythreadattr::MyThreadAttr;
         use crate::scheduler::scheduler_type::SchedulerType;
 
-        extern "C" fn test_thread_returns_static(_arg: *mut AnyParam) -> *mut AnyParam {
+        extern "C-unwind" fn test_thread_returns_static(_arg: *mut AnyParam) -> *mut AnyParam {
             let static_value: &'static mut i32 = Box::leak(Box::new(42));
             static_value as *mut i32 as *mut AnyParam
         }
@@ -73,6 +71,103 @@ mod tests {
             }
         }
     }
+    #[cfg(test)]
+    mod tests_sched_registry {
+        use std::any::Any;
+        use std::cell::{Cell, RefCell};
+        use std::ptr;
+        use std::rc::Rc;
+        use crate::mythread::mypthread::MyPThread;
+        use crate::mythread::mythread::{AnyParam, MyThread, ThreadId};
+        use crate::mythread::mythreadattr::MyThreadAttr;
+        use crate::scheduler::scheduler_type::SchedulerType;
+        use crate::Scheduler;
+
+        thread_local! {
+            static ORDER: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+        }
+
+        extern "C-unwind" fn record(arg: *mut AnyParam) -> *mut AnyParam {
+            ORDER.with(|o| o.borrow_mut().push(arg as usize));
+            ptr::null_mut()
+        }
+
+        // LIFO: el último en llegar corre primero. Cuenta los ticks que le avisa el runtime.
+        struct StackScheduler {
+            stack: Vec<ThreadId>,
+            ticks: Rc<Cell<usize>>,
+        }
+
+        impl Scheduler for StackScheduler {
+            fn enqueue(&mut self, tid: ThreadId, _t: &MyThread) {
+                self.stack.push(tid);
+            }
+            fn pick_next(&mut self) -> Option<ThreadId> {
+                self.stack.pop()
+            }
+            fn remove(&mut self, tid: ThreadId) -> bool {
+                let before = self.stack.len();
+                self.stack.retain(|&t| t != tid);
+                self.stack.len() != before
+            }
+            fn on_tick(&mut self) {
+                self.ticks.set(self.ticks.get() + 1);
+            }
+            fn as_any(&mut self) -> &mut dyn Any { self }
+            fn len(&self) -> usize { self.stack.len() }
+        }
+
+        unsafe fn create(pth: &mut MyPThread, arg: usize, kind: Option<SchedulerType>) -> (i32, ThreadId) {
+            let mut tid: ThreadId = 0;
+            let attr = MyThreadAttr::new(usize::MAX, 1);
+            let rc = unsafe { pth.my_thread_create(&mut tid, &attr, record, arg as *mut AnyParam, kind) };
+            (rc, tid)
+        }
+
+        #[test]
+        fn registered_scheduler_picks_its_threads() {
+            ORDER.with(|o| o.borrow_mut().clear());
+            let mut pth = MyPThread::new();
+            let ticks = Rc::new(Cell::new(0));
+            let stack = pth
+                .my_sched_register("stack", Box::new(StackScheduler { stack: Vec::new(), ticks: ticks.clone() }))
+                .unwrap();
+            assert_eq!(pth.runtime.scheduler_by_name("stack"), Some(stack));
+            assert_eq!(pth.runtime.scheduler_by_name("lottery"), Some(SchedulerType::Lottery));
+            let again = Box::new(StackScheduler { stack: Vec::new(), ticks: ticks.clone() });
+            assert!(pth.my_sched_register("stack", again).is_none(), "nombre repetido");
+
+            unsafe {
+                let (_, first) = create(&mut pth, 1, Some(stack));
+                create(&mut pth, 2, Some(stack));
+                create(&mut pth, 3, Some(stack));
+                assert_eq!(pth.my_thread_join(first, ptr::null_mut()), 0);
+            }
+            assert_eq!(ORDER.with(|o| o.borrow().clone()), [3, 2, 1]);
+            assert!(ticks.get() >= 3, "on_tick en cada despacho");
+        }
+
+        #[test]
+        fn chsched_moves_ready_thread_to_registered_scheduler() {
+            ORDER.with(|o| o.borrow_mut().clear());
+            let mut pth = MyPThread::new();
+            let stack = pth
+                .my_sched_register("stack", Box::new(StackScheduler { stack: Vec::new(), ticks: Rc::default() }))
+                .unwrap();
+
+            unsafe {
+                let (_, rr) = create(&mut pth, 1, None);
+                let (_, moved) = create(&mut pth, 2, None);
+                // Sale de la cola de RoundRobin: corre una sola vez, y antes que RoundRobin
+                assert_eq!(pth.my_thread_chsched(moved, stack), 0);
+                assert_eq!(pth.my_thread_chsched(moved, SchedulerType::Custom(7)), libc::EINVAL);
+                assert_eq!(create(&mut pth, 3, Some(SchedulerType::Custom(7))).0, libc::EINVAL);
+                assert_eq!(pth.my_thread_join(rr, ptr::null_mut()), 0);
+            }
+            assert_eq!(ORDER.with(|o| o.borrow().clone()), [2, 1]);
+        }
+    }
+
     #[cfg(test)]
     mod tests_change_sched_order {
         use std::ptr;
@@ -85,13 +180,13 @@ mod tests {
         // 0 = nadie ha corrido aún; 1 = corrió RT primero; 2 = corrió RR primero
         static FIRST_RAN: AtomicI32 = AtomicI32::new(0);
 
-        extern "C" fn mark_rt(_arg: *mut AnyParam) -> *mut AnyParam {
+        extern "C-unwind" fn mark_rt(_arg: *mut AnyParam) -> *mut AnyParam {
             // marca que el RT corrió primero si FIRST_RAN aún es 0
             FIRST_RAN.compare_exchange(0, 1, Ordering::SeqCst, Ordering::SeqCst).ok();
             std::ptr::null_mut()
         }
 
-        extern "C" fn mark_rr(_arg: *mut AnyParam) -> *mut AnyParam {
+        extern "C-unwind" fn mark_rr(_arg: *mut AnyParam) -> *mut AnyParam {
             // marca que el RR corrió primero si FIRST_RAN aún es 0
             FIRST_RAN.compare_exchange(0, 2, Ordering::SeqCst, Ordering::SeqCst).ok();
             std::ptr::null_mut()
@@ -154,6 +249,886 @@ mod tests {
         }
     }
 
+    #[cfg(test)]
+    mod tests_attr_ownership {
+        use std::ptr;
+        use libc::{c_int, EINVAL};
+        use crate::mythread::mypthread::MyPThread;
+        use crate::mythread::mythread::{AnyParam, ThreadId};
+        use crate::mythread::mythreadattr::{
+            my_attr_getdeadline, my_attr_getdetachstate, my_attr_getpriority, my_attr_setdeadline,
+            my_attr_setdetachstate, my_attr_setpriority, MyThreadAttr, PriorityLevel,
+            MY_THREAD_CREATE_DETACHED, MY_THREAD_CREATE_JOINABLE,
+        };
+        use crate::scheduler::SchedulerType;
+
+        extern "C-unwind" fn returns_arg(arg: *mut AnyParam) -> *mut AnyParam {
+            arg
+        }
+
+        #[test]
+        fn test_null_attr_uses_defaults() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                let mut tid: ThreadId = 0;
+
+                let r = pth.my_thread_create(&mut tid, ptr::null(), returns_arg, ptr::null_mut(), None);
+                assert_eq!(r, 0, "my_thread_create con attr nulo falló");
+
+                let t = pth.runtime.threads.get(&tid).unwrap();
+                assert!(!t.attr().is_detached());
+                assert_eq!(t.attr().priority(), 1);
+                assert_eq!(t.attr().dead_line(), usize::MAX);
+
+                let j = pth.my_thread_join(tid, ptr::null_mut());
+                assert_eq!(j, 0, "my_thread_join falló");
+            }
+        }
+
+        #[test]
+        fn test_attr_is_copied_at_create() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                let mut tid: ThreadId = 0;
+                {
+                    // El attr muere antes de que el hilo corra
+                    let mut attr = MyThreadAttr::new(25, 7);
+                    pth.my_thread_create(&mut tid, &attr, returns_arg, ptr::null_mut(), Some(SchedulerType::RealTime));
+                    attr.set_priority(99);
+                    attr.detach();
+                }
+
+                let t = pth.runtime.threads.get(&tid).unwrap();
+                assert_eq!(t.attr().priority(), 7, "cambiar el attr original no debe afectar al hilo");
+                assert_eq!(t.attr().dead_line(), 25);
+                assert!(!t.attr().is_detached());
+
+                assert_eq!(pth.my_thread_join(tid, ptr::null_mut()), 0);
+            }
+        }
+
+        #[test]
+        fn test_detached_attr_rejects_join() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                let mut tid: ThreadId = 0;
+                let mut attr = MyThreadAttr::default();
+                assert_eq!(my_attr_setdetachstate(&mut attr, MY_THREAD_CREATE_DETACHED), 0);
+
+                pth.my_thread_create(&mut tid, &attr, returns_arg, ptr::null_mut(), None);
+                assert_ne!(pth.my_thread_join(tid, ptr::null_mut()), 0, "join sobre detached debería fallar");
+            }
+        }
+
+        #[test]
+        fn test_attr_accessors() {
+            unsafe {
+                let mut attr = MyThreadAttr::default();
+
+                let mut state: c_int = -1;
+                assert_eq!(my_attr_getdetachstate(&attr, &mut state), 0);
+                assert_eq!(state, MY_THREAD_CREATE_JOINABLE);
+                assert_eq!(my_attr_setdetachstate(&mut attr, MY_THREAD_CREATE_DETACHED), 0);
+                assert_eq!(my_attr_getdetachstate(&attr, &mut state), 0);
+                assert_eq!(state, MY_THREAD_CREATE_DETACHED);
+                assert_eq!(my_attr_setdetachstate(&mut attr, 42), EINVAL);
+
+                let mut prio: PriorityLevel = 0;
+                assert_eq!(my_attr_setpriority(&mut attr, 12), 0);
+                assert_eq!(my_attr_getpriority(&attr, &mut prio), 0);
+                assert_eq!(prio, 12);
+
+                let mut dl: usize = 0;
+                assert_eq!(my_attr_setdeadline(&mut attr, 300), 0);
+                assert_eq!(my_attr_getdeadline(&attr, &mut dl), 0);
+                assert_eq!(dl, 300);
+
+                assert_eq!(my_attr_setpriority(ptr::null_mut(), 1), EINVAL);
+                assert_eq!(my_attr_getdeadline(ptr::null(), &mut dl), EINVAL);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests_signals {
+        use std::cell::RefCell;
+        use std::ptr;
+        use libc::c_int;
+        use crate::mythread::mypthread::MyPThread;
+        use crate::mythread::mysignal::my_sigbit;
+        use crate::mythread::mythread::{AnyParam, ThreadId};
+
+        thread_local! {
+            // Orden en que corrieron handlers y rutinas (los tests corren en hilos del SO distintos)
+            static LOG: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+        }
+
+        fn log(entry: String) {
+            LOG.with(|l| l.borrow_mut().push(entry));
+        }
+
+        fn take_log() -> Vec<String> {
+            LOG.with(|l| l.borrow_mut().drain(..).collect())
+        }
+
+        extern "C" fn on_signal(sig: c_int, tid: ThreadId) {
+            log(format!("signal {sig} en {tid}"));
+        }
+
+        extern "C-unwind" fn body(_: *mut AnyParam) -> *mut AnyParam {
+            log("rutina".to_string());
+            ptr::null_mut()
+        }
+
+        #[test]
+        fn test_kill_runs_handler_before_next_dispatch() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                assert_eq!(pth.my_signal(libc::SIGUSR1, on_signal), 0);
+
+                let mut tid: ThreadId = 0;
+                pth.my_thread_create(&mut tid, ptr::null(), body, ptr::null_mut(), None);
+                assert_eq!(pth.my_thread_kill(tid, libc::SIGUSR1), 0);
+                assert!(take_log().is_empty(), "el handler no debe correr antes de despachar el hilo");
+
+                pth.my_thread_join(tid, ptr::null_mut());
+                assert_eq!(take_log(), vec![format!("signal {} en {tid}", libc::SIGUSR1), "rutina".to_string()]);
+            }
+        }
+
+        #[test]
+        fn test_masked_signal_stays_pending() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                pth.my_signal(libc::SIGUSR1, on_signal);
+
+                let mut tid: ThreadId = 0;
+                pth.my_thread_create(&mut tid, ptr::null(), body, ptr::null_mut(), None);
+                pth.runtime.threads.get_mut(&tid).unwrap().sig_mask = my_sigbit(libc::SIGUSR1);
+                pth.my_thread_kill(tid, libc::SIGUSR1);
+
+                pth.my_thread_join(tid, ptr::null_mut());
+                assert_eq!(take_log(), vec!["rutina".to_string()]);
+                assert_eq!(pth.runtime.threads[&tid].sig_pending, my_sigbit(libc::SIGUSR1));
+            }
+        }
+
+        #[test]
+        fn test_kill_errors() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                let mut tid: ThreadId = 0;
+                pth.my_thread_create(&mut tid, ptr::null(), body, ptr::null_mut(), None);
+
+                assert_eq!(pth.my_thread_kill(tid, 0), 0);
+                assert_eq!(pth.my_thread_kill(tid, 65), libc::EINVAL);
+                assert_eq!(pth.my_thread_kill(999, libc::SIGUSR1), libc::ESRCH);
+                // Sin hilo actual no hay máscara que cambiar
+                assert_eq!(pth.my_thread_sigmask(libc::SIG_BLOCK, ptr::null(), ptr::null_mut()), libc::EPERM);
+
+                pth.my_thread_join(tid, ptr::null_mut());
+                take_log();
+                assert_eq!(pth.my_thread_kill(tid, libc::SIGUSR1), libc::ESRCH);
+            }
+        }
+
+        #[test]
+        fn test_process_signal_is_routed_to_thread() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                pth.my_signal(libc::SIGUSR2, on_signal);
+
+                let mut tid: ThreadId = 0;
+                pth.my_thread_create(&mut tid, ptr::null(), body, ptr::null_mut(), None);
+                assert_eq!(pth.my_signal_route(libc::SIGUSR2, tid), 0);
+                assert_eq!(pth.my_signal_route(libc::SIGKILL, tid), libc::EINVAL);
+
+                // La señal real del SO solo queda anotada; se reparte cuando el hilo se despacha
+                libc::raise(libc::SIGUSR2);
+                assert!(take_log().is_empty());
+
+                pth.my_thread_join(tid, ptr::null_mut());
+                assert_eq!(take_log(), vec![format!("signal {} en {tid}", libc::SIGUSR2), "rutina".to_string()]);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests_stack {
+        use std::ptr;
+        use libc::c_int;
+        use crate::mythread::mypthread::MyPThread;
+        use crate::mythread::myerror::MyError;
+        use crate::mythread::mystack::last_overflowed;
+        use crate::mythread::mythread::{AnyParam, ThreadId};
+        use crate::mythread::mythreadattr::{my_attr_getstacksize, my_attr_setstacksize, MyThreadAttr};
+
+        fn recurse(depth: usize) -> usize {
+            let frame = std::hint::black_box([depth as u8; 1024]);
+            if depth == usize::MAX {
+                return 0;
+            }
+            recurse(depth + 1) + frame[0] as usize
+        }
+
+        extern "C-unwind" fn overflow(_: *mut AnyParam) -> *mut AnyParam {
+            recurse(0) as *mut AnyParam
+        }
+
+        extern "C-unwind" fn returns_arg(arg: *mut AnyParam) -> *mut AnyParam {
+            arg
+        }
+
+        #[test]
+        fn test_stack_size_lives_in_pthread_attr() {
+            unsafe {
+                let mut attr = MyThreadAttr::default();
+                assert!(attr.stack_size() >= libc::PTHREAD_STACK_MIN);
+
+                assert_eq!(my_attr_setstacksize(&mut attr, 256 * 1024), 0);
+                let mut size = 0;
+                assert_eq!(my_attr_getstacksize(&attr, &mut size), 0);
+                assert_eq!(size, 256 * 1024);
+                assert_eq!(attr.clone().stack_size(), 256 * 1024);
+
+                assert_eq!(my_attr_setstacksize(&mut attr, 1), libc::EINVAL);
+                assert_eq!(my_attr_setstacksize(ptr::null_mut(), 4096), libc::EINVAL);
+            }
+        }
+
+        #[test]
+        fn test_overflow_terminates_only_that_thread() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                let mut small = MyThreadAttr::default();
+                small.set_stack_size(64 * 1024);
+
+                let (mut bad, mut good): (ThreadId, ThreadId) = (0, 0);
+                pth.my_thread_create(&mut bad, &small, overflow, ptr::null_mut(), None);
+                pth.my_thread_create(&mut good, &small, returns_arg, 7 as *mut AnyParam, None);
+
+                let mut ret: *mut AnyParam = ptr::null_mut();
+                assert_eq!(pth.my_thread_join(bad, &mut ret), MyError::StackOverflow as c_int);
+                assert!(ret.is_null());
+                assert_eq!(last_overflowed(), Some(bad));
+
+                // El resto del runtime sigue funcionando
+                assert_eq!(pth.my_thread_join(good, &mut ret), 0);
+                assert_eq!(ret as usize, 7);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests_fault {
+        use std::ptr;
+        use libc::c_int;
+        use crate::mythread::myfault::my_thread_guard;
+        use crate::mythread::mypthread::MyPThread;
+        use crate::mythread::myerror::MyError;
+        use crate::mythread::mythread::{AnyParam, ThreadId};
+        use crate::mythread::thread_state::ThreadState;
+
+        extern "C-unwind" fn boom(_: *mut AnyParam) -> *mut AnyParam {
+            my_thread_guard(|| panic!("reactor fuera de control"))
+        }
+
+        extern "C-unwind" fn returns_arg(arg: *mut AnyParam) -> *mut AnyParam {
+            my_thread_guard(|| arg)
+        }
+
+        // Sin my_thread_guard: el panic sale de la rutina y lo atrapa el runtime
+        extern "C-unwind" fn unguarded_boom(_: *mut AnyParam) -> *mut AnyParam {
+            panic!("válvula sin guarda")
+        }
+
+        #[test]
+        fn test_panic_marks_thread_faulted_and_runtime_continues() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                let (mut bad, mut good): (ThreadId, ThreadId) = (0, 0);
+                pth.my_thread_create(&mut bad, ptr::null(), boom, ptr::null_mut(), None);
+                pth.my_thread_create(&mut good, ptr::null(), returns_arg, 3 as *mut AnyParam, None);
+
+                let mut ret: *mut AnyParam = 1 as *mut AnyParam;
+                assert_eq!(pth.my_thread_join(bad, &mut ret), MyError::Faulted as c_int);
+                assert!(ret.is_null());
+                assert_eq!(pth.runtime.get_state(bad), Some(ThreadState::Faulted));
+                assert_eq!(pth.my_thread_panic_message(bad).as_deref(), Some("reactor fuera de control"));
+
+                assert_eq!(pth.my_thread_join(good, &mut ret), 0);
+                assert_eq!(ret as usize, 3);
+                assert_eq!(pth.my_thread_panic_message(good), None);
+            }
+        }
+
+        #[test]
+        fn test_unguarded_panic_marks_thread_faulted() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                let (mut bad, mut good): (ThreadId, ThreadId) = (0, 0);
+                pth.my_thread_create(&mut bad, ptr::null(), unguarded_boom, ptr::null_mut(), None);
+                pth.my_thread_create(&mut good, ptr::null(), returns_arg, 5 as *mut AnyParam, None);
+
+                let mut ret: *mut AnyParam = ptr::dangling_mut();
+                assert_eq!(pth.my_thread_join(bad, &mut ret), MyError::Faulted as c_int);
+                assert!(ret.is_null());
+                assert_eq!(pth.my_thread_panic_message(bad).as_deref(), Some("válvula sin guarda"));
+                assert_eq!(pth.join(good).map(|ret| ret as usize), Ok(5));
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests_introspection {
+        use std::ffi::CStr;
+        use std::ptr;
+        use libc::c_char;
+        use crate::mythread::mypthread::MyPThread;
+        use crate::mythread::mythread::{AnyParam, ThreadId, MY_DRIVER_THREAD};
+        use crate::mythread::mythreadattr::MyThreadAttr;
+        use crate::mythread::thread_state::ThreadState;
+        use crate::scheduler::SchedulerType;
+
+        extern "C-unwind" fn noop(_: *mut AnyParam) -> *mut AnyParam {
+            ptr::null_mut()
+        }
+
+        #[test]
+        fn test_setname_getname() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                let mut tid: ThreadId = 0;
+                pth.my_thread_create(&mut tid, ptr::null(), noop, ptr::null_mut(), None);
+
+                let mut buf = [0 as c_char; 32];
+                assert_eq!(pth.my_thread_getname(tid, buf.as_mut_ptr(), buf.len()), 0);
+                assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str().unwrap(), "");
+
+                assert_eq!(pth.my_thread_setname(tid, c"truck-plant(14,19)".as_ptr()), 0);
+                assert_eq!(pth.my_thread_getname(tid, buf.as_mut_ptr(), buf.len()), 0);
+                assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str().unwrap(), "truck-plant(14,19)");
+
+                let mut small = [0 as c_char; 8];
+                assert_eq!(pth.my_thread_getname(tid, small.as_mut_ptr(), small.len()), libc::ERANGE);
+                assert_eq!(pth.my_thread_setname(999, c"x".as_ptr()), libc::ESRCH);
+                assert_eq!(pth.my_thread_setname(tid, ptr::null()), libc::EINVAL);
+            }
+        }
+
+        #[test]
+        fn test_self_equal_and_list() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                assert_eq!(pth.my_thread_self(), MY_DRIVER_THREAD);
+                assert_ne!(MyPThread::my_thread_equal(1, 1), 0);
+                assert_eq!(MyPThread::my_thread_equal(1, 2), 0);
+
+                let (mut a, mut b): (ThreadId, ThreadId) = (0, 0);
+                let attr = MyThreadAttr::new(40, 7);
+                pth.my_thread_create(&mut a, &attr, noop, ptr::null_mut(), Some(SchedulerType::RealTime));
+                pth.my_thread_create(&mut b, ptr::null(), noop, ptr::null_mut(), None);
+                pth.my_thread_setname(a, c"car-17".as_ptr());
+
+                let list = pth.my_runtime_list_threads();
+                assert_eq!(list.len(), 2);
+                assert_eq!((list[0].id, list[0].name.as_deref()), (a, Some("car-17")));
+                assert_eq!((list[0].scheduler, list[0].priority, list[0].dead_line), (SchedulerType::RealTime, 7, 40));
+                assert_eq!(list[0].state, ThreadState::Ready);
+                assert_eq!((list[1].id, list[1].name.as_deref()), (b, None));
+
+                // Los terminados ya no salen en la foto
+                pth.my_thread_join(a, ptr::null_mut());
+                let ids: Vec<ThreadId> = pth.my_runtime_list_threads().iter().map(|i| i.id).collect();
+                assert_eq!(ids, vec![b]);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests_spin_once {
+        use std::ptr;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use crate::mythread::mypthread::MyPThread;
+        use crate::mythread::mythread::{AnyParam, ThreadId};
+        use crate::mythread::myonce::{my_once, MyOnce};
+        use crate::mythread::myspinlock::MySpinLock;
+
+        struct Shared {
+            pth: *mut MyPThread,
+            lock: MySpinLock,
+            log: Vec<&'static str>,
+        }
+
+        // Toma el lock y cede el CPU sin soltarlo
+        extern "C-unwind" fn holder(arg: *mut AnyParam) -> *mut AnyParam {
+            unsafe {
+                let shared = &mut *(arg as *mut Shared);
+                assert_eq!((*shared.pth).my_spin_lock(&mut shared.lock), 0);
+                shared.log.push("holder-lock");
+                (*shared.pth).my_thread_yield();
+                shared.log.push("holder-unlock");
+                assert_eq!((*shared.pth).my_spin_unlock(&mut shared.lock), 0);
+            }
+            ptr::null_mut()
+        }
+
+        extern "C-unwind" fn waiter(arg: *mut AnyParam) -> *mut AnyParam {
+            unsafe {
+                let shared = &mut *(arg as *mut Shared);
+                assert_eq!((*shared.pth).my_spin_trylock(&mut shared.lock), libc::EBUSY);
+                shared.log.push("waiter-spin");
+                assert_eq!((*shared.pth).my_spin_lock(&mut shared.lock), 0);
+                shared.log.push("waiter-lock");
+                (*shared.pth).my_spin_unlock(&mut shared.lock);
+            }
+            ptr::null_mut()
+        }
+
+        #[test]
+        fn test_spin_lock_yields_to_holder_in_cooperative_runtime() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                let mut shared = Shared { pth: &mut pth, lock: MySpinLock::new(), log: Vec::new() };
+                assert_eq!(pth.my_spin_init(&mut shared.lock), 0);
+                let arg = &mut shared as *mut Shared as *mut AnyParam;
+
+                let (mut a, mut b): (ThreadId, ThreadId) = (0, 0);
+                pth.my_thread_create(&mut a, ptr::null(), holder, arg, None);
+                pth.my_thread_create(&mut b, ptr::null(), waiter, arg, None);
+                pth.my_thread_join(b, ptr::null_mut());
+                pth.my_thread_join(a, ptr::null_mut());
+
+                // El que espera se estaciona tras girar y deja al dueño soltar el lock
+                assert_eq!(shared.log, vec!["holder-lock", "waiter-spin", "holder-unlock", "waiter-lock"]);
+                assert_eq!(pth.my_spin_destroy(&mut shared.lock), 0);
+            }
+        }
+
+        #[test]
+        fn test_spin_lock_error_codes() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                let mut lock = MySpinLock::new();
+                assert_eq!(pth.my_spin_unlock(&mut lock), libc::EPERM);
+                assert_eq!(pth.my_spin_lock(&mut lock), 0);
+                assert_eq!(pth.my_spin_trylock(&mut lock), libc::EBUSY);
+                assert_eq!(pth.my_spin_destroy(&mut lock), libc::EBUSY);
+                // Desde el driver y sin hilos que lo puedan soltar no gira para siempre
+                assert_eq!(pth.my_spin_lock(&mut lock), libc::EDEADLK);
+                assert_eq!(pth.my_spin_lock(ptr::null_mut()), libc::EINVAL);
+                assert_eq!(pth.my_spin_unlock(&mut lock), 0);
+            }
+        }
+
+        static ONCE: MyOnce = MyOnce::new();
+        static INIT_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        extern "C" fn init_once() {
+            INIT_CALLS.fetch_add(1, Ordering::SeqCst);
+        }
+
+        extern "C-unwind" fn call_once(_: *mut AnyParam) -> *mut AnyParam {
+            my_once(&ONCE, init_once);
+            ptr::null_mut()
+        }
+
+        #[test]
+        fn test_my_once_runs_init_once() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                let tids: Vec<ThreadId> = (0..4)
+                    .map(|_| {
+                        let mut tid: ThreadId = 0;
+                        pth.my_thread_create(&mut tid, ptr::null(), call_once, ptr::null_mut(), None);
+                        tid
+                    })
+                    .collect();
+                for tid in tids {
+                    pth.my_thread_join(tid, ptr::null_mut());
+                }
+                assert_eq!(my_once(&ONCE, init_once), 0);
+                assert!(ONCE.is_completed());
+                assert_eq!(INIT_CALLS.load(Ordering::SeqCst), 1);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests_errors {
+        use std::io;
+        use std::ptr;
+        use crate::mythread::myerror::{MyError, MyResult};
+        use crate::mythread::mymutex::MyMutex;
+        use crate::mythread::mypthread::MyPThread;
+        use crate::mythread::mythread::{AnyParam, MyTRoutine, ThreadId};
+        use crate::mythread::mythreadattr::MyThreadAttr;
+        use crate::scheduler::SchedulerType;
+
+        struct Shared {
+            pth: *mut MyPThread,
+            mutex: MyMutex,
+            target: ThreadId,
+            results: Vec<(&'static str, MyResult<()>)>,
+        }
+
+        fn shared(arg: *mut AnyParam) -> &'static mut Shared {
+            unsafe { &mut *(arg as *mut Shared) }
+        }
+
+        extern "C-unwind" fn noop(_: *mut AnyParam) -> *mut AnyParam {
+            ptr::null_mut()
+        }
+
+        extern "C-unwind" fn join_self(arg: *mut AnyParam) -> *mut AnyParam {
+            let s = shared(arg);
+            let pth = unsafe { &mut *s.pth };
+            let me = pth.my_thread_self();
+            s.results.push(("self", pth.join(me).map(drop)));
+            ptr::null_mut()
+        }
+
+        extern "C-unwind" fn join_target(arg: *mut AnyParam) -> *mut AnyParam {
+            let s = shared(arg);
+            let pth = unsafe { &mut *s.pth };
+            let target = s.target;
+            let rc = pth.join(target).map(drop);
+            s.results.push(("join", rc));
+            ptr::null_mut()
+        }
+
+        extern "C-unwind" fn unlock_foreign(arg: *mut AnyParam) -> *mut AnyParam {
+            let s = shared(arg);
+            let pth = unsafe { &mut *s.pth };
+            s.results.push(("unlock", pth.mutex_unlock(&mut s.mutex)));
+            ptr::null_mut()
+        }
+
+        // Toma el mutex y cede el CPU sin soltarlo
+        extern "C-unwind" fn holder(arg: *mut AnyParam) -> *mut AnyParam {
+            let s = shared(arg);
+            let pth = unsafe { &mut *s.pth };
+            s.results.push(("holder-lock", pth.mutex_lock(&mut s.mutex)));
+            pth.yield_now();
+            s.results.push(("holder-unlock", pth.mutex_unlock(&mut s.mutex)));
+            ptr::null_mut()
+        }
+
+        extern "C-unwind" fn waiter(arg: *mut AnyParam) -> *mut AnyParam {
+            let s = shared(arg);
+            let pth = unsafe { &mut *s.pth };
+            s.results.push(("waiter-lock", pth.mutex_lock(&mut s.mutex)));
+            s.results.push(("waiter-unlock", pth.mutex_unlock(&mut s.mutex)));
+            ptr::null_mut()
+        }
+
+        fn spawn(pth: &mut MyPThread, routine: MyTRoutine, arg: *mut AnyParam) -> MyResult<ThreadId> {
+            unsafe { pth.create(None, routine, arg, None) }
+        }
+
+        fn new_shared(pth: &mut MyPThread) -> Shared {
+            let mut mutex = MyMutex::new();
+            pth.mutex_init(&mut mutex).unwrap();
+            Shared { pth, mutex, target: 0, results: Vec::new() }
+        }
+
+        #[test]
+        fn test_error_codes_are_posix_errno() {
+            assert_eq!(MyError::Invalid.errno(), libc::EINVAL);
+            assert_eq!(MyError::from_errno(libc::ESRCH), Some(MyError::NoSuchThread));
+            assert_eq!(MyError::from_errno(libc::ENOENT), None);
+            assert_eq!(MyError::check(0), Ok(()));
+            assert_eq!(MyError::check(libc::EBUSY), Err(MyError::Busy));
+            assert_eq!(MyError::code(Err(MyError::Deadlock)), libc::EDEADLK);
+            assert_eq!(MyError::code(Ok(())), 0);
+            assert_eq!(io::Error::from(MyError::NotPermitted).raw_os_error(), Some(libc::EPERM));
+            assert!(MyError::Busy.to_string().contains(&format!("errno {}", libc::EBUSY)));
+        }
+
+        #[test]
+        fn test_thread_calls_report_missing_threads_and_bad_arguments() {
+            let mut pth = MyPThread::new();
+            let gone: ThreadId = 12345;
+            assert_eq!(pth.join(gone), Err(MyError::NoSuchThread));
+            assert_eq!(pth.detach(gone), Err(MyError::NoSuchThread));
+            assert_eq!(pth.chsched(gone, SchedulerType::Lottery), Err(MyError::NoSuchThread));
+            assert_eq!(pth.setname(gone, "x"), Err(MyError::NoSuchThread));
+            assert_eq!(pth.getname(gone), Err(MyError::NoSuchThread));
+            assert_eq!(pth.kill(gone, libc::SIGUSR1), Err(MyError::NoSuchThread));
+
+            assert_eq!(unsafe { pth.create(None, noop, ptr::null_mut(), Some(SchedulerType::Custom(99))) }, Err(MyError::Invalid));
+            let tid = spawn(&mut pth, noop, ptr::null_mut()).unwrap();
+            assert_eq!(pth.chsched(tid, SchedulerType::Custom(99)), Err(MyError::Invalid));
+            assert_eq!(pth.set_group(tid, Some(9999)), Err(MyError::Invalid));
+            assert_eq!(pth.kill(tid, 999), Err(MyError::Invalid));
+            // Fuera de un hilo verde no hay hilo que terminar ni máscara que cambiar
+            assert_eq!(unsafe { pth.end(ptr::null_mut()) }, Err(MyError::NotPermitted));
+            assert_eq!(pth.sigmask(libc::SIG_BLOCK, None), Err(MyError::NotPermitted));
+            assert_eq!(pth.join(tid), Ok(ptr::null_mut()));
+
+            let mut attr = MyThreadAttr::default();
+            attr.detach();
+            let detached = unsafe { pth.create(Some(&attr), noop, ptr::null_mut(), None) }.unwrap();
+            assert_eq!(pth.join(detached), Err(MyError::Invalid));
+        }
+
+        #[test]
+        fn test_join_self_is_deadlock_and_second_joiner_is_rejected() {
+            let mut pth = MyPThread::new();
+            let mut s = new_shared(&mut pth);
+            let arg = &mut s as *mut Shared as *mut AnyParam;
+
+            let me = spawn(&mut pth, join_self, arg).unwrap();
+            pth.join(me).unwrap();
+            assert_eq!(s.results, vec![("self", Err(MyError::Deadlock))]);
+
+            // a espera a c; b corre mientras tanto y también quiere esperar a c
+            s.results.clear();
+            let a = spawn(&mut pth, join_target, arg).unwrap();
+            spawn(&mut pth, join_target, arg).unwrap();
+            s.target = spawn(&mut pth, noop, ptr::null_mut()).unwrap();
+            pth.join(a).unwrap();
+            assert_eq!(s.results, vec![("join", Err(MyError::Invalid)), ("join", Ok(()))]);
+        }
+
+        #[test]
+        fn test_mutex_error_paths() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                assert_eq!(pth.my_mutex_init(ptr::null_mut(), ptr::null()), libc::EINVAL);
+                assert_eq!(pth.my_mutex_destroy(ptr::null_mut()), libc::EINVAL);
+                assert_eq!(pth.my_mutex_lock(ptr::null_mut()), libc::EINVAL);
+                assert_eq!(pth.my_mutex_trylock(ptr::null_mut()), libc::EINVAL);
+                assert_eq!(pth.my_mutex_unlock(ptr::null_mut()), libc::EINVAL);
+            }
+            let mut pth = MyPThread::new();
+            let mut m = MyMutex::new();
+            assert_eq!(pth.mutex_lock(&mut m), Err(MyError::Invalid));
+            assert_eq!(pth.mutex_unlock(&mut m), Err(MyError::Invalid));
+            assert_eq!(pth.mutex_destroy(&mut m), Err(MyError::Invalid));
+
+            pth.mutex_init(&mut m).unwrap();
+            assert_eq!(pth.mutex_unlock(&mut m), Err(MyError::NotPermitted));
+            assert_eq!(pth.mutex_lock(&mut m), Ok(()));
+            assert_eq!(pth.mutex_lock(&mut m), Err(MyError::Deadlock));
+            assert_eq!(pth.mutex_trylock(&mut m), Err(MyError::Busy));
+            assert_eq!(pth.mutex_destroy(&mut m), Err(MyError::Busy));
+            assert_eq!(pth.mutex_unlock(&mut m), Ok(()));
+
+            // Lo tiene un hilo que ya no existe: nadie lo va a soltar y no se queda en la cola
+            assert_eq!(m.try_lock(77), 0);
+            assert_eq!(pth.mutex_lock(&mut m), Err(MyError::Deadlock));
+            assert!(m.wait_queue.is_empty());
+            assert_eq!(pth.mutex_unlock(&mut m), Ok(()));
+            assert_eq!(pth.mutex_destroy(&mut m), Ok(()));
+        }
+
+        #[test]
+        fn test_mutex_unlock_by_other_thread_is_not_permitted() {
+            let mut pth = MyPThread::new();
+            let mut s = new_shared(&mut pth);
+            assert_eq!(s.mutex.try_lock(77), 0);
+            let arg = &mut s as *mut Shared as *mut AnyParam;
+            let tid = spawn(&mut pth, unlock_foreign, arg).unwrap();
+            pth.join(tid).unwrap();
+            assert_eq!(s.results, vec![("unlock", Err(MyError::NotPermitted))]);
+            assert!(s.mutex.is_locked());
+        }
+
+        #[test]
+        fn test_mutex_lock_waits_for_the_owner() {
+            let mut pth = MyPThread::new();
+            let mut s = new_shared(&mut pth);
+            let arg = &mut s as *mut Shared as *mut AnyParam;
+            let a = spawn(&mut pth, holder, arg).unwrap();
+            let b = spawn(&mut pth, waiter, arg).unwrap();
+            pth.join(b).unwrap();
+            pth.join(a).unwrap();
+            let order: Vec<&str> = s.results.iter().map(|(what, rc)| { assert_eq!(*rc, Ok(()), "{what}"); *what }).collect();
+            assert_eq!(order, vec!["holder-lock", "holder-unlock", "waiter-lock", "waiter-unlock"]);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests_clock {
+        use std::sync::Arc;
+        use std::time::Duration;
+        use crate::mythread::myclock::{Clock, MonotonicClock, ScaledClock, SharedClock, VirtualClock};
+        use crate::mythread::mypthread::MyPThread;
+
+        #[test]
+        fn test_runtime_shares_virtual_clock() {
+            let mut pth = MyPThread::new();
+            assert_eq!(pth.my_runtime_time_ms(), 0);
+            pth.runtime.advance_steps(5);
+            assert_eq!(pth.my_runtime_time_ms(), 5);
+
+            // Quien tenga el mismo Arc ve el mismo tiempo
+            let clock: SharedClock = Arc::new(VirtualClock::new());
+            pth.my_runtime_setclock(clock.clone());
+            clock.advance(40);
+            assert_eq!(pth.my_runtime_time_ms(), 40);
+            pth.runtime.advance_steps(2);
+            assert_eq!(clock.now_ms(), 42);
+        }
+
+        #[test]
+        fn test_scaled_clock_speeds_up_inner() {
+            let inner: SharedClock = Arc::new(VirtualClock::new());
+            let fast = ScaledClock::new(inner.clone(), 10.0);
+            inner.advance(3);
+            assert_eq!(fast.now_ms(), 30);
+            fast.advance(50);
+            assert_eq!((inner.now_ms(), fast.now_ms()), (8, 80));
+
+            let slow = ScaledClock::new(inner.clone(), 0.5);
+            slow.advance(1);
+            assert_eq!(inner.now_ms(), 10);
+        }
+
+        #[test]
+        fn test_monotonic_clock_follows_wall_and_ignores_advance() {
+            let wall = MonotonicClock::new();
+            wall.advance(10_000);
+            assert!(wall.now_ms() < 10_000);
+            std::thread::sleep(Duration::from_millis(5));
+            assert!(wall.now_ms() >= 5);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests_replay {
+        use crate::mythread::myreplay::{my_random_u64, my_replay_record, my_replay_start, my_replay_stop, MyEvent, MyEventLog};
+
+        #[test]
+        fn test_event_log_text_roundtrip() {
+            let log = MyEventLog { seed: 42, events: vec![MyEvent::Pick(3), MyEvent::Draw(u64::MAX), MyEvent::Pick(0)] };
+            let text = log.to_text();
+            assert_eq!(text, format!("seed 42\npick 3\ndraw {}\npick 0\n", u64::MAX));
+            assert_eq!(MyEventLog::from_text(&text).unwrap(), log);
+
+            assert!(MyEventLog::from_text("pick 3\n").is_err(), "sin semilla");
+            assert!(MyEventLog::from_text("seed 1\nswap 2\n").is_err());
+            assert!(MyEventLog::from_text("seed x\n").is_err());
+        }
+
+        #[test]
+        fn test_draws_follow_seed_and_replay_returns_recorded() {
+            my_replay_record(5);
+            let first: Vec<u64> = (0..4).map(|_| my_random_u64()).collect();
+            let log = my_replay_stop();
+            assert_eq!(log.events, first.iter().map(|&v| MyEvent::Draw(v)).collect::<Vec<_>>());
+
+            my_replay_record(5);
+            assert_eq!((0..4).map(|_| my_random_u64()).collect::<Vec<_>>(), first);
+
+            // El replay entrega lo grabado aunque la grabación se haya editado a mano
+            let mut edited = log.clone();
+            edited.events[1] = MyEvent::Draw(1234);
+            my_replay_start(edited);
+            assert_eq!(my_random_u64(), first[0]);
+            assert_eq!(my_random_u64(), 1234);
+            my_replay_stop();
+        }
+    }
+
+    #[cfg(test)]
+    mod tests_io {
+        use std::ptr;
+        use crate::mythread::mypthread::MyPThread;
+        use crate::mythread::mythread::{AnyParam, ThreadId};
+        use crate::mythread::thread_state::ThreadState;
+
+        // El argumento es el fd de escritura del pipe
+        extern "C-unwind" fn pipe_writer(arg: *mut AnyParam) -> *mut AnyParam {
+            let fd = arg as usize as i32;
+            let msg = b"ping";
+            unsafe { libc::write(fd, msg.as_ptr().cast(), msg.len()) };
+            ptr::null_mut()
+        }
+
+        #[test]
+        fn test_my_read_runs_other_threads_until_ready() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                let mut fds = [0; 2];
+                assert_eq!(libc::pipe(fds.as_mut_ptr()), 0);
+
+                let mut tid: ThreadId = 0;
+                pth.my_thread_create(&mut tid, ptr::null(), pipe_writer, fds[1] as usize as *mut AnyParam, None);
+
+                // Todavía no hay datos: my_read corre al escritor en vez de bloquear el proceso
+                let mut buf = [0u8; 8];
+                let n = pth.my_read(fds[0], buf.as_mut_ptr().cast(), buf.len());
+                assert_eq!(n, 4, "my_read devolvió {n}");
+                assert_eq!(&buf[..4], b"ping");
+
+                let flags = libc::fcntl(fds[0], libc::F_GETFL);
+                assert_ne!(flags & libc::O_NONBLOCK, 0, "my_read debe dejar el fd en O_NONBLOCK");
+
+                libc::close(fds[0]);
+                libc::close(fds[1]);
+            }
+        }
+
+        struct PipeRead {
+            pth: *mut MyPThread,
+            fd: i32,
+            got: isize,
+        }
+
+        extern "C-unwind" fn pipe_reader(arg: *mut AnyParam) -> *mut AnyParam {
+            unsafe {
+                let job = &mut *(arg as *mut PipeRead);
+                let mut buf = [0u8; 8];
+                job.got = (*job.pth).my_read(job.fd, buf.as_mut_ptr().cast(), buf.len());
+            }
+            ptr::null_mut()
+        }
+
+        #[test]
+        fn test_ready_reader_resumes_while_another_still_waits() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                let (mut slow, mut fast) = ([0; 2], [0; 2]);
+                assert_eq!(libc::pipe(slow.as_mut_ptr()), 0);
+                assert_eq!(libc::pipe(fast.as_mut_ptr()), 0);
+                let mut first = PipeRead { pth: &mut pth, fd: slow[0], got: -1 };
+                let mut second = PipeRead { pth: &mut pth, fd: fast[0], got: -1 };
+
+                let (mut a, mut b, mut w): (ThreadId, ThreadId, ThreadId) = (0, 0, 0);
+                pth.my_thread_create(&mut a, ptr::null(), pipe_reader, &mut first as *mut PipeRead as *mut AnyParam, None);
+                pth.my_thread_create(&mut b, ptr::null(), pipe_reader, &mut second as *mut PipeRead as *mut AnyParam, None);
+                pth.my_thread_create(&mut w, ptr::null(), pipe_writer, fast[1] as usize as *mut AnyParam, None);
+
+                // El primer lector espera un pipe que nadie escribe todavía; el segundo no queda debajo suyo
+                assert_eq!(pth.my_thread_join(b, ptr::null_mut()), 0);
+                assert_eq!(second.got, 4);
+                assert_eq!(pth.runtime.get_state(a), Some(ThreadState::Blocked));
+
+                libc::write(slow[1], b"pong".as_ptr().cast(), 4);
+                assert_eq!(pth.my_thread_join(a, ptr::null_mut()), 0);
+                assert_eq!(first.got, 4);
+                pth.my_thread_join(w, ptr::null_mut());
+
+                for fd in slow.into_iter().chain(fast) {
+                    libc::close(fd);
+                }
+            }
+        }
+
+        #[test]
+        fn test_my_write_and_read_report_errors() {
+            unsafe {
+                let mut pth = MyPThread::new();
+                let mut buf = [0u8; 4];
+                assert_eq!(pth.my_read(-1, buf.as_mut_ptr().cast(), buf.len()), -1);
+                assert_eq!(pth.my_write(-1, buf.as_ptr().cast(), buf.len()), -1);
+                assert_eq!(pth.my_accept(-1, ptr::null_mut(), ptr::null_mut()), -1);
+            }
+        }
+    }
+
     #[test]
     fn test_create_and_join_behaviors() {
         unsafe {
@@ -356,7 +1331,6 @@ mod tests {
     #[test]
     fn test_mutex_lock_and_unlock() {
         unsafe {
-            let block_val: c_int = Exits::ThreadBlocked as c_int;
             let mut pth = MyPThread::new();
             let mut tid: ThreadId = 0;
             let mut attr: MyThreadAttr = MyThreadAttr::new(usize::MAX, 1);
@@ -377,9 +1351,9 @@ mod tests {
             // Inicializar mutex
             pth.my_mutex_init(&mut mutex as *mut MyMutex, ptr::null());
 
-            // Bloquear mutex
+            // Bloquear mutex: está libre, lo toma de una vez
             let res_lock = pth.my_mutex_lock(&mut mutex as *mut MyMutex);
-            assert_eq!(res_lock, block_val, "my_mutex_lock no retornó ThreadBlocked (2)");
+            assert_eq!(res_lock, 0, "my_mutex_lock no retornó 0");
 
             // Asignar manualmente el dueño para la prueba
             mutex.owner = Some(tid);
@@ -411,4 +1385,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_dump_lists_mutex_owner_and_waiters() {
+        use crate::mythread::myinspect::my_list_mutexes;
+        unsafe {
+            let mut pth = MyPThread::new();
+            let mut mutex = MyMutex::new();
+            pth.my_mutex_init(&mut mutex, ptr::null());
+            mutex.set_name("puente");
+            let id = mutex.id();
+            assert_ne!(id, 0);
+
+            mutex.lock(1);
+            mutex.lock(2);
+            mutex.lock(3);
+            let dump = pth.my_runtime_dump();
+            let info = dump.mutexes.iter().find(|m| m.id == id).expect("el mutex debería estar registrado");
+            assert_eq!(info.name.as_deref(), Some("puente"));
+            assert_eq!(info.owner, Some(1));
+            assert_eq!(info.waiters, vec![2, 3]);
+            assert!(dump.to_string().contains("puente"));
+
+            // Al soltarlo queda libre; los de la cola vuelven a intentar
+            mutex.unlock(Some(1));
+            let info = my_list_mutexes().into_iter().find(|m| m.id == id).unwrap();
+            assert_eq!((info.owner, info.waiters), (None, vec![2, 3]));
+
+            // Con cola no se puede destruir, pero al soltarlo sale del registro
+            assert_ne!(pth.my_mutex_destroy(&mut mutex), 0);
+            drop(mutex);
+            assert!(my_list_mutexes().iter().all(|m| m.id != id));
+        }
+    }
+
+    #[test]
+    fn test_dump_on_signal_rejects_uncatchable() {
+        unsafe {
+            let mut pth = MyPThread::new();
+            assert_eq!(pth.my_runtime_dump_on(libc::SIGKILL), libc::EINVAL);
+            assert_eq!(pth.my_runtime_dump_on(0), libc::EINVAL);
+            assert_eq!(pth.my_runtime_dump_on(libc::SIGWINCH), 0);
+        }
+    }
+
 }
@@ -5,6 +5,8 @@ use gtk::prelude::*;
 use gtk::{Application, ApplicationWindow, DrawingArea};
 use gtk::cairo;
 use glib::source::timeout_add_local;
+use std::sync::Arc;
+use mypthreads::mythread::myclock::{MonotonicClock, ScaledClock};
 use mypthreads::mythread::mymutex::MyMutex;
 use crate::cityblock::{map, Block};
 use crate::cityblock::coord::Coord;
@@ -23,11 +25,14 @@ use std::collections::{HashMap, HashSet};
 use std::thread::sleep;
 use std::time::Duration;
 use gtk::cairo::Operator;
-use crate::city::simulation_controller::SimulationController;
+use crate::city::simulation_controller::{SimulationController, FRAME_MS};
 use crate::cityblock::block_type::BlockType::NuclearPlant;
 use crate::cityblock::nuclearplant::plant_status::PlantStatus::Boom;
 use crate::vehicle::vehicle_type::VehicleType;
 
+// Milisegundos reales entre frames
+const TICK_MS: u64 = 1000;
+
 //  UI Hooks: cómo la GUI consulta
 #[derive(Clone)]
 pub struct UiHooks {
@@ -237,10 +242,10 @@ pub(crate) fn build_ui(app: &Application, hooks: UiHooks) {
     win.set_child(Some(&area));
     win.show();
 
-    // Timer de frames (33ms ~ 30 FPS). En cada tick, avanza 1 frame y repinta.
+    // Timer de frames (TICK_MS por frame). En cada tick, avanza 1 frame y repinta.
     let area_weak = area.downgrade();
     let tick_cb = hooks.tick.clone();
-    timeout_add_local(std::time::Duration::from_millis(1000), move || {
+    timeout_add_local(std::time::Duration::from_millis(TICK_MS), move || {
         (tick_cb.borrow_mut())(); // avanza tu simulación 1 frame
         if let Some(area) = area_weak.upgrade() {
             area.queue_draw();
@@ -270,6 +275,11 @@ pub(crate) fn make_hooks_from_world() -> UiHooks {
     //Controlador de simulación (map, tráfico, plantas, etc.)
     let sim: Rc<RefCell<SimulationController>> =
         Rc::new(RefCell::new(SimulationController::new()));
+    // En la GUI la ciudad va en tiempo real: FRAME_MS de la ciudad por cada TICK_MS de reloj de pared
+    sim.borrow_mut().set_clock(Arc::new(ScaledClock::new(
+        Arc::new(MonotonicClock::new()),
+        FRAME_MS as f64 / TICK_MS as f64,
+    )));
 
     // Ocupación visible para la GUI (se actualiza en cada tick)
     let occupancy = Rc::new(RefCell::new(HashSet::<(i16, i16)>::new()));
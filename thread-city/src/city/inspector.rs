@@ -0,0 +1,50 @@
+use std::fmt::Write;
+use std::time::Duration;
+use crate::city::simulation_controller::SimulationController;
+use crate::cityblock::nuclearplant::NuclearPlantBlock;
+
+// Un frame de `thread-city top`: el dump del runtime (hilos y mutex de los puentes) y los vehículos
+// que siguen en la ciudad.
+pub fn render(sim: &SimulationController) -> String {
+    let mut out = String::new();
+    let vehicles = &sim.traffic.vehicles;
+    let _ = writeln!(out, "frame {}  vehículos {}", sim.traffic.passed_frames, vehicles.len());
+    let _ = writeln!(out);
+    let _ = writeln!(out, "{}", sim.runtime.dump());
+    let _ = writeln!(out, "{:>6} {:<12} {:<10} {:<10} {:>9}", "tid", "tipo", "posición", "destino", "paciencia");
+    for (tid, vehicle) in vehicles {
+        let base = vehicle.base();
+        let _ = writeln!(
+            out,
+            "{:>6} {:<12} {:<10} {:<10} {:>9}",
+            tid,
+            format!("{:?}", base.vehicle_type),
+            format!("({},{})", base.current_position.x, base.current_position.y),
+            format!("({},{})", base.destination.x, base.destination.y),
+            format!("{}/{}", base.patience, base.max_patience),
+        );
+    }
+    // Plantas con los insumos que esperan y hace cuánto los pidieron (reloj de la ciudad)
+    let _ = writeln!(out);
+    let mut map = sim.map.borrow_mut();
+    for &coord in &sim.nuclear_plants {
+        let Some(plant) = map.get_block_at(coord).and_then(|b| b.as_any().downcast_mut::<NuclearPlantBlock>()) else { continue };
+        let pending: Vec<String> = plant.requires
+            .iter()
+            .map(|r| format!("{:?} hace {} ms", r.kind, r.elapsed_ms(&*sim.clock)))
+            .collect();
+        let _ = writeln!(out, "planta ({},{}) {:?}  pendientes: {}", coord.x, coord.y, plant.plant_status, pending.join(", "));
+    }
+    out
+}
+
+// Corre `frames` frames desde `seed` redibujando la terminal cada `refresh`.
+pub fn run_top(seed: u64, frames: usize, refresh: Duration) {
+    let mut sim = SimulationController::with_seed(seed);
+    for _ in 0..frames {
+        sim.advance_time(1);
+        // Limpia la pantalla y vuelve al inicio
+        print!("\x1b[2J\x1b[H{}", render(&sim));
+        std::thread::sleep(refresh);
+    }
+}
@@ -4,6 +4,9 @@ use crate::cityblock::map::Map;
 pub mod supply_kind;
 pub mod traffic_handler;
 pub mod simulation_controller;
+pub mod path_bench;
+pub mod replay;
+pub mod inspector;
 
 pub struct ThreadCity {
     pub map: Map,
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use mypthreads::mythread::mymutex::MyMutex;
+use mypthreads::MultiRuntime;
+use crate::cityblock::block_type::BlockType::{Road, Shops};
+use crate::cityblock::coord::Coord;
+use crate::cityblock::map::Map;
+use crate::vehicle::car::Car;
+
+// Cuántas rutas calcula cada hilo verde
+const PATHS_PER_THREAD: usize = 64;
+
+pub struct BenchResult {
+    pub workers: usize,
+    pub elapsed: Duration,
+    pub checksum: usize,
+}
+
+// Recalcula la ruta de `vehicles` carros sobre el mapa 25x25, repartidos en hilos verdes del
+// runtime M:N, una vez por cada cantidad de workers. Las rutas son fijas para comparar corridas.
+pub fn run(vehicles: usize, worker_counts: &[usize]) -> Vec<BenchResult> {
+    let map = Arc::new(Map::map_25x25_with_all_blocks(MyMutex::new(), MyMutex::new(), MyMutex::new()));
+    let roads = map.find_blocks(Road);
+    let shops = map.find_blocks(Shops);
+    let routes: Arc<Vec<(Coord, Coord)>> = Arc::new(
+        (0..vehicles)
+            .map(|i| (roads[(i * 7) % roads.len()], shops[(i * 3) % shops.len()]))
+            .collect(),
+    );
+
+    worker_counts
+        .iter()
+        .map(|&workers| {
+            let rt = MultiRuntime::new(workers);
+            let start = Instant::now();
+
+            let handles: Vec<_> = (0..vehicles)
+                .step_by(PATHS_PER_THREAD)
+                .map(|from| {
+                    let (map, routes) = (map.clone(), routes.clone());
+                    rt.spawn(move || {
+                        let to = (from + PATHS_PER_THREAD).min(routes.len());
+                        routes[from..to]
+                            .iter()
+                            .map(|&(origin, destination)| {
+                                let mut car = Car::new(origin, destination);
+                                car.base.calculate_path(&map);
+                                car.base.path.map_or(0, |p| p.len())
+                            })
+                            .sum::<usize>()
+                    })
+                })
+                .collect();
+            let checksum = handles.into_iter().map(|h| h.join()).sum();
+
+            BenchResult { workers, elapsed: start.elapsed(), checksum }
+        })
+        .collect()
+}
+
+pub fn print_report(results: &[BenchResult]) {
+    let base = results.first().map(|r| r.elapsed.as_secs_f64()).unwrap_or(1.0);
+    println!("workers,ms,speedup,checksum");
+    for r in results {
+        let secs = r.elapsed.as_secs_f64();
+        println!("{},{:.2},{:.2},{}", r.workers, secs * 1000.0, base / secs, r.checksum);
+    }
+}
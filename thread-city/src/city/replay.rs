@@ -0,0 +1,31 @@
+use std::io;
+use std::path::Path;
+use mypthreads::mythread::myreplay::{my_replay_diverged, my_replay_log, my_replay_remaining, MyEventLog};
+use crate::city::simulation_controller::SimulationController;
+
+// Corre `frames` frames de la ciudad sin GUI a partir de `seed` y guarda la bitácora en `path`.
+pub fn record(path: impl AsRef<Path>, seed: u64, frames: usize) -> io::Result<MyEventLog> {
+    let mut sim = SimulationController::with_seed(seed);
+    advance(&mut sim, frames);
+    let log = my_replay_log();
+    log.save(path)?;
+    Ok(log)
+}
+
+// Repite la corrida grabada en `path` frame por frame. Devuelve el índice del primer evento que no
+// coincidió (None si se repitió igual).
+pub fn replay(path: impl AsRef<Path>, frames: usize) -> io::Result<Option<usize>> {
+    let mut sim = SimulationController::replaying(MyEventLog::load(path)?);
+    advance(&mut sim, frames);
+    Ok(my_replay_diverged().or_else(|| {
+        // Si quedaron eventos sin consumir la corrida se cortó antes que la grabación
+        let left = my_replay_remaining();
+        (left > 0).then(|| my_replay_log().events.len() - left)
+    }))
+}
+
+fn advance(sim: &mut SimulationController, frames: usize) {
+    for _ in 0..frames {
+        sim.advance_time(1);
+    }
+}
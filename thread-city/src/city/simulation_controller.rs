@@ -1,30 +1,90 @@
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
-use rand::Rng;
+use mypthreads::mythread::myclock::SharedClock;
 use mypthreads::mythread::mutexlockkind::MyMutexAttr;
 use mypthreads::mythread::mymutex::MyMutex;
 use mypthreads::mythread::mypthread::MyPThread;
-use mypthreads::mythread::mythread::{AnyParam, MyTRoutine, MyThreadAttr, ThreadId};
+use mypthreads::mythread::myreplay::{my_random_below, my_random_bool, MyEventLog};
+use mypthreads::mythread::mysignal::{MY_SIGTERM, MY_SIGUSR1, MY_SIGUSR2};
+use mypthreads::mythread::mythread::ThreadId;
+use mypthreads::{JoinHandle, MyChannel, MyReceiver, MySender, Runtime, SchedulerType, ROOT_GROUP};
+use mypthreads::scheduler::{GroupId, ServerId};
 use crate::city::traffic_handler::TrafficHandler;
 use crate::cityblock::block_type::BlockType::NuclearPlant;
 use crate::cityblock::bridge::BridgeBlock;
 use crate::cityblock::coord::Coord;
 use crate::cityblock::map::Map;
 use crate::cityblock::nuclearplant::NuclearPlantBlock;
-use crate::cityblock::nuclearplant::plant_status::PlantStatus::Boom;
+use crate::cityblock::nuclearplant::plant_status::PlantStatus::Critical;
+use crate::cityblock::nuclearplant::supply_order::SupplyOrder;
 use crate::cityblock::nuclearplant::supply_spec::SupplySpec;
+use crate::cityblock::road::RoadBlock;
+use crate::vehicle::vehicle_type::VehicleType;
+use crate::vehicle::vehicle_type::VehicleType::{AmbulanceE, CarE, ShipE, TruckE};
+
+// Tiempo del reloj de la ciudad que dura un frame; los intervalos de semáforos y plantas están en
+// estas mismas unidades
+pub const FRAME_MS: usize = 1;
+
+// Hilos verdes entre los que se reparte el recálculo de rutas
+const PATH_THREADS: usize = 4;
+
+// Señal que reciben los hilos de las ambulancias cuando una planta entra en Critical
+pub const EMERGENCY_SIGNAL: i32 = MY_SIGUSR1;
+
+// Señal que despierta al hilo de una ambulancia que ya salió de la ciudad para que termine
+const OFF_DUTY_SIGNAL: i32 = MY_SIGTERM;
+
+// Hilo de una ambulancia: vive mientras el vehículo siga en la ciudad, en pause entre una señal y otra
+pub(crate) struct AmbulanceDuty {
+    on_duty: Rc<Cell<bool>>,
+    thread: JoinHandle<()>,
+}
 
 pub struct SimulationController {
     pub(crate) traffic: TrafficHandler,
     pub(crate) nuclear_plants: Vec<Coord>,
     pub(crate) with_traffic_bridge: Coord,
     pub(crate) map: Rc<RefCell<Map>>,
-    pub(crate) my_pthread: MyPThread,
+    pub(crate) runtime: Runtime,
+    // El mismo reloj del runtime. Virtual por defecto: avanza FRAME_MS por frame y la ciudad corre
+    // tan rápido como se le pida; la GUI pone uno de pared escalado para ir en tiempo real
+    pub(crate) clock: SharedClock,
+    // Ambulancias cuyo hilo ya atendió EMERGENCY_SIGNAL
+    pub(crate) emergencies: Rc<RefCell<Vec<ThreadId>>>,
+    pub(crate) ambulances: BTreeMap<ThreadId, AmbulanceDuty>,
+    // Grupo de scheduling de cada tipo de vehículo
+    pub(crate) vehicle_groups: HashMap<VehicleType, GroupId>,
+    // Pedidos de insumos de las plantas; los recoge el hilo despachador
+    pub(crate) order_sender: MySender<SupplyOrder>,
+    pub(crate) supply_orders: Rc<MyReceiver<SupplyOrder>>,
+    // Servidor de RealTime del despachador: los pedidos llegan a cualquier hora y no deben
+    // quitarle el CPU a los hilos de tiempo real periódicos
+    pub(crate) supply_server: ServerId,
 }
 
 impl SimulationController {
+    // Corrida nueva con semilla del reloj; se imprime para poder repetirla con `thread-city record`.
     pub fn new() -> Self {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        println!("thread-city seed {seed}");
+        Self::with_seed(seed)
+    }
+
+    // Toda decisión del scheduler y todo sorteo de la ciudad sale de `seed` y queda grabado.
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_runtime(Runtime::with_seed(seed))
+    }
+
+    // Repite una corrida grabada con `with_seed`.
+    pub fn replaying(log: MyEventLog) -> Self {
+        Self::with_runtime(Runtime::replaying(log))
+    }
+
+    fn with_runtime(runtime: Runtime) -> Self {
         let mut mpt = MyPThread::new();
         let mut mut1 = MyMutex::new();
         let mut mut2 = MyMutex::new();
@@ -46,38 +106,80 @@ impl SimulationController {
                                        vec![ Coord::new(23, 0),
                                                          Coord::new(24,9),
                                                          Coord::new(24,21)]);
+        let emergencies = Rc::new(RefCell::new(Vec::new()));
+        let notified = emergencies.clone();
+        runtime
+            .on_signal(EMERGENCY_SIGNAL, move |_, tid| notified.borrow_mut().push(tid))
+            .expect("EMERGENCY_SIGNAL inválida");
+        // kill -USR2 <pid> imprime en stderr qué hace cada hilo y quién tiene cada puente
+        runtime.dump_on_signal(MY_SIGUSR2);
+
+        // Un grupo por tipo de vehículo: una ola de carros no le quita CPU a los camiones que
+        // abastecen las plantas. Los camiones llevan el doble de share.
+        let mut vehicle_groups = HashMap::new();
+        for (kind, name, share) in [(CarE, "cars", 100), (AmbulanceE, "ambulances", 100), (ShipE, "ships", 100), (TruckE, "trucks", 200)] {
+            let group = runtime
+                .create_group(ROOT_GROUP, name, share, SchedulerType::RoundRobin)
+                .expect("la raíz siempre existe");
+            vehicle_groups.insert(kind, group);
+        }
+
+        // Las plantas piden sus insumos por un canal en vez de que se revise `requires` en cada frame
+        let (order_sender, orders) = MyChannel::unbounded(&runtime).split();
+        let supply_server = runtime.create_server(2, 10).expect("es el primer servidor");
+
         Self {
             traffic: traf,
             nuclear_plants: plants,
             map: city_map,
-            my_pthread: mpt,
+            clock: runtime.clock(),
+            runtime,
+            emergencies,
+            ambulances: BTreeMap::new(),
             with_traffic_bridge: Coord::new(1, 10),
+            vehicle_groups,
+            order_sender,
+            supply_orders: Rc::new(orders),
+            supply_server,
         }
     }
+    // Reemplaza el reloj de la ciudad y del runtime; conviene hacerlo antes del primer frame.
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        self.runtime.set_clock(clock.clone());
+        self.clock = clock;
+    }
+
     pub fn advance_time(&mut self, frames: u8) {
         for _ in 0..frames {
+            // Con un reloj de pared no hace nada: el tiempo ya pasó solo
+            self.clock.advance(FRAME_MS);
             {
                 let mut map = self.map.borrow_mut();
                 if let Some(b) = map.get_block_at(self.with_traffic_bridge).unwrap().as_any().downcast_mut::<BridgeBlock>() {
-                   b.advance_time(1);
+                   b.update(&*self.clock);
                 }
             }
-            let mut scheds : HashMap<Coord, Vec<SupplySpec>> = HashMap::new();
+            let mut went_critical = false;
             for coord in self.nuclear_plants.clone().iter() {
                 let mut map = self.map.borrow_mut();
                 if let Some(p) = map.get_block_at(*coord).unwrap().as_any().downcast_mut::<NuclearPlantBlock>() {
-                    let status = p.advance_time(1);
-                    if status != Boom && p.requires.len() > 0{
-                        if p.scheduled_kinds.is_empty() {
-                            scheds.insert(*coord, p.requires.clone());
-                        }
+                    let was = p.plant_status;
+                    let status = p.update(&*self.clock);
+                    went_critical |= was != Critical && status == Critical;
+                    if let Some(order) = p.take_order(*coord) {
+                        // Canal sin límite: nunca espera
+                        let _ = self.order_sender.try_send(order);
                     }
                 }
             }
-            if !scheds.is_empty() {
-                self.generate_trucks(scheds);
+            if !self.supply_orders.is_empty() {
+                self.dispatch_supply_orders();
+            }
+            if went_critical {
+                self.alert_ambulances();
             }
             self.traffic.advance_time();
+            self.retire_ambulances();
             self.check_traffic();
             self.generate_vehicles();
         }
@@ -86,63 +188,147 @@ impl SimulationController {
         if self.traffic.vehicles.len() > 70 {
             return;
         }
-        let mut rng = rand::rng();
-        if rng.random_bool(0.74) {
-            let option = rng.random_range(0..3);
-            let tid = self.initialize_a_thread();
+        if my_random_bool(0.74) {
+            let option = my_random_below(3);
             match option {
                 0 => {
+                    let tid = self.initialize_a_thread(CarE, |tid| format!("car-{tid}"));
                     self.traffic.new_car(tid);
                     return;
                 }
                 1 => {
+                    let tid = self.initialize_ambulance();
                     self.traffic.new_ambulance(tid);
                     return;
                 }
                 _ => {
+                    let tid = self.initialize_a_thread(ShipE, |tid| format!("ship-{tid}"));
                     self.traffic.new_ship(tid);
                 }
             }
         }
     }
-    fn generate_trucks(&mut self, scheds : HashMap<Coord, Vec<SupplySpec>>) {
+    // Un hilo aperiódico de tiempo real vacía el canal de pedidos y por cada uno salen sus camiones.
+    fn dispatch_supply_orders(&mut self) {
+        let orders = self.supply_orders.clone();
+        let dispatcher = self.runtime
+            .builder()
+            .name("supply-dispatcher")
+            .server(self.supply_server)
+            .spawn(move || orders.try_iter().collect::<Vec<SupplyOrder>>());
+        let mut scheds: BTreeMap<Coord, Vec<SupplySpec>> = BTreeMap::new();
+        for order in dispatcher.join() {
+            scheds.entry(order.plant).or_default().extend(order.specs);
+        }
+        self.generate_trucks(scheds);
+    }
+    fn generate_trucks(&mut self, scheds : BTreeMap<Coord, Vec<SupplySpec>>) {
         for sched in scheds {
             let specs = sched.1;
             for spec in specs {
-                let tid = self.initialize_a_thread();
-                self.traffic.new_truck(tid, sched.0, spec);
+                let plant = sched.0;
+                let tid = self.initialize_a_thread(TruckE, |_| format!("truck-plant({},{})", plant.x, plant.y));
+                self.traffic.new_truck(tid, plant, spec);
+            }
+        }
+    }
+    // `name` recibe el id del hilo nuevo y arma su nombre para trazas ("car-17", "truck-plant(14,19)").
+    fn initialize_a_thread(&mut self, kind: VehicleType, name: impl FnOnce(ThreadId) -> String) -> ThreadId {
+        // El handle se suelta de una vez: el hilo queda detached y el runtime lo limpia al terminar
+        self.spawn_vehicle_thread(kind, name, || println!("Im mooving")).id()
+    }
+    fn spawn_vehicle_thread(&mut self, kind: VehicleType, name: impl FnOnce(ThreadId) -> String, body: impl FnOnce() + 'static) -> JoinHandle<()> {
+        let thread = self.runtime
+            .builder()
+            .group(self.vehicle_groups[&kind])
+            .deadline(0)
+            .priority(30)
+            .spawn(body);
+        self.runtime.set_name(thread.id(), &name(thread.id()));
+        thread
+    }
+    // El hilo de la ambulancia espera en pause cada EMERGENCY_SIGNAL y termina cuando el vehículo
+    // sale de la ciudad (retire_ambulances).
+    pub(crate) fn initialize_ambulance(&mut self) -> ThreadId {
+        let on_duty = Rc::new(Cell::new(true));
+        let (runtime, duty) = (self.runtime.clone(), on_duty.clone());
+        let thread = self.spawn_vehicle_thread(AmbulanceE, |tid| format!("ambulance-{tid}"), move || {
+            while duty.get() && runtime.pause() {}
+        });
+        let tid = thread.id();
+        self.ambulances.insert(tid, AmbulanceDuty { on_duty, thread });
+        tid
+    }
+    // Las ambulancias que ya llegaron o se rindieron dejan su pause y terminan.
+    fn retire_ambulances(&mut self) {
+        let gone: Vec<ThreadId> = self.ambulances.keys().filter(|tid| !self.traffic.vehicles.contains_key(tid)).copied().collect();
+        for tid in gone {
+            let Some(duty) = self.ambulances.remove(&tid) else { continue };
+            duty.on_duty.set(false);
+            if let Err(err) = self.runtime.kill(tid, OFF_DUTY_SIGNAL) {
+                eprintln!("{} no recibió el fin de turno: {err}", self.thread_label(tid));
             }
+            duty.thread.join();
         }
     }
-    fn initialize_a_thread(&mut self) -> ThreadId {
-        extern "C" fn dummy_vehicle(arg: *mut AnyParam) -> *mut AnyParam {
-            println!("Im mooving");
-            arg as *mut AnyParam
+    // Nombre del hilo si todavía está en el runtime, si no solo el id.
+    fn thread_label(&self, tid: ThreadId) -> String {
+        self.runtime.name(tid).unwrap_or_else(|| tid.to_string())
+    }
+    // Manda EMERGENCY_SIGNAL a los hilos de las ambulancias y los despacha para que la atiendan; cada
+    // uno vuelve a su pause. Si el hilo ya no está, se avisa en stderr y la ambulancia recibe la
+    // emergencia igual.
+    pub(crate) fn alert_ambulances(&mut self) {
+        let ambulances: Vec<ThreadId> = self.traffic.vehicles.iter().filter(|(_, v)| *v.get_type() == AmbulanceE).map(|(tid, _)| *tid).collect();
+        for tid in ambulances {
+            if let Err(err) = self.runtime.kill(tid, EMERGENCY_SIGNAL) {
+                eprintln!("{} no recibió la emergencia: {err}", self.thread_label(tid));
+                self.emergencies.borrow_mut().push(tid);
+            }
         }
+        self.runtime.run();
 
-        let mut tid: ThreadId = 0;
-        let mut attr: MyThreadAttr = MyThreadAttr::new(0, 30);
-        let routine: MyTRoutine = dummy_vehicle;
-        let args: *mut AnyParam = tid as *mut AnyParam;
-        unsafe {
-            self.my_pthread.my_thread_create(&mut tid,
-                                             &mut attr,
-                                             routine,
-                                             args,
-                                             None);
+        let notified: Vec<ThreadId> = self.emergencies.borrow_mut().drain(..).collect();
+        for tid in notified {
+            if let Some(ambulance) = self.traffic.vehicles.get_mut(&tid) {
+                // Con la planta en Critical la ambulancia no se rinde: recupera toda su paciencia
+                let base = ambulance.base_mut();
+                base.patience = base.max_patience;
+            }
         }
-        tid
+    }
+    // Cierra la calle en `coord` y recalcula la ruta de todos los vehículos. False si no es una calle.
+    pub fn close_road(&mut self, coord: Coord) -> bool {
+        {
+            let mut map = self.map.borrow_mut();
+            let Some(road) = map.get_block_at(coord).and_then(|b| b.as_any().downcast_mut::<RoadBlock>()) else {
+                return false;
+            };
+            road.close();
+        }
+        self.recompute_paths();
+        true
+    }
+    // Cada vehículo busca de nuevo su ruta desde donde está, repartidos en hilos verdes. El que ya no
+    // tiene salida sigue con la ruta vieja y espera frente a la calle cerrada.
+    pub fn recompute_paths(&mut self) {
+        let map = self.map.borrow();
+        self.runtime.parallel_for(self.traffic.vehicles.values_mut(), PATH_THREADS, |vehicle| {
+            vehicle.base_mut().calculate_path(&map)
+        });
     }
     fn check_traffic(&mut self) {
         let frame = self.traffic.passed_frames;
         if let Some(fails) = self.traffic.fails.get(&frame) {
-            println!("Fails in frame {:?}: {:?} ", frame, fails);
+            let names: Vec<String> = fails.iter().map(|tid| self.thread_label(*tid)).collect();
+            println!("Fails in frame {:?}: {:?} ", frame, names);
         }
         if let Some(fails) = self.traffic.fails_by_type.keys().next() {
             println!("Fails by type {:?}: {:?} ", fails, self.traffic.fails_by_type.get(fails).unwrap());
         }
         if let Some(successes) = self.traffic.successes.get(&frame) {
-            println!("Successes in frame {:?}: {:?} ", frame, successes);
+            let names: Vec<String> = successes.iter().map(|tid| self.thread_label(*tid)).collect();
+            println!("Successes in frame {:?}: {:?} ", frame, names);
         }
     }
 }
\ No newline at end of file
@@ -1,8 +1,8 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 use glib::PropertyGet;
-use rand::prelude::IndexedRandom;
+use mypthreads::mythread::myreplay::my_random_below;
 use mypthreads::mythread::mythread::ThreadId;
 use crate::cityblock::block_type::BlockType::{Bridge, Dock, Road, Shops, Water, NuclearPlant};
 use crate::cityblock::bridge::bridge_permision_enum::EntryOutcome;
@@ -24,7 +24,8 @@ use crate::vehicle::vehicle_type::VehicleType;
 use crate::vehicle::vehicle_type::VehicleType::{ShipE, TruckE};
 
 pub struct TrafficHandler{
-    pub(crate) vehicles: HashMap<ThreadId, Box<dyn Vehicle>>,
+    // Ordenado por id para recorrerlos siempre igual (el replay depende del orden de los sorteos)
+    pub(crate) vehicles: BTreeMap<ThreadId, Box<dyn Vehicle>>,
     road_coords: Vec<Coord>,
     shops_coords: Vec<Coord>,
     water_spawns: Vec<Coord>,
@@ -44,7 +45,7 @@ impl TrafficHandler {
         let shops = map.borrow().find_blocks(Shops).clone();
         
         Self {
-            vehicles: HashMap::new(),
+            vehicles: BTreeMap::new(),
             road_coords: roads,
             shops_coords: shops,
             water_spawns,
@@ -151,7 +152,7 @@ impl TrafficHandler {
         self.vehicles.insert(tid, Box::new(ns));
     }
     fn any_coord(vec: Vec<Coord>) -> Coord {
-        vec.choose(&mut rand::rng()).cloned().unwrap()
+        vec[my_random_below(vec.len() as u64) as usize]
     }
 
     pub fn occupied_coords(&self) -> Vec<Coord> {
@@ -161,7 +162,7 @@ impl TrafficHandler {
     pub fn advance_time(&mut self) {
         //  Avanza en la carretera u prepara los puentes
         self.passed_frames += 1;
-        let mut planned_for_bridge: HashMap<Coord, Vec<ThreadId>> = HashMap::new();
+        let mut planned_for_bridge: BTreeMap<Coord, Vec<ThreadId>> = BTreeMap::new();
         for tid in self.vehicles.keys().cloned().collect::<Vec<_>>() {
             if let Some(v_type) = self.vehicles.get(&tid) {
                 match v_type.get_type() {
@@ -279,10 +280,10 @@ impl TrafficHandler {
                         if batype == Bridge {
                             let parcial = map.get_block_at(from).unwrap().as_any().downcast_mut::<BridgeBlock>().unwrap();
                             parcial.exit_bridge(v_type, v_patience);
-
+                        } else {
+                            let current_rbl = map.get_block_at(from).unwrap().as_any().downcast_mut::<RoadBlock>().unwrap();
+                            current_rbl.liberate_space();
                         }
-                        let current_rbl = map.get_block_at(from).unwrap().as_any().downcast_mut::<RoadBlock>().unwrap();
-                        current_rbl.liberate_space();
                         self.vehicles.remove(&tid);
 
                     }
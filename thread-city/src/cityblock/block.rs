@@ -22,7 +22,7 @@ impl BlockBase {
 }
 
 
-pub trait Block: Any {
+pub trait Block: Any + Send + Sync {
     fn get_id(&self) -> &usize;
     fn get_policy(&self) -> &TransportPolicy;
     fn get_type(&self) -> &BlockType;
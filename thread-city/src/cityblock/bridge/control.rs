@@ -1,5 +1,6 @@
-use rand::{rng, Rng};
+use mypthreads::mythread::myreplay::my_random_f64;
 use mypthreads::mythread::mythread::{ThreadId};
+use mypthreads::mythread::myclock::Clock;
 use crate::cityblock::bridge::traffic_light::TrafficLight;
 use crate::vehicle::vehicle::{PatienceLevel, Vehicle};
 use crate::vehicle::vehicle::PatienceLevel::{Critical, Maxed, Low, Starved};
@@ -35,12 +36,12 @@ impl Control {
             can_pass_boats: !has_yield,
         }
     }
-    pub fn advance_time(&mut self, frames: usize) {
+    pub fn update(&mut self, clock: &dyn Clock) {
         if let Some(out_t) = self.out_traffic_light.as_mut() {
-            out_t.advance_time(frames);
+            out_t.update(clock);
         }
         if let Some(int_t) = self.in_traffic_light.as_mut() {
-            int_t.advance_time(frames);
+            int_t.update(clock);
         }
     }
 
@@ -117,7 +118,7 @@ impl Control {
         match vehicle_type {
             AmbulanceE => true,
             _ => {
-                let chance = rand::rng().random_range(0.0..1.0);
+                let chance = my_random_f64();
                 match patience_level {
                     Maxed { .. } => chance < 0.6,
                     Low => chance < 0.8,
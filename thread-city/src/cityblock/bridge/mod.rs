@@ -3,6 +3,7 @@ mod traffic_light;
 pub mod bridge_permision_enum;
 
 use std::any::Any;
+use mypthreads::mythread::myclock::Clock;
 use mypthreads::mythread::mymutex::MyMutex;
 use mypthreads::mythread::mypthread::MyPThread;
 use crate::cityblock::Block;
@@ -39,6 +40,8 @@ impl Block for BridgeBlock {
 impl BridgeBlock {
     pub fn new(id: usize, control: Control, bridge_mutex: MyMutex) -> Self {
         let policy : TransportPolicy = if control.can_pass_boats { AnyVehicle } else { Car };
+        // Así sale en el dump del runtime (thread-city top)
+        bridge_mutex.set_name(&format!("bridge-{id}"));
         Self {
             base: BlockBase::new(id, policy, Bridge),
             control,
@@ -81,7 +84,7 @@ impl BridgeBlock {
     pub fn return_mutex(&mut self) -> Option<MyMutex> {
         self.mutex.take()
     }
-    pub fn advance_time(&mut self, frames: usize) {
-        self.control.advance_time(frames);
+    pub fn update(&mut self, clock: &dyn Clock) {
+        self.control.update(clock);
     }
 }
\ No newline at end of file
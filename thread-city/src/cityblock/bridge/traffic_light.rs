@@ -1,7 +1,10 @@
+use mypthreads::mythread::myclock::Clock;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TrafficLight {
     pub(crate) in_red: bool,
-    pub(crate) time_passed_ms: usize,
+    // Hora del reloj de la ciudad del último cambio de luz
+    pub(crate) since_ms: usize,
     pub(crate) update_interval_ms: usize,
 }
 
@@ -9,7 +12,7 @@ impl TrafficLight {
     pub fn new(update_interval_ms: usize) -> TrafficLight {
         Self {
             in_red: false,
-            time_passed_ms: 0,
+            since_ms: 0,
             update_interval_ms
         }
     }
@@ -17,10 +20,13 @@ impl TrafficLight {
     pub fn can_pass(&self) -> bool {
         !self.in_red
     }
-    pub fn advance_time(&mut self, time_passed: usize) {
-        self.time_passed_ms += time_passed;
-        if self.time_passed_ms >= self.update_interval_ms {
-            self.time_passed_ms -= self.update_interval_ms;
+    // Cambia de luz cada update_interval_ms del reloj. Si el reloj saltó (de pared o escalado)
+    // se pone al día con todos los cambios que se perdió.
+    pub fn update(&mut self, clock: &dyn Clock) {
+        let interval = self.update_interval_ms.max(1);
+        let now = clock.now_ms();
+        while now.saturating_sub(self.since_ms) >= interval {
+            self.since_ms += interval;
             self.in_red = !self.in_red;
         }
     }
@@ -1,4 +1,5 @@
 use std::any::Any;
+use mypthreads::mythread::myclock::Clock;
 use crate::city::supply_kind::SupplyKind;
 use crate::cityblock::{Block, BlockBase};
 use crate::cityblock::block_type::BlockType;
@@ -6,22 +7,28 @@ use crate::cityblock::block_type::BlockType::NuclearPlant;
 use crate::cityblock::coord::Coord;
 use crate::cityblock::nuclearplant::plant_status::PlantStatus;
 use crate::cityblock::nuclearplant::plant_status::PlantStatus::{Ok, AtRisk, Critical, Boom};
+use crate::cityblock::nuclearplant::supply_order::SupplyOrder;
 use crate::cityblock::nuclearplant::supply_spec::SupplySpec;
 use crate::cityblock::transport_policy::TransportPolicy;
 use crate::cityblock::transport_policy::TransportPolicy::NoVehicles;
 use crate::vehicle::cargotruck::CargoTruck;
 
 pub mod plant_status;
+pub mod supply_order;
 pub mod supply_spec;
 
 pub struct NuclearPlantBlock {
     pub(crate) base: BlockBase,
     pub(crate) plant_status: PlantStatus,
-    pub(crate) time_passed_ms: usize,
+    // Hora del reloj de la ciudad de la última transición
+    pub(crate) since_ms: usize,
     pub(crate) dead_line_policy: usize,
     pub(crate) update_interval_ms: usize,
     pub(crate) requires: Vec<SupplySpec>,
     pub(crate) scheduled_kinds: Vec<SupplyKind>,
+    // Pedido armado en la última transición; SimulationController lo manda por su canal (los
+    // bloques son Send + Sync, así que la punta del canal no puede vivir aquí)
+    pub(crate) outbox: Vec<SupplySpec>,
 }
 
 impl Block for NuclearPlantBlock {
@@ -46,35 +53,50 @@ impl NuclearPlantBlock {
             plant_status: Ok,
             dead_line_policy,
             requires: Vec::new(),
-            time_passed_ms: 0,
+            since_ms: 0,
             update_interval_ms,
             scheduled_kinds: Vec::new(),
+            outbox: Vec::new(),
         }
     }
 
-    // Avanza el "reloj". Solo decide si hay transición y la aplica.
-    // Devuelve siempre None (firma preservada para compatibilidad).
-    pub fn advance_time(&mut self, time_passed: usize) -> PlantStatus {
-        if self.plant_status == Boom { return Boom; }
-
-        self.time_passed_ms += time_passed;
+    // Pedido pendiente de mandar a `plant` (la posición de esta planta), si hay.
+    pub fn take_order(&mut self, plant: Coord) -> Option<SupplyOrder> {
+        if self.outbox.is_empty() {
+            return None;
+        }
+        Some(SupplyOrder { plant, specs: std::mem::take(&mut self.outbox) })
+    }
 
+    // Pide lo que falta y todavía no está pedido.
+    fn post_order(&mut self) {
+        for req in &self.requires {
+            if !self.scheduled_kinds.contains(&req.kind) {
+                self.scheduled_kinds.push(req.kind);
+                self.outbox.push(*req);
+            }
+        }
+    }
 
-        if self.time_passed_ms >= self.update_interval_ms {
-            self.time_passed_ms -= self.update_interval_ms;
+    // Aplica una transición por cada update_interval_ms que pasó en `clock` desde la última.
+    pub fn update(&mut self, clock: &dyn Clock) -> PlantStatus {
+        let interval = self.update_interval_ms.max(1);
+        let now = clock.now_ms();
+        while self.plant_status != Boom && now.saturating_sub(self.since_ms) >= interval {
+            self.since_ms += interval;
 
             let next = self.compute_next_status();
-            self.apply_transition(next);
+            self.apply_transition(next, self.since_ms);
         }
         self.plant_status
     }
 
     // Pone los 2 requerimientos por defecto al entrar en AtRisk.
-    fn enqueue_default_requirements(&mut self) {
+    fn enqueue_default_requirements(&mut self, now: usize) {
         let dl = self.dead_line_policy;
         self.requires.clear();
-        self.requires.push(SupplySpec { kind: SupplyKind::NuclearMaterial, dead_line: dl, time_passed_ms: 0 });
-        self.requires.push(SupplySpec { kind: SupplyKind::Water,            dead_line: dl, time_passed_ms: 0 });
+        self.requires.push(SupplySpec::new(SupplyKind::NuclearMaterial, dl, now));
+        self.requires.push(SupplySpec::new(SupplyKind::Water,           dl, now));
     }
 
     // Llamar cuando un camión llega y entrega.
@@ -100,7 +122,7 @@ impl NuclearPlantBlock {
         }
     }
 
-    fn apply_transition(&mut self, next: PlantStatus) {
+    fn apply_transition(&mut self, next: PlantStatus, now: usize) {
         let prev = self.plant_status;
         if prev == next { return; }
 
@@ -110,16 +132,20 @@ impl NuclearPlantBlock {
             AtRisk => {
                 // Solo creamos pedidos al entrar a AtRisk si aún no hay
                 if self.requires.is_empty() {
-                    self.enqueue_default_requirements();
+                    self.enqueue_default_requirements(now);
                 }
+                self.post_order();
             }
             Critical => {
-
+                // Si algún camión no llegó se vuelve a pedir lo que falta
+                self.scheduled_kinds.clear();
+                self.post_order();
             }
             Boom => {
                 // kaboom
                 self.requires.clear();
                 self.scheduled_kinds.clear();
+                self.outbox.clear();
                     self.dead_line_policy = 0;
                     self.update_interval_ms = 0;
             }
@@ -0,0 +1,9 @@
+use crate::cityblock::coord::Coord;
+use crate::cityblock::nuclearplant::supply_spec::SupplySpec;
+
+// Pedido de insumos que una planta manda por el canal de SimulationController.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SupplyOrder {
+    pub(crate) plant: Coord,
+    pub(crate) specs: Vec<SupplySpec>,
+}
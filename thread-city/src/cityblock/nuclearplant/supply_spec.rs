@@ -1,8 +1,21 @@
+use mypthreads::mythread::myclock::Clock;
 use crate::city::supply_kind::SupplyKind;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SupplySpec {
     pub(crate) kind: SupplyKind,
     pub(crate) dead_line: usize,
-    pub(crate) time_passed_ms: usize
+    // Hora del reloj de la ciudad en que la planta lo pidió
+    pub(crate) requested_ms: usize,
+}
+
+impl SupplySpec {
+    pub fn new(kind: SupplyKind, dead_line: usize, requested_ms: usize) -> Self {
+        Self { kind, dead_line, requested_ms }
+    }
+
+    // Tiempo que lleva pedido según `clock`.
+    pub fn elapsed_ms(&self, clock: &dyn Clock) -> usize {
+        clock.now_ms().saturating_sub(self.requested_ms)
+    }
 }
\ No newline at end of file
@@ -3,7 +3,7 @@ use crate::cityblock::{Block, BlockBase};
 use crate::cityblock::block_type::BlockType;
 use crate::cityblock::block_type::BlockType::Road;
 use crate::cityblock::transport_policy::TransportPolicy;
-use crate::cityblock::transport_policy::TransportPolicy::Car;
+use crate::cityblock::transport_policy::TransportPolicy::{Car, NoVehicles};
 
 pub struct RoadBlock {
     pub(crate) base: BlockBase,
@@ -46,4 +46,8 @@ impl RoadBlock {
     pub fn liberate_space(&mut self) {
         self.space += 1;
     }
+    // Calle cerrada: ninguna ruta nueva pasa por aquí
+    pub fn close(&mut self) {
+        self.base.policy = NoVehicles;
+    }
 }
\ No newline at end of file
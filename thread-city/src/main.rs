@@ -9,6 +9,51 @@ mod city;
 mod GUI;
 
 fn main() {
+    // thread-city bench-paths [vehiculos] [max_workers]: benchmark de pathfinding en el runtime M:N
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("bench-paths") {
+        let vehicles = args.get(2).and_then(|v| v.parse().ok()).unwrap_or(20_000);
+        let max_workers = args.get(3).and_then(|v| v.parse().ok())
+            .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
+        let counts: Vec<usize> = (1..=max_workers).collect();
+        city::path_bench::print_report(&city::path_bench::run(vehicles, &counts));
+        return;
+    }
+    // thread-city record <archivo> <semilla> <frames> / thread-city replay <archivo> <frames>:
+    // corren la ciudad sin GUI; la semilla de una corrida con GUI sale impresa al arrancar
+    if args.get(1).map(String::as_str) == Some("record") {
+        let (Some(path), Some(seed), Some(frames)) = (args.get(2), args.get(3).and_then(|v| v.parse().ok()), args.get(4).and_then(|v| v.parse().ok())) else {
+            eprintln!("uso: thread-city record <archivo> <semilla> <frames>");
+            return;
+        };
+        match city::replay::record(path, seed, frames) {
+            Ok(log) => println!("{} eventos grabados en {path}", log.events.len()),
+            Err(e) => eprintln!("no se pudo grabar {path}: {e}"),
+        }
+        return;
+    }
+    // thread-city top [semilla] [frames] [refresh_ms]: corre la ciudad sin GUI y muestra los hilos,
+    // los puentes y los vehículos en cada frame, como top
+    if args.get(1).map(String::as_str) == Some("top") {
+        let seed = args.get(2).and_then(|v| v.parse().ok()).unwrap_or(42);
+        let frames = args.get(3).and_then(|v| v.parse().ok()).unwrap_or(200);
+        let refresh = args.get(4).and_then(|v| v.parse().ok()).unwrap_or(250);
+        city::inspector::run_top(seed, frames, Duration::from_millis(refresh));
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("replay") {
+        let (Some(path), Some(frames)) = (args.get(2), args.get(3).and_then(|v| v.parse().ok())) else {
+            eprintln!("uso: thread-city replay <archivo> <frames>");
+            return;
+        };
+        match city::replay::replay(path, frames) {
+            Ok(None) => println!("replay idéntico a {path}"),
+            Ok(Some(at)) => println!("el replay se separó de {path} en el evento {at}"),
+            Err(e) => eprintln!("no se pudo leer {path}: {e}"),
+        }
+        return;
+    }
+
     let app = Application::builder()
         .application_id("com.helberth.citygtk")
         .build();
@@ -1,5 +1,5 @@
 use std::any::Any;
-use rand::Rng;
+use mypthreads::mythread::myreplay::my_random_below;
 use mypthreads::mythread::mythread::ThreadId;
 use crate::cityblock::coord::Coord;
 use crate::cityblock::map::Map;
@@ -49,7 +49,7 @@ impl Vehicle for Ship {
             self.base.path_idx += 1;
             return Maxed {moved: true};
         }
-        self.base.patience = self.base.patience.saturating_sub(rand::rng().random_range(1..4));
+        self.base.patience = self.base.patience.saturating_sub(1 + my_random_below(3) as u8);
         self.calc_patience()
     }
 
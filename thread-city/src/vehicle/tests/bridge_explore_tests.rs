@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod bridge_exploration {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use mypthreads::mythread::mutexlockkind::MyMutexAttr;
+    use mypthreads::mythread::mymutex::MyMutex;
+    use mypthreads::mythread::mypthread::MyPThread;
+    use mypthreads::mythread::mythread::ThreadId;
+    use mypthreads::safe::{Explorer, Scenario};
+    use crate::cityblock::bridge::bridge_permision_enum::EntryOutcome::{GrantedFor, Occupied};
+    use crate::cityblock::bridge::control::Control;
+    use crate::cityblock::bridge::BridgeBlock;
+    use crate::cityblock::coord::Coord;
+    use crate::vehicle::car::Car;
+    use crate::vehicle::vehicle::PatienceLevel::Maxed;
+    use crate::vehicle::vehicle::Vehicle;
+    use crate::vehicle::vehicle_type::VehicleType::CarE;
+
+    fn bridge() -> BridgeBlock {
+        let mut mutex = MyMutex::new();
+        let mut attr = MyMutexAttr::new(0);
+        unsafe { MyPThread::new().my_mutex_init(&mut mutex, &mut attr) };
+        // Sin semáforos: la salida la decide un sorteo, así también se exploran los reintentos
+        BridgeBlock::new(0, Control::without_traffic(false), mutex)
+    }
+
+    // `cars` hilos piden el puente, lo cruzan cediendo el CPU a mitad de camino y salen.
+    // Cada punto de cesión es un punto donde el explorador puede cambiar de hilo.
+    fn crossing(sc: &mut Scenario, cars: usize) {
+        let rt = sc.runtime().clone();
+        let bridge = Rc::new(RefCell::new(bridge()));
+        let on_bridge: Rc<RefCell<Vec<ThreadId>>> = Rc::new(RefCell::new(Vec::new()));
+
+        for _ in 0..cars {
+            let (r, b, on) = (rt.clone(), bridge.clone(), on_bridge.clone());
+            sc.spawn(move || {
+                let me = r.current().unwrap();
+                let mut car = Car::new(Coord::new(0, 0), Coord::new(0, 0));
+                car.base.thread_id = Some(me);
+                let car: Box<dyn Vehicle> = Box::new(car);
+
+                loop {
+                    match b.borrow_mut().request_entry(vec![&car]) {
+                        GrantedFor { tid } => {
+                            assert_eq!(tid, me);
+                            on.borrow_mut().push(me);
+                            break;
+                        }
+                        Occupied => {}
+                    }
+                    r.yield_now();
+                }
+                r.yield_now();
+                while !b.borrow_mut().exit_bridge(CarE, Maxed { moved: false }) {
+                    r.yield_now();
+                }
+                on.borrow_mut().retain(|&t| t != me);
+            });
+        }
+
+        sc.invariant(move || match on_bridge.borrow().as_slice() {
+            [_, _, ..] => Err(format!("varios carros en el puente: {:?}", on_bridge.borrow())),
+            _ => Ok(()),
+        });
+    }
+
+    #[test]
+    fn bridge_is_exclusive_under_every_order() {
+        let report = Explorer::dfs(3000).max_steps(200).run(|sc| crossing(sc, 2)).unwrap_or_else(|f| {
+            panic!("{} (corrida {}):\n{}", f.message, f.schedule, f.trace.to_text())
+        });
+        assert!(report.exhausted, "{report:?}");
+    }
+
+    #[test]
+    fn bridge_is_exclusive_under_pct_sampling() {
+        let report = Explorer::pct(300, 3).seed(17).max_steps(400).run(|sc| crossing(sc, 3)).unwrap_or_else(|f| {
+            panic!("{} (corrida {}):\n{}", f.message, f.schedule, f.trace.to_text())
+        });
+        assert_eq!(report.schedules, 300);
+    }
+}
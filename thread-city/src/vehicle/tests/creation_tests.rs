@@ -84,7 +84,7 @@ mod create_vehicle {
     #[test]
     fn test_create_truck() {
         let tid: ThreadId = 0;
-        let supply = SupplySpec { kind: NuclearMaterial, dead_line: 20, time_passed_ms: 0};
+        let supply = SupplySpec::new(NuclearMaterial, 20, 0);
         let mut brrrum = CargoTruck::new(Coord::new(0, 0), Coord::new(2, 0), supply);
 
         let mut custom = Vec::new();
@@ -196,4 +196,51 @@ mod complex_routing {
         assert!(boat.base.path.is_some());
         assert_eq!(expected, boat.base.path.unwrap());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parallel_paths_match_single_worker() {
+        // Las mismas rutas calculadas en 1 y 3 workers del runtime M:N dan el mismo resultado
+        let results = crate::city::path_bench::run(300, &[1, 3]);
+        assert_eq!(results.len(), 2);
+        assert!(results[0].checksum > 0);
+        assert_eq!(results[0].checksum, results[1].checksum);
+    }
+
+    #[test]
+    fn test_close_road_reroutes_vehicles() {
+        use crate::city::simulation_controller::SimulationController;
+        use crate::cityblock::block_type::BlockType::Road;
+        use crate::vehicle::vehicle_type::VehicleType::ShipE;
+
+        let mut sim = SimulationController::with_seed(7);
+        sim.advance_time(10);
+
+        // La siguiente calle (no el destino) de algún carro que todavía va en camino
+        let closed = sim.traffic.vehicles.values()
+            .filter(|v| *v.get_type() != ShipE)
+            .find_map(|v| {
+                let base = v.base();
+                let path = base.path.as_ref()?;
+                path.get(base.path_idx..path.len() - 1)?.iter().copied()
+                    .find(|c| *c != base.current_position && sim.map.borrow().block_type_at(*c) == Some(Road))
+            })
+            .expect("ningún carro en camino");
+        assert!(sim.close_road(closed));
+
+        let map = sim.map.borrow();
+        for v in sim.traffic.vehicles.values() {
+            let Some(path) = v.base().path.as_ref() else { continue };
+            if path.contains(&closed) {
+                // Solo conserva la ruta vieja quien ya no tiene otra salida
+                let mut probe = Car::new(v.current(), v.base().destination);
+                probe.base.vehicle_type = *v.get_type();
+                probe.base.calculate_path(&map);
+                assert!(probe.base.path.is_none(), "{:?} sigue pasando por {closed:?}", v.base().thread_id);
+            } else {
+                assert_eq!(path[0], v.current(), "la ruta nueva sale de donde está");
+            }
+        }
+        drop(map);
+        assert!(!sim.close_road(sim.nuclear_plants[0]), "solo se cierran calles");
+    }
+}
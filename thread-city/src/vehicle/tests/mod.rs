@@ -1,4 +1,6 @@
 mod creation_tests;
 mod map_tests;
 mod traffic_tests;
-mod nuclear_plant_test;
\ No newline at end of file
+mod nuclear_plant_test;
+mod replay_tests;
+mod bridge_explore_tests;
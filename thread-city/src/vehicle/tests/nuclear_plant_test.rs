@@ -38,17 +38,20 @@ mod nuclear_plant_tests {
 
     use crate::city::supply_kind::SupplyKind;
     use crate::cityblock::nuclearplant::plant_status::PlantStatus;
+    use mypthreads::mythread::myclock::{Clock, VirtualClock};
     use crate::cityblock::nuclearplant::NuclearPlantBlock;
+    use crate::cityblock::coord::Coord;
     use crate::vehicle::cargotruck::CargoTruck;
     use crate::vehicle::vehicle::{Vehicle, MoveIntent};
 
     // Helpers del usuario (ajusta el path si están en otro módulo)
     use crate::vehicle::tests::traffic_tests::c;
 
-    // Pequeño helper para avanzar N frames (cada frame = advance_time(1))
-    fn advance_frames(plant: &mut NuclearPlantBlock, frames: usize) {
+    // Pequeño helper para avanzar N frames (cada frame = 1 ms del reloj virtual)
+    fn advance_frames(plant: &mut NuclearPlantBlock, clock: &VirtualClock, frames: usize) {
         for _ in 0..frames {
-            let _ = plant.advance_time(1);
+            clock.advance(1);
+            let _ = plant.update(clock);
         }
     }
 
@@ -56,32 +59,34 @@ mod nuclear_plant_tests {
     #[test]
     fn plant_advances_state_every_interval() {
         let mut plant = NuclearPlantBlock::new(/*id*/ 1, /*deadline*/ 100, /*update_interval_ms*/ 30);
+        let clock = VirtualClock::new();
 
         assert_eq!(plant.plant_status, PlantStatus::Ok);
 
         // Ok -> AtRisk
-        advance_frames(&mut plant, 30);
+        advance_frames(&mut plant, &clock, 30);
         assert_eq!(plant.plant_status, PlantStatus::AtRisk);
 
         // AtRisk -> Critical
-        advance_frames(&mut plant, 30);
+        advance_frames(&mut plant, &clock, 30);
         assert_eq!(plant.plant_status, PlantStatus::Critical);
 
         // Critical -> Boom
-        advance_frames(&mut plant, 30);
+        advance_frames(&mut plant, &clock, 30);
         assert_eq!(plant.plant_status, PlantStatus::Boom);
 
         // En Boom permanece inerte
-        advance_frames(&mut plant, 300);
+        advance_frames(&mut plant, &clock, 300);
         assert_eq!(plant.plant_status, PlantStatus::Boom);
     }
 
     #[test]
     fn plant_enqueues_requirements_when_entering_at_risk_once() {
         let mut plant = NuclearPlantBlock::new(1, 100, 30);
+        let clock = VirtualClock::new();
 
         // Entra a AtRisk
-        advance_frames(&mut plant, 30);
+        advance_frames(&mut plant, &clock, 30);
         assert_eq!(plant.plant_status, PlantStatus::AtRisk);
 
         // Debe haber 2 requerimientos: NuclearMaterial y Water
@@ -90,27 +95,63 @@ mod nuclear_plant_tests {
         assert!(plant.requires.iter().any(|r| r.kind == SupplyKind::Water));
 
         // No debe duplicar requerimientos en frames subsiguientes
-        advance_frames(&mut plant, 10);
+        advance_frames(&mut plant, &clock, 10);
         assert_eq!(plant.requires.len(), 2);
     }
 
     #[test]
     fn reaching_boom_clears_requirements_and_scheduled() {
         let mut plant = NuclearPlantBlock::new(1, 100, 30);
+        let clock = VirtualClock::new();
 
         // Ok -> AtRisk (crea requerimientos)
-        advance_frames(&mut plant, 30);
+        advance_frames(&mut plant, &clock, 30);
         assert_eq!(plant.requires.len(), 2);
 
         // AtRisk -> Critical
-        advance_frames(&mut plant, 30);
+        advance_frames(&mut plant, &clock, 30);
         assert_eq!(plant.plant_status, PlantStatus::Critical);
 
         // Critical -> Boom (aquí se debe limpiar todo)
-        advance_frames(&mut plant, 30);
+        advance_frames(&mut plant, &clock, 30);
         assert_eq!(plant.plant_status, PlantStatus::Boom);
         assert!(plant.requires.is_empty(), "Al llegar a Boom se limpian los requerimientos");
         assert!(plant.scheduled_kinds.is_empty(), "Al llegar a Boom se limpia la lista de programados");
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn plant_posts_one_order_per_transition() {
+        let mut plant = NuclearPlantBlock::new(1, 100, 30);
+        let clock = VirtualClock::new();
+        let here = Coord::new(3, 4);
+        assert!(plant.take_order(here).is_none());
+
+        // Al entrar a AtRisk pide los dos insumos, una sola vez
+        advance_frames(&mut plant, &clock, 30);
+        let order = plant.take_order(here).expect("pedido al entrar a AtRisk");
+        assert_eq!(order.plant, here);
+        assert_eq!(order.specs.len(), 2);
+        advance_frames(&mut plant, &clock, 10);
+        assert!(plant.take_order(here).is_none());
+
+        // Llegó el agua pero no el material: en Critical se vuelve a pedir solo lo que falta
+        plant.requires.retain(|r| r.kind != SupplyKind::Water);
+        advance_frames(&mut plant, &clock, 20);
+        assert_eq!(plant.plant_status, PlantStatus::Critical);
+        let order = plant.take_order(here).expect("pedido al entrar a Critical");
+        assert_eq!(order.specs.iter().map(|s| s.kind).collect::<Vec<_>>(), vec![SupplyKind::NuclearMaterial]);
+    }
+
+    #[test]
+    fn plant_catches_up_when_clock_jumps() {
+        let mut plant = NuclearPlantBlock::new(1, 100, 30);
+        let clock = VirtualClock::new();
+
+        // Un reloj escalado o de pared puede saltar varios intervalos entre dos frames
+        clock.advance(65);
+        assert_eq!(plant.update(&clock), PlantStatus::Critical);
+        // Los insumos se pidieron al entrar a AtRisk, en el ms 30
+        assert!(plant.requires.iter().all(|r| r.elapsed_ms(&clock) == 35));
+    }
+
+}
@@ -0,0 +1,72 @@
+use mypthreads::mythread::myreplay::{my_replay_diverged, my_replay_log};
+use crate::city::simulation_controller::SimulationController;
+use crate::cityblock::coord::Coord;
+use mypthreads::mythread::mythread::ThreadId;
+
+fn snapshot(sim: &SimulationController) -> Vec<(ThreadId, Coord, u8)> {
+    sim.traffic.vehicles
+        .iter()
+        .map(|(tid, v)| (*tid, v.base().current_position, v.base().patience))
+        .collect()
+}
+
+fn run(mut sim: SimulationController, frames: usize) -> Vec<(ThreadId, Coord, u8)> {
+    for _ in 0..frames {
+        sim.advance_time(1);
+    }
+    snapshot(&sim)
+}
+
+#[test]
+fn replay_reproduces_city_run() {
+    let recorded = run(SimulationController::with_seed(2024), 40);
+    let log = my_replay_log();
+    assert!(!recorded.is_empty());
+    assert!(!log.events.is_empty());
+
+    assert_eq!(run(SimulationController::with_seed(2024), 40), recorded, "misma semilla, misma ciudad");
+
+    assert_eq!(run(SimulationController::replaying(log), 40), recorded);
+    assert_eq!(my_replay_diverged(), None);
+}
+
+#[test]
+fn city_and_runtime_share_the_clock() {
+    use std::sync::Arc;
+    use mypthreads::mythread::myclock::{ScaledClock, VirtualClock};
+
+    let mut sim = SimulationController::with_seed(3);
+    sim.advance_time(4);
+    assert_eq!(sim.runtime.time_ms(), 4);
+
+    // Diez veces más rápido: cada frame son 10 ms para semáforos, plantas y runtime
+    sim.set_clock(Arc::new(ScaledClock::new(Arc::new(VirtualClock::new()), 10.0)));
+    sim.advance_time(3);
+    assert_eq!(sim.runtime.time_ms(), 30);
+}
+
+#[test]
+fn ambulance_threads_outlive_emergencies_until_they_leave() {
+    use mypthreads::mythread::thread_state::ThreadState;
+
+    let mut sim = SimulationController::with_seed(5);
+    let tid = sim.initialize_ambulance();
+    sim.traffic.new_ambulance(tid);
+    assert!(sim.traffic.vehicles.contains_key(&tid), "la ambulancia entró a la ciudad");
+    sim.runtime.run();
+
+    for _ in 0..3 {
+        let ambulance = sim.traffic.vehicles.get_mut(&tid).unwrap();
+        ambulance.base_mut().patience = 0;
+        sim.alert_ambulances();
+        let base = sim.traffic.vehicles[&tid].base();
+        assert_eq!(base.patience, base.max_patience, "cada emergencia le llega a su hilo");
+        assert_eq!(sim.runtime.state(tid), Some(ThreadState::Blocked), "el hilo sigue esperando la siguiente");
+    }
+
+    // Al salir de la ciudad su hilo termina
+    sim.traffic.vehicles.remove(&tid);
+    sim.advance_time(1);
+    assert!(!sim.ambulances.contains_key(&tid));
+    assert_eq!(sim.runtime.state(tid), None);
+}